@@ -0,0 +1,28 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use otel_broccoli::augmentation::generate_datapoints;
+use otel_broccoli::config::Config;
+
+/// the same entries / duration (and seed) for every model so the results are comparable.
+fn bench_config(model: &str) -> Config {
+    let mut cfg = Config::new();
+    cfg.set_distribution_by(Some(model.to_string()));
+    cfg.set_number_of_entries(Some(1_000_000));
+    cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+    cfg.set_use_now_as_timestamp(Some(false));
+    cfg.set_generation_duration(Some("1h".to_string()));
+    cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+    cfg.set_seed(Some(42));
+    cfg
+}
+
+fn bench_distribution_models(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_datapoints");
+    for model in ["even", "early_fill", "sparse_fill"] {
+        let cfg = bench_config(model);
+        group.bench_function(model, |b| b.iter(|| generate_datapoints(&cfg).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_distribution_models);
+criterion_main!(benches);
@@ -1,8 +1,374 @@
-use crate::config::Config;
-use chrono::{DateTime, Duration, Utc};
-use rand::Rng;
+use crate::config::{Config, ConfigStep};
+use crate::templates::RowTemplates;
+use crate::transforms::TransformRegistry;
+use chrono::format::ParseErrorKind;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use getset::{CopyGetters, Getters};
+use rand::distr::uniform::{SampleRange, SampleUniform};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_pcg::Pcg64;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::fmt;
 
 const DEFAULT_SPARSE_FILL_ZONE_GENERATION_FACTOR: u32 = 3;
+const DEFAULT_SPARSE_FILL_MIN_ZONES: u32 = 3;
+const DEFAULT_SPARSE_FILL_MAX_ZONES: u32 = 6;
+
+const DEFAULT_BIMODAL_PEAK1: f64 = 0.25;
+const DEFAULT_BIMODAL_PEAK2: f64 = 0.75;
+const DEFAULT_BIMODAL_PEAK1_RATIO: f64 = 0.5;
+// width (standard deviation) of each bimodal hump, as a fraction of the generation window.
+const DEFAULT_BIMODAL_PEAK_WIDTH: f64 = 0.08;
+
+// safety limits against accidental huge runs (e.g. a typo in number_of_entries).
+const DEFAULT_MAX_ENTRIES: u64 = 100_000_000;
+// 1 slot per second; ~115 days
+const MAX_SLOTS: i64 = 10_000_000;
+
+// the default decay_lambda is derived so that the last slot is ~1% of the first one.
+const DEFAULT_DECAY_LAST_TO_FIRST_RATIO: f64 = 0.01;
+
+// the default magnitudes of the anomalies; a drop to zero and a 10x spike.
+const DEFAULT_DROP_MAGNITUDE: f64 = 1.0;
+const DEFAULT_SPIKE_MAGNITUDE: f64 = 10.0;
+
+// the default bounds of the gauge values (e.g. a CPU %).
+const DEFAULT_GAUGE_MIN: i16 = 0;
+const DEFAULT_GAUGE_MAX: i16 = 100;
+
+/// What the `rows_to_add` of a datapoint represents.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueMode {
+    /// the number of events (rows) of the slot; the default.
+    Count,
+    /// a gauge value (e.g. a CPU %) of the slot within [`gauge_min`, `gauge_max`];
+    /// the distribution model shapes the value curve.
+    Gauge,
+}
+
+impl ValueMode {
+    /// The configured `value_mode`; `count` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `value_mode` is neither `count` nor `gauge`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        ValueMode::parse(cfg.value_mode().as_deref().unwrap_or("count"))
+    }
+
+    pub fn parse(value_mode: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match value_mode.to_lowercase().as_str() {
+            "count" => Ok(ValueMode::Count),
+            "gauge" => Ok(ValueMode::Gauge),
+            _ => Err(format!(
+                "unknown value_mode [{}]; expecting \"count\" or \"gauge\"",
+                value_mode
+            )
+            .into()),
+        }
+    }
+}
+
+/// What a row (1 unit of `rows_to_add`) stands for; the distribution math is the same, only the
+/// interpretation and labeling (run report, metrics) differ.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Unit {
+    /// 1 event; the default.
+    #[default]
+    Events,
+    /// 1 byte; e.g. for throughput tests in bytes per second.
+    Bytes,
+}
+
+impl Unit {
+    /// The configured `unit`; `events` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `unit` is neither `events` nor `bytes`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        Unit::parse(cfg.unit().as_deref().unwrap_or("events"))
+    }
+
+    pub fn parse(unit: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match unit.to_lowercase().as_str() {
+            "events" => Ok(Unit::Events),
+            "bytes" => Ok(Unit::Bytes),
+            _ => Err(format!("unknown unit [{}]; expecting \"events\" or \"bytes\"", unit).into()),
+        }
+    }
+
+    /// the plural label of the unit's quantities; e.g. "10000 rows".
+    pub fn label(&self) -> &'static str {
+        match self {
+            Unit::Events => "rows",
+            Unit::Bytes => "bytes",
+        }
+    }
+}
+
+/// How the non-empty zones of the sparse_fill model are placed among the buckets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SparsePlacement {
+    /// random buckets; the default.
+    Random,
+    /// evenly spaced buckets (deterministic); consistent visual output.
+    Spread,
+}
+
+impl SparsePlacement {
+    /// The configured `sparse_placement`; `random` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `sparse_placement` is neither `random` nor `spread`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        match cfg.sparse_placement().as_deref() {
+            None => Ok(SparsePlacement::Random),
+            Some(placement) => match placement.to_lowercase().as_str() {
+                "random" => Ok(SparsePlacement::Random),
+                "spread" => Ok(SparsePlacement::Spread),
+                _ => Err(format!(
+                    "unknown sparse_placement [{}]; expecting \"random\" or \"spread\"",
+                    placement
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+/// How the even model rounds `number_of_entries / slots` into integer slot counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounding {
+    /// the integer division per slot; the whole remainder goes into the last slot. The default.
+    LastSlot,
+    /// the remainder goes 1 each into the first slots; no single slot is an outlier.
+    Spread,
+}
+
+impl Rounding {
+    /// The configured `rounding`; `last_slot` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `rounding` is neither `last_slot` nor `spread`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        match cfg.rounding().as_deref() {
+            None => Ok(Rounding::LastSlot),
+            Some(rounding) => match rounding.to_lowercase().as_str() {
+                "last_slot" => Ok(Rounding::LastSlot),
+                "spread" => Ok(Rounding::Spread),
+                _ => Err(format!(
+                    "unknown rounding [{}]; expecting \"last_slot\" or \"spread\"",
+                    rounding
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+/// The order of the generated datapoints.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Order {
+    /// oldest first. The default.
+    Asc,
+    /// newest first.
+    Desc,
+}
+
+impl Order {
+    /// The configured `order`; `asc` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `order` is neither `asc` nor `desc`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        match cfg.order().as_deref() {
+            None => Ok(Order::Asc),
+            Some(order) => match order.to_lowercase().as_str() {
+                "asc" => Ok(Order::Asc),
+                "desc" => Ok(Order::Desc),
+                _ => {
+                    Err(format!("unknown order [{}]; expecting \"asc\" or \"desc\"", order).into())
+                }
+            },
+        }
+    }
+}
+
+/// The kind of an injected anomaly (see `Anomaly`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnomalyKind {
+    /// the rows of the slot drop by `magnitude` (a fraction; 1 = to zero, the default).
+    Drop,
+    /// the rows of the slot are multiplied by `magnitude` (10 by default).
+    Spike,
+}
+
+impl AnomalyKind {
+    pub fn parse(kind: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match kind.to_lowercase().as_str() {
+            "drop" => Ok(AnomalyKind::Drop),
+            "spike" => Ok(AnomalyKind::Spike),
+            _ => Err(format!(
+                "unknown anomaly kind [{}]; expecting \"drop\" or \"spike\"",
+                kind
+            )
+            .into()),
+        }
+    }
+
+    /// the lowercase name; as configured and as flagged by the exporters.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnomalyKind::Drop => "drop",
+            AnomalyKind::Spike => "spike",
+        }
+    }
+}
+
+/// A labeled anomaly injected into the slot at the fractional position `at` (0..1) of the window,
+/// after the distribution model (and the transforms) filled the slots.
+///
+/// In the `count` value mode the total stays the same; a spike borrows its extra rows from the
+/// other slots and a drop donates its rows to them (both proportionally to their rows).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomaly {
+    pub at: f64,
+    pub kind: AnomalyKind,
+    pub magnitude: f64,
+}
+
+impl Anomaly {
+    /// The configured `anomalies`; none if not set.
+    ///
+    /// # Errors
+    ///
+    /// If an `at` is not within [0, 1], a `kind` is neither `drop` nor `spike`, or a `magnitude`
+    /// is out of range (a drop: within [0, 1]; a spike: >= 1).
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Vec<Self>, Box<dyn std::error::Error>> {
+        let mut anomalies = vec![];
+        for anomaly in cfg.anomalies().as_deref().unwrap_or_default() {
+            let at = *anomaly.at();
+            if !(0.0..=1.0).contains(&at) {
+                return Err(format!("anomaly at [{}] must be within [0, 1]", at).into());
+            }
+            let kind = AnomalyKind::parse(anomaly.kind())?;
+            let magnitude = match kind {
+                AnomalyKind::Drop => anomaly.magnitude().unwrap_or(DEFAULT_DROP_MAGNITUDE),
+                AnomalyKind::Spike => anomaly.magnitude().unwrap_or(DEFAULT_SPIKE_MAGNITUDE),
+            };
+            let valid = match kind {
+                AnomalyKind::Drop => (0.0..=1.0).contains(&magnitude),
+                AnomalyKind::Spike => magnitude.is_finite() && magnitude >= 1.0,
+            };
+            if !valid {
+                let range = match kind {
+                    AnomalyKind::Drop => "within [0, 1]",
+                    AnomalyKind::Spike => ">= 1",
+                };
+                return Err(format!(
+                    "{} anomaly magnitude must be {}, got [{}]",
+                    kind.name(),
+                    range,
+                    magnitude
+                )
+                .into());
+            }
+            anomalies.push(Anomaly {
+                at,
+                kind,
+                magnitude,
+            });
+        }
+        Ok(anomalies)
+    }
+}
+
+/// The random generator algorithm of the generation (see `seed_generation_rng_with`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RngAlgorithm {
+    /// rand's standard generator; the algorithm may change across rand versions. The default.
+    #[default]
+    Thread,
+    /// ChaCha8; reproducible across platforms (and versions) with a fixed seed.
+    ChaCha8,
+    /// Pcg64.
+    Pcg,
+}
+
+impl RngAlgorithm {
+    /// The configured `rng`; `thread` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `rng` is none of `thread`, `chacha8` and `pcg`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        match cfg.rng().as_deref() {
+            None => Ok(RngAlgorithm::Thread),
+            Some(rng) => match rng.to_lowercase().as_str() {
+                "thread" => Ok(RngAlgorithm::Thread),
+                "chacha8" => Ok(RngAlgorithm::ChaCha8),
+                "pcg" => Ok(RngAlgorithm::Pcg),
+                _ => Err(format!(
+                    "unknown rng [{}]; expecting \"thread\", \"chacha8\" or \"pcg\"",
+                    rng
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+/// A typed generation failure; boxed into the `Box<dyn Error>` of the generation functions,
+/// hence match on it with `downcast_ref::<GenerationError>()`.
+#[derive(Debug)]
+pub enum GenerationError {
+    /// `start_timestamp` could not be parsed with `timestamp_format`; the chrono error (the
+    /// `source`) tells an invalid format (`ParseErrorKind::BadFormat`) from a value not matching
+    /// the format (e.g. `ParseErrorKind::Invalid`).
+    TimestampParse {
+        value: String,
+        format: String,
+        source: chrono::ParseError,
+    },
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationError::TimestampParse {
+                value,
+                format,
+                source,
+            } => write!(
+                f,
+                "failed to parse start_timestamp [{}] with format [{}]: {}",
+                value, format, source
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GenerationError::TimestampParse { source, .. } => Some(source),
+        }
+    }
+}
 
 /// Generate a tuple of two DateTime values, `start_time` and `end_time`.
 ///
@@ -14,18 +380,19 @@ const DEFAULT_SPARSE_FILL_ZONE_GENERATION_FACTOR: u32 = 3;
 ///
 /// # Errors
 ///
-/// If `start_timestamp` cannot be parsed with `timestamp_format`, an error is
-/// returned. If `generation_duration` cannot be parsed, an error is returned.
+/// If `start_timestamp` cannot be parsed with `timestamp_format`, a
+/// `GenerationError::TimestampParse` is returned. If `generation_duration` cannot be parsed, an
+/// error is returned.
 ///
-fn generate_time_range(
+pub fn generate_time_range(
     cfg: &Config,
 ) -> Result<(DateTime<Utc>, DateTime<Utc>), Box<dyn std::error::Error>> {
     let mut start_time = Utc::now();
-    let mut end_time = Utc::now();
+    let mut end_time = start_time;
 
     if let Some(use_now) = cfg.use_now_as_timestamp() {
-        // not using NOW()
-        if !use_now {
+        // not using NOW(); also when start_timestamp is explicitly preferred over NOW()
+        if !use_now || cfg.prefer_start_timestamp().unwrap_or(false) {
             // get the `start_timestamp`
             // [lesson] if the start_timestamp is a valid dateTime format
             // start_time = cfg.start_timestamp().as_ref().unwrap().parse().unwrap();
@@ -39,49 +406,110 @@ fn generate_time_range(
             // .with_timezone(&Utc);
 
             // [lesson] might have issue on parsing if the format doesn't match with the timestamp value
-            let intermediate_start_time = DateTime::parse_from_str(
-                cfg.start_timestamp().as_ref().unwrap(),
-                cfg.timestamp_format().as_ref().unwrap(),
-            );
-            if intermediate_start_time.is_err() {
-                return Err(format!(
-                    "failed to parse start_timestamp [{}] with format [{}]: {}",
-                    cfg.start_timestamp().as_ref().unwrap(),
-                    cfg.timestamp_format().as_ref().unwrap(),
-                    intermediate_start_time.err().unwrap()
-                )
-                .into());
-            }
-            start_time = intermediate_start_time.unwrap().with_timezone(&Utc);
+            let start_timestamp = cfg.start_timestamp().as_ref().unwrap();
+            let timestamp_format = cfg.timestamp_format().as_ref().unwrap();
+            start_time =
+                parse_start_timestamp(start_timestamp, timestamp_format).map_err(|source| {
+                    GenerationError::TimestampParse {
+                        value: start_timestamp.clone(),
+                        format: timestamp_format.clone(),
+                        source,
+                    }
+                })?;
             // [lesson] DateTime has implemented the Copy trait
             // end_time = start_time.clone();
             end_time = start_time;
         }
     }
     // update the end_time with the value = generation_duration
-    if let Some(generation_duration) = cfg.generation_duration() {
+    if cfg.slot_count().is_some() {
+        // or slot_count * slot_width
+        let (duration_in_seconds, _) = generation_window_in_seconds(cfg)?;
+        end_time = start_time + Duration::seconds(duration_in_seconds);
+    } else if let Some(generation_duration) = cfg.generation_duration() {
         // throw the error to upper stack OR get the duration value
         let value_and_unit = parse_time_duration(generation_duration.clone())?;
         end_time = start_time + value_and_unit;
     }
+    check_time_range(start_time, end_time, cfg.single_instant().unwrap_or(false))?;
 
     Ok((start_time, end_time))
 }
 
+/// parse the `start_timestamp` with the `timestamp_format`; a date-only format (e.g. "%Y-%m-%d")
+/// has neither a time nor an offset, hence the date is parsed as midnight UTC. Likewise a format
+/// without an offset (e.g. "%Y-%m-%dT%H:%M:%S") parses the timestamp as UTC.
+pub(crate) fn parse_start_timestamp(
+    start_timestamp: &str,
+    timestamp_format: &str,
+) -> Result<DateTime<Utc>, chrono::ParseError> {
+    if is_date_only_format(timestamp_format) {
+        let date = NaiveDate::parse_from_str(start_timestamp, timestamp_format)?;
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    match DateTime::parse_from_str(start_timestamp, timestamp_format) {
+        Ok(start_time) => Ok(start_time.with_timezone(&Utc)),
+        // no offset parsed; the (naive) timestamp is taken as UTC
+        Err(e) if e.kind() == ParseErrorKind::NotEnough => {
+            Ok(NaiveDateTime::parse_from_str(start_timestamp, timestamp_format)?.and_utc())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// whether the strftime format has no time nor offset specifiers (e.g. "%Y-%m-%d", "%d/%m/%Y").
+fn is_date_only_format(timestamp_format: &str) -> bool {
+    // the time / offset / timestamp specifiers; the padding & precision modifiers are skipped over
+    const TIME_SPECIFIERS: &str = "HkIlMSTRrXcsfpPzZ+";
+    let mut chars = timestamp_format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        for specifier in chars.by_ref() {
+            match specifier {
+                '-' | '_' | '0'..='9' | '.' | ':' | '#' => continue,
+                '%' => break,
+                _ if TIME_SPECIFIERS.contains(specifier) => return false,
+                _ => break,
+            }
+        }
+    }
+    true
+}
+
+/// the generation window must not be empty (unless `single_instant`) nor inverted.
+fn check_time_range(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    single_instant: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if end_time < start_time || (end_time == start_time && !single_instant) {
+        return Err(format!(
+            "generation window is empty or inverted: [{}, {}]",
+            start_time, end_time
+        )
+        .into());
+    }
+    Ok(())
+}
+
 /// parse the time duration value and unit from the given string value.
-fn parse_time_duration_value_and_unit(value: String) -> Option<(i64, String)> {
+/// The value could be fractional (e.g. "1.5h"); whitespace around the value and in between the
+/// value and the unit is ignored (e.g. "10 m").
+fn parse_time_duration_value_and_unit(value: String) -> Option<(f64, String)> {
+    let value = value.trim();
     // find out which index is a non-numeric value
-    let idx = value.find(|c: char| !c.is_ascii_digit())?;
+    let idx = value.find(|c: char| !c.is_ascii_digit() && c != '.')?;
     let (num, unit) = value.split_at(idx);
-    let num: i64 = num.parse::<i64>().ok()?;
+    let num: f64 = num.parse::<f64>().ok()?;
 
-    Some((num, unit.to_string()))
+    Some((num, unit.trim_start().to_string()))
 }
 
-/// parse the time duration based on the given string value.
-/// For non supported value (invalid format etc) would return zero duration.
-fn parse_time_duration(value: String) -> Result<Duration, Box<dyn std::error::Error>> {
-    let parsed_value_and_unit = parse_time_duration_value_and_unit(value);
+/// parse the time duration based on the given string value (e.g. "10m", "1.5h").
+pub fn parse_time_duration(value: String) -> Result<Duration, Box<dyn std::error::Error>> {
+    let parsed_value_and_unit = parse_time_duration_value_and_unit(value.clone());
     if parsed_value_and_unit.is_none() {
         return Err("failed to parse time duration value and unit"
             .to_string()
@@ -89,242 +517,1953 @@ fn parse_time_duration(value: String) -> Result<Duration, Box<dyn std::error::Er
     }
 
     let (num, unit) = parsed_value_and_unit.unwrap();
-    match unit.as_str() {
-        "s" => Ok(Duration::seconds(num)),
-        "m" => Ok(Duration::minutes(num)),
-        "h" => Ok(Duration::hours(num)),
-        "d" => Ok(Duration::days(num)),
-        _ => {
-            // anything else is not supported and return zero duration...
-            // Err("invalid time duration unit".to_string().into()),
-            Ok(Duration::zero())
-        }
-    } // end - match
-}
+    let unit_in_nanos: i64 = match unit.as_str() {
+        "ms" => 1_000_000,
+        "s" => 1_000_000_000,
+        "m" => 60 * 1_000_000_000,
+        "h" => 60 * 60 * 1_000_000_000,
+        "d" => 24 * 60 * 60 * 1_000_000_000,
+        _ => return Err(format!("invalid time duration unit [{}]", unit).into()),
+    }; // end - match
 
-/// struct to hold the timestamp and the number of rows to add - acts as a DataPoint in the distribution.
-#[derive(Debug)]
-pub struct DataPoint {
-    timestamp: DateTime<Utc>,
-    rows_to_add: i16,
+    // a Duration holds up to i64::MAX nanoseconds (about 292 years)
+    let too_large = || format!("time duration [{}] is too large", value.trim()).into();
+    if num.fract() == 0.0 {
+        // integer values; exact
+        return (num as i64)
+            .checked_mul(unit_in_nanos)
+            .map(Duration::nanoseconds)
+            .ok_or_else(too_large);
+    }
+    let nanos = (num * unit_in_nanos as f64).round();
+    if nanos >= i64::MAX as f64 {
+        return Err(too_large());
+    }
+    Ok(Duration::nanoseconds(nanos as i64))
 }
 
-pub fn generate_datapoints(cfg: &Config) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
-    let mut datapoints: Vec<DataPoint> = Vec::new();
-    let (start_time, _) = generate_time_range(cfg)?;
-
-    // [lesson] also works ... cfg.generation_duration().as_ref().unwrap().clone()
-    let duration = parse_time_duration(cfg.generation_duration().as_deref().unwrap().to_string())?;
-    // duration in seconds is the unit of time for generating datapoints.
-    // Seconds granularity works in this case as though in production, events are created at microseconds or milliseconds level;
-    // however for graph plotting etc, the datapoints are usually re-grouped in a less granular unit such as seconds, minutes or days
-    // and thus would not make much difference to have a microsecond granularity or not.
-    //
-    // PS. you might view this as a limitation of the implementation.
-    let duration_in_seconds = duration.num_seconds();
+/// The inputs shared by every distribution model.
+///
+/// The `Config` is carried along for models having their own settings (e.g. bimodal's peaks).
+#[derive(Getters, CopyGetters)]
+pub struct GenerationContext<'a> {
+    #[getset(get = "pub")]
+    cfg: &'a Config,
 
-    let num_entries_to_generate = cfg.number_of_entries().as_ref().unwrap();
-    let model = cfg.distribution_by().as_deref().unwrap().to_lowercase();
-    match model.as_str() {
-        "even" => generate_datapoints_even(
-            start_time,
-            duration_in_seconds,
-            *num_entries_to_generate,
-            &mut datapoints,
-        )?,
-        "early_fill" => generate_datapoints_early_fill(
-            start_time,
-            duration_in_seconds,
-            *num_entries_to_generate,
-            &mut datapoints,
-        )?,
-        "sparse_fill" => generate_datapoints_sparse_fill(
+    #[getset(get_copy = "pub")]
+    start_time: DateTime<Utc>,
+
+    #[getset(get_copy = "pub")]
+    duration_in_seconds: i64,
+
+    #[getset(get_copy = "pub")]
+    num_entries_to_generate: u32,
+}
+
+impl<'a> GenerationContext<'a> {
+    pub fn new(
+        cfg: &'a Config,
+        start_time: DateTime<Utc>,
+        duration_in_seconds: i64,
+        num_entries_to_generate: u32,
+    ) -> Self {
+        GenerationContext {
+            cfg,
             start_time,
             duration_in_seconds,
-            *num_entries_to_generate,
-            &mut datapoints,
-        )?,
-        _ => {
-            return Err(format!("unknown distribution model [{}]", model)
-                .to_string()
-                .into())
+            num_entries_to_generate,
         }
     }
-    Ok(datapoints)
 }
 
-fn generate_datapoints_even(
-    start_time: DateTime<Utc>,
-    duration_in_seconds: i64,
-    num_entries_to_generate: u32,
-    datapoints: &mut Vec<DataPoint>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // approximately per datapoint interval should generate how many rows?
-    let per_datapoint_entries_to_generate = num_entries_to_generate as i64 / duration_in_seconds;
+/// Common synonyms of the distribution model names; (alias, canonical name).
+const MODEL_ALIASES: &[(&str, &str)] = &[("flat", "even"), ("uniform", "even")];
 
-    // first fill
-    let mut sum = 0;
-    let last_datapoint_index = duration_in_seconds - 1;
-    for i in 0..duration_in_seconds {
-        if i != last_datapoint_index {
-            datapoints.push(DataPoint {
-                timestamp: start_time + Duration::seconds(i),
-                rows_to_add: per_datapoint_entries_to_generate as i16,
-            });
-            sum += per_datapoint_entries_to_generate;
-        } else {
-            datapoints.push(DataPoint {
-                timestamp: start_time + Duration::seconds(i),
-                rows_to_add: num_entries_to_generate as i16 - sum as i16,
-            });
-        }
-    } // end - for duration_in_seconds loop
+/// The canonical (lower case) name of the distribution model; aliases (e.g. "flat") are
+/// resolved to the model they stand for (e.g. "even").
+pub fn canonical_model_name(name: &str) -> String {
+    let name = name.to_lowercase();
+    MODEL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, canonical)| canonical.to_string())
+        .unwrap_or(name)
+}
 
-    // second fill (random pick and assign)
-    // rounds 2/10 of the num_of_entries_to_generate, make sure a randomness is introduced in the distribution set.
-    let num_shuffles = (num_entries_to_generate as f32 * 0.2) as u32;
-    for _ in 0..num_shuffles {
-        let (first_slot, second_slot) = pick_2_random_datapoint(duration_in_seconds);
-        // update a random additive deducted from first_slot to second_slot
-        let first_slot_row_to_add = datapoints[first_slot as usize].rows_to_add;
-        tracing::trace!(
-            "first_slot={} vs second_slot={} - first_slot_in_usize {}, rows_to_add {}",
-            first_slot,
-            second_slot,
-            first_slot as usize,
-            first_slot_row_to_add
-        );
-        if first_slot_row_to_add == 1 {
-            continue;
-        }
-        let delta = rand::rng().random_range(1..first_slot_row_to_add);
-        datapoints[first_slot as usize].rows_to_add -= delta;
-        datapoints[second_slot as usize].rows_to_add += delta;
+/// A distribution model; decides how `num_entries_to_generate` rows are spread into datapoints.
+///
+/// Implement this trait and register it into a `DistributionModelRegistry` to plug in a custom model
+/// without editing the crate.
+pub trait DistributionModel {
+    /// one-line description of the model, used by the `--list-distributions` listing.
+    fn description(&self) -> &str {
+        "custom distribution model"
+    }
+
+    /// fill up `out` with the datapoints generated for the given context.
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// the total rows derived from the model's own settings when `number_of_entries` is not set
+    /// (e.g. the rates of the step model); none by default, i.e. `number_of_entries` is required.
+    fn derived_total(
+        &self,
+        _cfg: &Config,
+        _duration_in_seconds: i64,
+    ) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+        Ok(None)
     }
-    Ok(())
 }
 
-fn pick_2_random_datapoint(slots_length: i64) -> (i64, i64) {
-    // slots_length = duration_in_seconds
-    let first_slot = rand::rng().random_range(0..slots_length);
-    let mut second_slot = rand::rng().random_range(0..slots_length);
+struct EvenModel;
 
-    loop {
-        if second_slot != first_slot {
-            break;
-        }
-        second_slot = rand::rng().random_range(0..slots_length);
+impl DistributionModel for EvenModel {
+    fn description(&self) -> &str {
+        "every interval gets 0 or more entries; no huge gap of empty intervals"
+    }
+
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        generate_datapoints_even(ctx, out)
     }
-    (first_slot, second_slot)
 }
 
-fn generate_datapoints_early_fill(
-    start_time: DateTime<Utc>,
-    duration_in_seconds: i64,
-    num_entries_to_generate: u32,
-    datapoints: &mut Vec<DataPoint>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // loop through the slots
-    // assign a random rows_to_add value to the given slot
-    //  (remember the actual ceiling is the num_entries_to_generate; so a logical ceiling would be num_entries_to_generate * 1% per slot's rows_to_add')
-    // once the accumulated rows_to_add is greater than or equals to num_entries_to_generate, augmentation done and can't exit the allocation.
+struct EarlyFillModel;
 
-    let logical_ceiling = (num_entries_to_generate as f32 * 0.01) as u32;
-    let logical_floor: u32 = 1;
+impl DistributionModel for EarlyFillModel {
+    fn description(&self) -> &str {
+        "fill the entries asap into the early intervals; later intervals stay empty"
+    }
 
-    let mut sum = 0;
-    // [deprecated] used to create `empty` datapoints, but not make sense for most use case, hence simply drop it.
-    // let mut done_allocation = false;
-    // let mut early_log = false;
-    for i in 0..duration_in_seconds {
-        let mut rows_to_add = rand::rng().random_range(logical_floor..=logical_ceiling);
-        // guard check
-        if sum + rows_to_add > num_entries_to_generate {
-            rows_to_add = num_entries_to_generate - sum;
-            sum = num_entries_to_generate;
-        } else {
-            sum += rows_to_add;
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        generate_datapoints_early_fill(ctx, out)?;
+        if ctx.cfg.pad_window().unwrap_or(false) {
+            fill_gap_datapoints(out, ctx.start_time, ctx.duration_in_seconds);
         }
-        // push a datapoint
-        // even though empty rows_to_add, must still have a datapoint
-        datapoints.push(DataPoint {
-            timestamp: start_time + Duration::seconds(i),
-            rows_to_add: rows_to_add as i16,
-        });
-        if sum == num_entries_to_generate {
-            // [log]
-            tracing::info!(
-                message = format!(
-                    "{} of distribution all early filled at idx {}, saved {} rows to generate",
-                    num_entries_to_generate,
-                    i,
-                    duration_in_seconds - i
-                ),
-                module = "augmentation"
-            );
-            break;
-        } // end - if (sum == num_entries_to_generate)
+        Ok(())
     }
-    Ok(())
 }
 
-fn generate_datapoints_sparse_fill(
-    start_time: DateTime<Utc>,
-    duration_in_seconds: i64,
-    num_entries_to_generate: u32,
-    datapoints: &mut Vec<DataPoint>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // create a random number of `zones`;
-    //   each zone would be allocated a number of datapoints to be generated. (also another random value based on num_entries_to_generate)
-    // there would be a random gap between the `zones`; could be 0 - adjacent with the previous zone. Or could be a random number of seconds (etc)
-    //   however, the last zone's outer boundary must be touching the the last datapoint's timestamp.
-    //   hence the logic would make sense in this way
-    //   - calculate the first zone's boundaries
-    //   - calculate the last zone's boundaries
-    //   - the residual boundary would be shared with the remaining zone(s).
-    //   - each zone would be allocated a random rows_to_add value based on num_entries_to_generate.
+struct SparseFillModel;
 
-    let num_of_zone = rand::rng().random_range(3..=6);
-    let zone_allocation_ceiling = num_entries_to_generate / num_of_zone;
-    let mut zone_allocations: Vec<u32> = vec![];
+impl DistributionModel for SparseFillModel {
+    fn description(&self) -> &str {
+        "random interval-ranges (zones) are filled; huge gaps in between the zones"
+    }
 
-    // first fill for zone_allocations
-    let mut sum = 0;
-    for i in 0..num_of_zone {
-        if i == num_of_zone - 1 {
-            zone_allocations.push(num_entries_to_generate - sum);
-            break;
-        } else {
-            zone_allocations.push(zone_allocation_ceiling);
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut datapoints = generate_datapoints_sparse_fill(ctx)?;
+        // explicit zero-row datapoints for the gaps instead
+        if ctx.cfg.fill_gaps().unwrap_or(false) {
+            fill_gap_datapoints(&mut datapoints, ctx.start_time, ctx.duration_in_seconds);
         }
-        sum += zone_allocation_ceiling;
+        out.append(&mut datapoints);
+        Ok(())
     }
-    // shuffling
-    // - based on num_of_zone * 5 times of shuffle
-    for _ in 0..num_of_zone * 5 {
-        let (first_slot, second_slot) = pick_2_random_datapoint(num_of_zone as i64);
-        // generate a random delta
-        let upper_bound = zone_allocations[first_slot as usize];
-        if upper_bound < 2 {
-            continue;
-        }
-        let delta = rand::rng().random_range(1..upper_bound);
+}
 
-        zone_allocations[first_slot as usize] -= delta;
-        zone_allocations[second_slot as usize] += delta;
+struct BimodalModel;
+
+impl DistributionModel for BimodalModel {
+    fn description(&self) -> &str {
+        "2 gaussian humps at `peak1` and `peak2`, split by `peak1_ratio`"
     }
-    // [log]
-    tracing::debug!(
-        message = format!(
-            "number of zones {} for sparse-fill after shuffle, ceilings per zone: {:?}",
-            num_of_zone, zone_allocations
-        ),
-        module = "augmentation"
-    );
 
-    // logic of slots...
-    // - num_of_zones = 6 -> slots available = num_of_zones * 6 = 36;
-    // - each slots boundary is the result of an even value of the duration_in_seconds; ie. duration_in_seconds / num_of_zone_slots (36 in this case);
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        generate_datapoints_bimodal(ctx, out)
+    }
+}
+
+struct DecayModel;
+
+impl DistributionModel for DecayModel {
+    fn description(&self) -> &str {
+        "a spike at the beginning decaying exponentially by `decay_lambda`"
+    }
+
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        generate_datapoints_decay(ctx, out)
+    }
+}
+
+struct StepModel;
+
+impl DistributionModel for StepModel {
+    fn description(&self) -> &str {
+        "flat rates jumping to new flat rates at the configured `steps`"
+    }
+
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        generate_datapoints_step(ctx, out)
+    }
+
+    /// the sum of the step rates over the window.
+    fn derived_total(
+        &self,
+        cfg: &Config,
+        duration_in_seconds: i64,
+    ) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+        let rates = generate_step_slot_rates(
+            cfg.steps().as_deref().unwrap_or_default(),
+            duration_in_seconds,
+        )?;
+        let total = rates
+            .iter()
+            .try_fold(0_u32, |total, rate| total.checked_add(*rate))
+            .ok_or("the rates of the steps overflow the number_of_entries range")?;
+        Ok(Some(total))
+    }
+}
+
+struct ReplayCsvModel;
+
+impl DistributionModel for ReplayCsvModel {
+    fn description(&self) -> &str {
+        "the shape of the `profile_path` CSV (timestamp,count) scaled to the entries"
+    }
+
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let profile_path = ctx
+            .cfg
+            .profile_path()
+            .as_deref()
+            .ok_or("the replay_csv distribution model requires the `profile_path` field")?;
+        generate_datapoints_replay_csv(ctx, "replay_csv", &read_profile_counts(profile_path)?, out)
+    }
+}
+
+struct WeightsModel;
+
+impl DistributionModel for WeightsModel {
+    fn description(&self) -> &str {
+        "the shape of the inline `weights` scaled to the entries"
+    }
+
+    fn fill(
+        &self,
+        ctx: &GenerationContext,
+        out: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let weights = ctx
+            .cfg
+            .weights()
+            .as_deref()
+            .ok_or("the weights distribution model requires the `weights` field")?;
+        check_weights(weights)?;
+        generate_datapoints_replay_csv(ctx, "weights", weights, out)
+    }
+}
+
+/// The registry of distribution models by name (i.e. the valid `distribution_by` values).
+///
+/// Both the dispatch in `generate_datapoints` and the `--list-distributions` listing are
+/// sourced from here; hence adding a model is simply registering it.
+#[derive(Default)]
+pub struct DistributionModelRegistry {
+    // a Vec instead of a HashMap to keep the registration order for listing
+    models: Vec<(String, Box<dyn DistributionModel>)>,
+}
+
+impl DistributionModelRegistry {
+    /// an empty registry.
+    pub fn new() -> Self {
+        DistributionModelRegistry { models: vec![] }
+    }
+
+    /// a registry with all the built-in models registered.
+    pub fn with_builtin_models() -> Self {
+        let mut registry = DistributionModelRegistry::new();
+        registry.register("even", Box::new(EvenModel));
+        registry.register("early_fill", Box::new(EarlyFillModel));
+        registry.register("sparse_fill", Box::new(SparseFillModel));
+        registry.register("bimodal", Box::new(BimodalModel));
+        registry.register("decay", Box::new(DecayModel));
+        registry.register("step", Box::new(StepModel));
+        registry.register("replay_csv", Box::new(ReplayCsvModel));
+        registry.register("weights", Box::new(WeightsModel));
+        registry
+    }
+
+    /// register a model by name (case-insensitive); an existing model with the same name is replaced.
+    pub fn register(&mut self, name: &str, model: Box<dyn DistributionModel>) {
+        let name = name.to_lowercase();
+        match self.models.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = model,
+            None => self.models.push((name, model)),
+        }
+    }
+
+    /// get the model by name (case-insensitive).
+    pub fn get(&self, name: &str) -> Option<&dyn DistributionModel> {
+        let name = name.to_lowercase();
+        self.models
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, model)| model.as_ref())
+    }
+
+    /// names of the registered models in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.models.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// List the registered models, 1 line per model with its description.
+    pub fn list(&self) -> String {
+        let mut listing = String::new();
+        for (name, model) in self.models.iter() {
+            listing.push_str(format!("{:<12} - {}\n", name, model.description()).as_str());
+        }
+        listing
+    }
+}
+
+/// struct to hold the timestamp and the number of rows to add - acts as a DataPoint in the distribution.
+#[derive(Debug, Clone, Getters)]
+pub struct DataPoint {
+    #[getset(get = "pub")]
+    timestamp: DateTime<Utc>,
+
+    #[getset(get = "pub")]
+    rows_to_add: i16,
+
+    /// the injected anomaly (see `anomalies`) of the slot, if any.
+    #[getset(get = "pub")]
+    anomaly: Option<AnomalyKind>,
+
+    /// a slot of the warmup (see `warmup_window`); its rows are reported apart from the entries.
+    #[getset(get = "pub")]
+    warmup: bool,
+}
+
+/// Sum up the `rows_to_add` of the given datapoints into a wide integer;
+/// safe from overflow even if the total is way beyond the per-datapoint `i16` range.
+///
+/// # Errors
+///
+/// If a datapoint has negative rows; the models never generate one (see `check_invariants`),
+/// hence it is a bug to report rather than rows to count.
+///
+pub fn total_rows(datapoints: &[DataPoint]) -> Result<u64, String> {
+    datapoints.iter().try_fold(0u64, |total, datapoint| {
+        u64::try_from(datapoint.rows_to_add)
+            .map(|rows| total + rows)
+            .map_err(|_| {
+                format!(
+                    "negative rows [{}] at [{}]",
+                    datapoint.rows_to_add, datapoint.timestamp
+                )
+            })
+    })
+}
+
+/// The `rows` of the `slot` (of the `model`) as the `rows_to_add` of its datapoint.
+///
+/// # Errors
+///
+/// If the `rows` are beyond the per-datapoint `i16` range; i.e. too many entries for the window.
+///
+pub fn slot_rows(rows: u32, slot: usize, model: &str) -> Result<i16, Box<dyn std::error::Error>> {
+    i16::try_from(rows).map_err(|_| {
+        format!(
+            "slot [{}] of the {} model needs [{}] rows; at most {} per slot, lower the number_of_entries or raise the generation_duration",
+            slot,
+            model,
+            rows,
+            i16::MAX
+        )
+        .into()
+    })
+}
+
+/// The running total of the `rows_to_add` up to and including each datapoint (in the given order);
+/// (datapoint, total) pairs. Wide integers like `total_rows`.
+pub fn cumulative_rows<'a, I>(datapoints: I) -> impl Iterator<Item = (&'a DataPoint, u64)>
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+{
+    datapoints.into_iter().scan(0u64, |total, datapoint| {
+        *total += datapoint.rows_to_add.max(0) as u64;
+        Some((datapoint, *total))
+    })
+}
+
+/// Merge the datapoints sharing the same timestamp (e.g. after jitter or truncation) by summing
+/// up their `rows_to_add`; the result is sorted by timestamp and the total rows stay the same.
+///
+/// # Errors
+///
+/// If a merged sum is beyond the per-datapoint `i16` range (i.e. more than 32767 rows per
+/// timestamp); splitting it would bring back the duplicate timestamps.
+///
+pub fn coalesce_datapoints(
+    datapoints: &[DataPoint],
+) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+    // the merged datapoint keeps the anomaly flag of any of its datapoints; the warmup rows are
+    // never merged into the others (e.g. a main slot jittered into the warmup) to be told apart
+    let mut sums: BTreeMap<(DateTime<Utc>, bool), (i64, Option<AnomalyKind>)> = BTreeMap::new();
+    for datapoint in datapoints {
+        let sum = sums
+            .entry((datapoint.timestamp, !datapoint.warmup))
+            .or_default();
+        sum.0 += datapoint.rows_to_add as i64;
+        sum.1 = sum.1.or(datapoint.anomaly);
+    }
+    let mut coalesced = Vec::with_capacity(sums.len());
+    for ((timestamp, main), (rows, anomaly)) in sums {
+        let rows_to_add = i16::try_from(rows).map_err(|_| {
+            format!(
+                "coalesced rows [{}] at [{}] are beyond the per-datapoint range of [{}, {}]",
+                rows,
+                timestamp,
+                i16::MIN,
+                i16::MAX
+            )
+        })?;
+        coalesced.push(DataPoint {
+            timestamp,
+            rows_to_add,
+            anomaly,
+            warmup: !main,
+        });
+    }
+    Ok(coalesced)
+}
+
+/// Statistics of the per-slot (per-datapoint) `rows_to_add`; for tuning the model settings
+/// (e.g. does a bimodal run actually have 2 humps of the expected spread).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DistributionStats {
+    pub min: i16,
+    pub max: i16,
+    pub mean: f64,
+    pub median: f64,
+    /// the 95th percentile (nearest-rank).
+    pub p95: f64,
+    /// the population standard deviation.
+    pub std_dev: f64,
+    /// datapoints with 0 rows; the slots without a datapoint at all (e.g. the gaps of
+    /// sparse_fill without `fill_gaps`) are not counted.
+    pub empty_slots: usize,
+}
+
+/// Compute the `DistributionStats` of the datapoints; all zeros if there are none.
+pub fn datapoint_stats(datapoints: &[DataPoint]) -> DistributionStats {
+    if datapoints.is_empty() {
+        return DistributionStats::default();
+    }
+    let mut rows: Vec<i16> = datapoints
+        .iter()
+        .map(|datapoint| datapoint.rows_to_add)
+        .collect();
+    rows.sort_unstable();
+    let len = rows.len();
+    let mean = rows.iter().map(|row| *row as f64).sum::<f64>() / len as f64;
+    let variance = rows
+        .iter()
+        .map(|row| (*row as f64 - mean).powi(2))
+        .sum::<f64>()
+        / len as f64;
+    let median = match len % 2 {
+        0 => (rows[len / 2 - 1] as f64 + rows[len / 2] as f64) / 2.0,
+        _ => rows[len / 2] as f64,
+    };
+    let p95_rank = ((0.95 * len as f64).ceil() as usize).max(1);
+
+    DistributionStats {
+        min: rows[0],
+        max: rows[len - 1],
+        mean,
+        median,
+        p95: rows[p95_rank - 1] as f64,
+        std_dev: variance.sqrt(),
+        empty_slots: rows.iter().filter(|row| **row == 0).count(),
+    }
+}
+
+/// Aggregate the datapoints into `bars` buckets of equal time spans (from the earliest to the
+/// latest timestamp), each bar summing the rows of its slots; e.g. a readable histogram of
+/// thousands of slots. Always `bars` bars (all zeros without datapoints); the bars sum up to
+/// `total_rows`.
+pub fn histogram_bucketed(datapoints: &[DataPoint], bars: usize) -> Vec<u64> {
+    let mut histogram = vec![0u64; bars];
+    let first = datapoints.iter().map(|datapoint| datapoint.timestamp).min();
+    let last = datapoints.iter().map(|datapoint| datapoint.timestamp).max();
+    let (first, last) = match (first, last, bars) {
+        (Some(first), Some(last), 1..) => (first, last),
+        _ => return histogram,
+    };
+    // + the latest (1 second) slot itself, which falls into the last bar
+    let span = (last - first + Duration::seconds(1)).num_milliseconds() as i128;
+    for datapoint in datapoints {
+        let offset = (datapoint.timestamp - first).num_milliseconds() as i128;
+        let bar = (offset * bars as i128 / span) as usize;
+        histogram[bar.min(bars - 1)] += datapoint.rows_to_add.max(0) as u64;
+    }
+    histogram
+}
+
+/// Apportion `total` rows into integer slot counts proportional to the relative `weights`;
+/// the counts always sum up to exactly `total` (for non-empty `weights`).
+///
+/// Largest-remainder apportionment: every slot gets the floor of its exact share, then the
+/// leftover rows go 1 each to the slots with the largest fractional parts (ties to the earlier slot).
+/// Negative / non-finite weights count as 0; all-zero weights split `total` evenly.
+pub fn distribute_exact(total: u32, weights: &[f64]) -> Vec<u32> {
+    if weights.is_empty() {
+        return vec![];
+    }
+    let mut weights: Vec<f64> = weights
+        .iter()
+        .map(|weight| {
+            if weight.is_finite() {
+                weight.max(0.0)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let mut weights_sum: f64 = weights.iter().sum();
+    if weights_sum <= 0.0 {
+        weights = vec![1.0; weights.len()];
+        weights_sum = weights.len() as f64;
+    }
+
+    let shares: Vec<f64> = weights
+        .iter()
+        .map(|weight| total as f64 * weight / weights_sum)
+        .collect();
+    let mut slots: Vec<u32> = shares.iter().map(|share| share.floor() as u32).collect();
+    // float rounding could (in theory) over-allocate by a row or so; never hand out more than `total`
+    let allocated: u64 = slots.iter().map(|slot| *slot as u64).sum();
+    let leftover = (total as u64).saturating_sub(allocated) as usize;
+
+    let mut by_remainder: Vec<usize> = (0..slots.len()).collect();
+    by_remainder.sort_by(|a, b| {
+        let remainder_a = shares[*a] - shares[*a].floor();
+        let remainder_b = shares[*b] - shares[*b].floor();
+        remainder_b.total_cmp(&remainder_a).then(a.cmp(b))
+    });
+    for idx in by_remainder.iter().cycle().take(leftover) {
+        slots[*idx] += 1;
+    }
+    slots
+}
+
+/// Check the core invariants of generated datapoints: the rows sum up to `num_entries`,
+/// no negative rows, timestamps within the [start_time, end_time] window and in ascending order.
+///
+/// # Errors
+///
+/// A description of the 1st violation found.
+///
+pub fn check_invariants(
+    datapoints: &[DataPoint],
+    num_entries: u64,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Result<(), String> {
+    let mut sum: u64 = 0;
+    let mut previous: Option<&DataPoint> = None;
+    for (idx, datapoint) in datapoints.iter().enumerate() {
+        if datapoint.rows_to_add < 0 {
+            return Err(format!(
+                "datapoint [{}] has negative rows [{}]",
+                idx, datapoint.rows_to_add
+            ));
+        }
+        if datapoint.timestamp < start_time || datapoint.timestamp > end_time {
+            return Err(format!(
+                "datapoint [{}] timestamp [{}] is outside of the window [{}, {}]",
+                idx, datapoint.timestamp, start_time, end_time
+            ));
+        }
+        if let Some(previous) = previous {
+            if datapoint.timestamp < previous.timestamp {
+                return Err(format!(
+                    "datapoint [{}] timestamp [{}] is before the previous one [{}]",
+                    idx, datapoint.timestamp, previous.timestamp
+                ));
+            }
+        }
+        sum += datapoint.rows_to_add as u64;
+        previous = Some(datapoint);
+    }
+    if sum != num_entries {
+        return Err(format!(
+            "rows sum up to [{}] instead of number_of_entries [{}]",
+            sum, num_entries
+        ));
+    }
+    Ok(())
+}
+
+/// Flatten the datapoints into the individual event timestamps; each datapoint becomes
+/// `rows_to_add` copies of its timestamp.
+///
+/// For huge datasets prefer `expand_datapoints_iter` which does not materialize the events.
+pub fn expand_datapoints(datapoints: &[DataPoint]) -> Vec<DateTime<Utc>> {
+    // a capacity hint only
+    let mut timestamps = Vec::with_capacity(total_rows(datapoints).unwrap_or_default() as usize);
+    timestamps.extend(expand_datapoints_iter(datapoints));
+    timestamps
+}
+
+/// The lazy version of `expand_datapoints`.
+pub fn expand_datapoints_iter(
+    datapoints: &[DataPoint],
+) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+    datapoints.iter().flat_map(|datapoint| {
+        std::iter::repeat_n(datapoint.timestamp, datapoint.rows_to_add.max(0) as usize)
+    })
+}
+
+/// An individual generated row (event) with its synthetic attributes.
+#[derive(Debug, Getters)]
+pub struct ExpandedRow {
+    #[getset(get = "pub")]
+    timestamp: DateTime<Utc>,
+
+    /// (key, value) pairs in key order.
+    #[getset(get = "pub")]
+    attributes: Vec<(String, String)>,
+
+    /// the rendered `templates`; (field, value) pairs in field order.
+    #[getset(get = "pub")]
+    fields: Vec<(String, String)>,
+}
+
+/// Expand the datapoints into the individual rows (see `expand_datapoints_iter`), each carrying
+/// 1 randomly chosen value per key of the attribute `pools` (e.g. "service.name" -> ["cart", "checkout"])
+/// and its own rendering of the field `templates` (e.g. message = "GET /api/{path} -> {status}").
+///
+/// Keys with an empty pool are skipped. The values are drawn from the generation rng, so a
+/// `seed` reproduces them too.
+pub fn expand_rows<'a>(
+    datapoints: &'a [DataPoint],
+    pools: &'a HashMap<String, Vec<String>>,
+    templates: &'a RowTemplates,
+) -> impl Iterator<Item = ExpandedRow> + 'a {
+    // sorted keys; a HashMap's iteration order would break the reproducibility
+    let mut keys: Vec<&String> = pools.keys().filter(|key| !pools[*key].is_empty()).collect();
+    keys.sort();
+
+    expand_datapoints_iter(datapoints).map(move |timestamp| ExpandedRow {
+        timestamp,
+        attributes: keys
+            .iter()
+            .map(|key| {
+                let pool = &pools[*key];
+                ((*key).clone(), pool[random_range(0..pool.len())].clone())
+            })
+            .collect(),
+        fields: templates.render(),
+    })
+}
+
+impl DataPoint {
+    pub fn new(timestamp: DateTime<Utc>, rows_to_add: i16) -> Self {
+        DataPoint {
+            timestamp,
+            rows_to_add,
+            anomaly: None,
+            warmup: false,
+        }
+    }
+
+    /// The same datapoint (timestamp and anomaly) with `rows_to_add` rows instead.
+    pub fn with_rows_to_add(&self, rows_to_add: i16) -> Self {
+        DataPoint {
+            rows_to_add,
+            ..self.clone()
+        }
+    }
+}
+
+thread_local! {
+    /// the rng behind every random decision of the generation; re-seeded per `generate_datapoints`.
+    static GENERATION_RNG: RefCell<Box<dyn RngCore>> =
+        RefCell::new(Box::new(StdRng::from_rng(&mut rand::rng())));
+
+    /// the seed of the `GENERATION_RNG`.
+    static GENERATION_SEED: Cell<u64> = const { Cell::new(0) };
+
+    /// the time range of the latest generation.
+    static GENERATION_TIME_RANGE: Cell<Option<(DateTime<Utc>, DateTime<Utc>)>> =
+        const { Cell::new(None) };
+}
+
+/// (Re-)seed the generation rng of the current thread; `None` picks a random seed.
+///
+/// With the same seed (and config) the generated datapoints are reproducible.
+///
+/// # Returns
+/// The seed actually used.
+pub fn seed_generation_rng(seed: Option<u64>) -> u64 {
+    seed_generation_rng_with(seed, RngAlgorithm::Thread)
+}
+
+/// Same as `seed_generation_rng` with the given algorithm as the generation rng.
+pub fn seed_generation_rng_with(seed: Option<u64>, algorithm: RngAlgorithm) -> u64 {
+    let seed = seed.unwrap_or_else(|| rand::rng().random());
+    let rng = new_generation_rng(seed, algorithm);
+    GENERATION_RNG.with(|generation_rng| *generation_rng.borrow_mut() = rng);
+    GENERATION_SEED.with(|generation_seed| generation_seed.set(seed));
+    seed
+}
+
+fn new_generation_rng(seed: u64, algorithm: RngAlgorithm) -> Box<dyn RngCore> {
+    match algorithm {
+        RngAlgorithm::Thread => Box::new(StdRng::seed_from_u64(seed)),
+        RngAlgorithm::ChaCha8 => Box::new(ChaCha8Rng::seed_from_u64(seed)),
+        RngAlgorithm::Pcg => Box::new(Pcg64::seed_from_u64(seed)),
+    }
+}
+
+/// The seed used by the latest generation on the current thread (even if randomly picked).
+pub fn generation_seed() -> u64 {
+    GENERATION_SEED.with(|generation_seed| generation_seed.get())
+}
+
+/// The time range (the start and end of the 1st window) of the latest generation on the current
+/// thread; none before any.
+pub fn generation_time_range() -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    GENERATION_TIME_RANGE.with(|time_range| time_range.get())
+}
+
+/// The time range of the generation of the `cfg`; the one of the latest generation on the current
+/// thread (if any) when starting from NOW(), as NOW() has moved on since.
+///
+/// # Errors
+///
+/// See `generate_time_range`.
+///
+pub fn generated_time_range(
+    cfg: &Config,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), Box<dyn std::error::Error>> {
+    let from_now = cfg
+        .use_now_as_timestamp()
+        .is_none_or(|use_now| use_now && !cfg.prefer_start_timestamp().unwrap_or(false));
+    match generation_time_range() {
+        Some(time_range) if from_now => Ok(time_range),
+        _ => generate_time_range(cfg),
+    }
+}
+
+/// A random value within `range` from the generation rng; custom distribution models should
+/// use this too so that a `seed` makes them reproducible.
+pub fn random_range<T, R>(range: R) -> T
+where
+    T: SampleUniform,
+    R: SampleRange<T>,
+{
+    GENERATION_RNG.with(|rng| rng.borrow_mut().random_range(range))
+}
+
+/// Generate the datapoints with the built-in distribution models.
+pub fn generate_datapoints(cfg: &Config) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+    generate_datapoints_with_registry(cfg, &DistributionModelRegistry::with_builtin_models())
+}
+
+/// The streaming flavour of `generate_datapoints`; for consumers processing the datapoints one
+/// by one (e.g. a `BatchingSink`) instead of holding on to the whole dataset.
+///
+/// The (repeated) windows are generated lazily, 1 window at a time as the iterator advances;
+/// hence only the window being consumed is held (see `GeneratedDatapoints`).
+///
+/// # Errors
+///
+/// Same as `generate_datapoints`; the config is validated (and the 1st window generated) up
+/// front.
+///
+pub fn generate_datapoints_iter(
+    cfg: &Config,
+) -> Result<GeneratedDatapoints<'_>, Box<dyn std::error::Error>> {
+    generate_datapoints_iter_with(
+        cfg,
+        DistributionModelRegistry::with_builtin_models(),
+        TransformRegistry::with_builtin_transforms(cfg),
+    )
+}
+
+/// Same as `generate_datapoints_iter` with the given model and transform registries.
+pub fn generate_datapoints_iter_with(
+    cfg: &Config,
+    registry: DistributionModelRegistry,
+    transforms: TransformRegistry,
+) -> Result<GeneratedDatapoints<'_>, Box<dyn std::error::Error>> {
+    GeneratedDatapoints::new(cfg, registry, transforms)
+}
+
+/// Generate the datapoints with the `distribution_by` model looked up from the given registry.
+pub fn generate_datapoints_with_registry(
+    cfg: &Config,
+    registry: &DistributionModelRegistry,
+) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+    generate_datapoints_with(
+        cfg,
+        registry,
+        &TransformRegistry::with_builtin_transforms(cfg),
+    )
+}
+
+/// Generate the datapoints with the `distribution_by` model looked up from the given registry,
+/// then apply the configured `transforms` (in order) looked up from the transform registry.
+pub fn generate_datapoints_with(
+    cfg: &Config,
+    registry: &DistributionModelRegistry,
+    transforms: &TransformRegistry,
+) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+    let plan = GenerationPlan::new(cfg, registry)?;
+    seed_generation_rng_with(Some(plan.seed), plan.rng);
+    GENERATION_TIME_RANGE.with(|time_range| time_range.set(Some((plan.start_time, plan.end_time))));
+    tracing::debug!(module = "augmentation", seed = plan.seed, "generating");
+
+    let mut datapoints: Vec<DataPoint> = Vec::new();
+    for window in 0..plan.repeat_count {
+        datapoints.append(&mut plan.generate_window(cfg, registry, transforms, window)?);
+    }
+    // the timestamps and counts stay intact; only the order flips
+    if plan.order == Order::Desc {
+        datapoints.reverse();
+    }
+    plan.log_generated(total_rows(&datapoints)?);
+    Ok(datapoints)
+}
+
+/// The resolved (and validated) settings of a generation; the (repeated) windows are generated
+/// from it 1 at a time (see `generate_window`).
+struct GenerationPlan {
+    model: String,
+    seed: u64,
+    rng: RngAlgorithm,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    num_entries: u32,
+    repeat_count: u32,
+    repeat_interval: Duration,
+    slot_width: i64,
+    num_slots: i64,
+    warmup_seconds: i64,
+    warmup_rate: u32,
+    main_slots: i64,
+    value_mode: ValueMode,
+    order: Order,
+    anomalies: Vec<Anomaly>,
+    timestamp_jitter: Option<Duration>,
+    /// the `single_instant` mode with an empty window; all the entries at the start.
+    single_instant: bool,
+}
+
+impl GenerationPlan {
+    /// The plan of the `cfg`; `None` as the `seed` picks a random one.
+    ///
+    /// # Errors
+    ///
+    /// If the config is invalid (e.g. an unknown model, beyond the safety limits).
+    ///
+    fn new(
+        cfg: &Config,
+        registry: &DistributionModelRegistry,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (start_time, end_time) = generate_time_range(cfg)?;
+        let rng = RngAlgorithm::from_config(cfg)?;
+        let seed = cfg.seed().unwrap_or_else(|| rand::rng().random());
+
+        // duration in seconds is the unit of time for generating datapoints.
+        // Seconds granularity works in this case as though in production, events are created at microseconds or milliseconds level;
+        // however for graph plotting etc, the datapoints are usually re-grouped in a less granular unit such as seconds, minutes or days
+        // and thus would not make much difference to have a microsecond granularity or not.
+        //
+        // PS. you might view this as a limitation of the implementation.
+        let (duration_in_seconds, slot_width) = generation_window_in_seconds(cfg)?;
+
+        let model = canonical_model_name(cfg.distribution_by().as_deref().unwrap());
+        let registered_model = registry
+            .get(model.as_str())
+            .ok_or(format!("unknown distribution model [{}]", model))?;
+        let num_entries_to_generate = match cfg.number_of_entries() {
+            Some(num_entries) => *num_entries,
+            None => registered_model
+                .derived_total(cfg, duration_in_seconds)?
+                .ok_or("number_of_entries is not set")?,
+        };
+        let num_entries_to_generate = scale_entries(num_entries_to_generate, *cfg.scale())?;
+        if num_entries_to_generate == 0 {
+            return Err("number_of_entries must be greater than zero".into());
+        }
+        // safety limits; checked before any allocation
+        let (repeat_count, repeat_interval) = repeat_windows(cfg, end_time - start_time)?;
+        let max_entries = cfg.max_entries().unwrap_or(DEFAULT_MAX_ENTRIES);
+        if num_entries_to_generate as u64 * repeat_count as u64 > max_entries {
+            let repeated = match repeat_count {
+                1 => String::new(),
+                _ => format!(" x repeat count [{}]", repeat_count),
+            };
+            return Err(format!(
+                "number_of_entries [{}]{} exceeds the max_entries safety limit [{}]; raise `max_entries` explicitly if intended",
+                num_entries_to_generate, repeated, max_entries
+            )
+            .into());
+        }
+        if slot_width > 1 && model == "step" {
+            let setting = match cfg.slot_count() {
+                Some(_) => "slot_width wider than 1 second",
+                None => "max_slots coarsening",
+            };
+            return Err(format!(
+                "{} is not supported by the step model (its steps are in seconds)",
+                setting
+            )
+            .into());
+        }
+        let mut num_slots = (duration_in_seconds + slot_width - 1) / slot_width;
+        // 1 more slot right at the end boundary; i.e. [start, end] instead of [start, end)
+        if cfg.end_inclusive().unwrap_or(false) {
+            num_slots += 1;
+        }
+        let single_instant = cfg.single_instant().unwrap_or(false) && start_time == end_time;
+        if single_instant {
+            if num_entries_to_generate > i16::MAX as u32 {
+                return Err(format!(
+                    "single_instant carries the number_of_entries [{}] in 1 datapoint; at most {}",
+                    num_entries_to_generate,
+                    i16::MAX
+                )
+                .into());
+            }
+            num_slots = 1;
+        }
+        if num_slots > MAX_SLOTS {
+            return Err(format!(
+                "generation_duration of {} seconds exceeds the maximum of {} slots (1 per second); shorten `generation_duration`",
+                duration_in_seconds, MAX_SLOTS
+            )
+            .into());
+        }
+        // the warmup slots come first; the model fills the remaining ones
+        let (warmup_seconds, warmup_rate) = warmup_window(cfg)?;
+        if warmup_seconds > 0 && slot_width > 1 {
+            return Err("warmup_duration is not supported with slots wider than 1 second".into());
+        }
+        if warmup_seconds >= num_slots {
+            return Err(format!(
+                "warmup_duration [{}] must be shorter than the generation window ({} seconds)",
+                cfg.warmup_duration().as_deref().unwrap_or_default(),
+                duration_in_seconds
+            )
+            .into());
+        }
+        // whatever the model, some slot carries at least the average rows
+        let main_slots = num_slots - warmup_seconds;
+        let min_peak_rows = (num_entries_to_generate as i64 + main_slots - 1) / main_slots;
+        if !single_instant && min_peak_rows > i16::MAX as i64 {
+            return Err(format!(
+                "number_of_entries [{}] over [{}] slots needs at least [{}] rows per slot; at most {} per slot, lower the number_of_entries or raise the generation_duration",
+                num_entries_to_generate,
+                main_slots,
+                min_peak_rows,
+                i16::MAX
+            )
+            .into());
+        }
+        let value_mode = ValueMode::from_config(cfg)?;
+        let order = Order::from_config(cfg)?;
+        let anomalies = Anomaly::from_config(cfg)?;
+        if value_mode == ValueMode::Gauge {
+            let gauge_min = cfg.gauge_min().unwrap_or(DEFAULT_GAUGE_MIN);
+            let gauge_max = cfg.gauge_max().unwrap_or(DEFAULT_GAUGE_MAX);
+            if gauge_min > gauge_max {
+                return Err(format!(
+                    "gauge_min [{}] must be <= gauge_max [{}]",
+                    gauge_min, gauge_max
+                )
+                .into());
+            }
+        }
+        let timestamp_jitter = match cfg.timestamp_jitter() {
+            Some(timestamp_jitter) => Some(parse_time_duration(timestamp_jitter.clone())?),
+            None => None,
+        };
+        Ok(GenerationPlan {
+            model,
+            seed,
+            rng,
+            start_time,
+            end_time,
+            num_entries: num_entries_to_generate,
+            repeat_count,
+            repeat_interval,
+            slot_width,
+            num_slots,
+            warmup_seconds,
+            warmup_rate,
+            main_slots,
+            value_mode,
+            order,
+            anomalies,
+            timestamp_jitter,
+            single_instant,
+        })
+    }
+
+    /// Generate the `window`-th (repeated) window with the generation rng of the current thread
+    /// (see `seed_generation_rng_with`); in ascending order whatever the `order`.
+    ///
+    /// Every window is generated on its own; the jitter stays within its window, hence the
+    /// windows never overlap and coalescing them 1 by 1 is the same as coalescing them all.
+    fn generate_window(
+        &self,
+        cfg: &Config,
+        registry: &DistributionModelRegistry,
+        transforms: &TransformRegistry,
+        window: u32,
+    ) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+        let offset = self.repeat_interval * window as i32;
+        if self.single_instant {
+            // no slots to spread over (nor to shape); exactly 1 datapoint
+            return Ok(vec![DataPoint::new(
+                self.start_time + offset,
+                self.num_entries as i16,
+            )]);
+        }
+        let main_start_time = self.start_time + offset + Duration::seconds(self.warmup_seconds);
+        let ctx = GenerationContext::new(cfg, main_start_time, self.main_slots, self.num_entries);
+        let mut window_datapoints: Vec<DataPoint> = Vec::new();
+        registry
+            .get(self.model.as_str())
+            .ok_or(format!("unknown distribution model [{}]", self.model))?
+            .fill(&ctx, &mut window_datapoints)?;
+        if let Some(names) = cfg.transforms() {
+            transforms.apply_all(names, &mut window_datapoints)?;
+        }
+        if let Some(min_rows) = cfg.min_rows_per_slot() {
+            apply_min_rows_per_slot(
+                &mut window_datapoints,
+                *min_rows,
+                main_start_time,
+                self.main_slots,
+            )?;
+        }
+
+        if self.value_mode == ValueMode::Gauge {
+            shape_gauge_values(
+                &mut window_datapoints,
+                cfg.gauge_min().unwrap_or(DEFAULT_GAUGE_MIN),
+                cfg.gauge_max().unwrap_or(DEFAULT_GAUGE_MAX),
+            );
+        }
+        apply_anomalies(
+            &mut window_datapoints,
+            &self.anomalies,
+            main_start_time,
+            self.main_slots,
+            self.value_mode == ValueMode::Count,
+        )?;
+        if self.warmup_seconds > 0 {
+            let mut warmup_datapoints: Vec<DataPoint> = (0..self.warmup_seconds)
+                .map(|second| DataPoint {
+                    timestamp: self.start_time + offset + Duration::seconds(second),
+                    rows_to_add: self.warmup_rate as i16,
+                    anomaly: None,
+                    warmup: true,
+                })
+                .collect();
+            warmup_datapoints.append(&mut window_datapoints);
+            window_datapoints = warmup_datapoints;
+        }
+        if self.slot_width > 1 {
+            widen_slots(
+                &mut window_datapoints,
+                self.start_time + offset,
+                self.end_time + offset,
+                self.slot_width,
+            );
+        }
+        if let Some(jitter) = self.timestamp_jitter {
+            apply_timestamp_jitter(
+                &mut window_datapoints,
+                jitter,
+                self.start_time + offset,
+                self.end_time + offset,
+            );
+        }
+        if cfg.coalesce().unwrap_or(false) {
+            window_datapoints = coalesce_datapoints(&window_datapoints)?;
+        }
+        Ok(window_datapoints)
+    }
+
+    fn log_generated(&self, rows: u64) {
+        tracing::debug!(
+            module = "augmentation",
+            model = self.model.as_str(),
+            seed = self.seed,
+            slots = self.num_slots,
+            windows = self.repeat_count,
+            rows,
+            total = self.num_entries,
+            "generated"
+        );
+    }
+}
+
+/// The datapoints of `generate_datapoints_iter`; the (repeated) windows are generated 1 at a
+/// time, each as the previous one is used up.
+///
+/// The iterator carries its own generation rng (seeded like `generate_datapoints`), swapped into
+/// the current thread per window; hence it yields the same datapoints as `generate_datapoints`
+/// whatever else the thread generates meanwhile.
+///
+/// PS. a window failing after the 1st one (which is generated up front) is logged and ends the
+/// datapoints; with the `desc` order, every window is generated up front (the newest comes 1st).
+pub struct GeneratedDatapoints<'a> {
+    cfg: &'a Config,
+    registry: DistributionModelRegistry,
+    transforms: TransformRegistry,
+    plan: GenerationPlan,
+    rng: Box<dyn RngCore>,
+    next_window: u32,
+    window: std::vec::IntoIter<DataPoint>,
+    rows: u64,
+}
+
+impl<'a> GeneratedDatapoints<'a> {
+    fn new(
+        cfg: &'a Config,
+        registry: DistributionModelRegistry,
+        transforms: TransformRegistry,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let plan = GenerationPlan::new(cfg, &registry)?;
+        tracing::debug!(module = "augmentation", seed = plan.seed, "generating");
+        GENERATION_SEED.with(|generation_seed| generation_seed.set(plan.seed));
+        GENERATION_TIME_RANGE
+            .with(|time_range| time_range.set(Some((plan.start_time, plan.end_time))));
+        let mut datapoints = GeneratedDatapoints {
+            cfg,
+            registry,
+            transforms,
+            rng: new_generation_rng(plan.seed, plan.rng),
+            plan,
+            next_window: 0,
+            window: Vec::new().into_iter(),
+            rows: 0,
+        };
+        let window_count = match datapoints.plan.order {
+            Order::Desc => datapoints.plan.repeat_count,
+            Order::Asc => 1,
+        };
+        let mut window = Vec::new();
+        for _ in 0..window_count {
+            window.append(&mut datapoints.generate_next_window()?);
+        }
+        if datapoints.plan.order == Order::Desc {
+            window.reverse();
+        }
+        datapoints.window = window.into_iter();
+        Ok(datapoints)
+    }
+
+    /// The seed actually used (even if randomly picked).
+    pub fn seed(&self) -> u64 {
+        self.plan.seed
+    }
+
+    fn generate_next_window(&mut self) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+        // the own rng of the iterator; the thread's is put back afterwards
+        GENERATION_RNG.with(|rng| std::mem::swap(&mut *rng.borrow_mut(), &mut self.rng));
+        let window =
+            self.plan
+                .generate_window(self.cfg, &self.registry, &self.transforms, self.next_window);
+        GENERATION_RNG.with(|rng| std::mem::swap(&mut *rng.borrow_mut(), &mut self.rng));
+        self.next_window += 1;
+        let window = window?;
+        self.rows += total_rows(&window)?;
+        if self.next_window == self.plan.repeat_count {
+            self.plan.log_generated(self.rows);
+        }
+        Ok(window)
+    }
+}
+
+impl Iterator for GeneratedDatapoints<'_> {
+    type Item = DataPoint;
+
+    fn next(&mut self) -> Option<DataPoint> {
+        loop {
+            if let Some(datapoint) = self.window.next() {
+                return Some(datapoint);
+            }
+            if self.next_window >= self.plan.repeat_count {
+                return None;
+            }
+            match self.generate_next_window() {
+                Ok(window) => self.window = window.into_iter(),
+                Err(e) => {
+                    tracing::error!(
+                        module = "augmentation",
+                        window = self.next_window - 1,
+                        "generation stopped; {}",
+                        e
+                    );
+                    self.next_window = self.plan.repeat_count;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// the `number_of_entries` scaled by the `scale` factor (rounded); as-is without a `scale`.
+pub fn scale_entries(
+    num_entries: u32,
+    scale: Option<f64>,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let scale = match scale {
+        None => return Ok(num_entries),
+        Some(scale) if !scale.is_finite() || scale <= 0.0 => {
+            return Err(format!("scale must be positive, got [{}]", scale).into());
+        }
+        Some(scale) => scale,
+    };
+    let scaled = (num_entries as f64 * scale).round();
+    if scaled > u32::MAX as f64 {
+        return Err(format!(
+            "number_of_entries [{}] x scale [{}] overflows the entries range",
+            num_entries, scale
+        )
+        .into());
+    }
+    Ok(scaled as u32)
+}
+
+/// the width of a slot; 1 second unless the window has more seconds than `max_slots`, then the
+/// narrowest width fitting the window into `max_slots` slots.
+pub fn slot_width_in_seconds(
+    duration_in_seconds: i64,
+    max_slots: Option<u32>,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    match max_slots {
+        None => Ok(1),
+        Some(0) => Err("max_slots must be greater than zero".into()),
+        Some(max_slots) if duration_in_seconds > max_slots as i64 => {
+            Ok((duration_in_seconds + max_slots as i64 - 1) / max_slots as i64)
+        }
+        Some(_) => Ok(1),
+    }
+}
+
+/// the window in seconds and the width of its slots; either the `generation_duration` split into
+/// 1 second slots (fewer, wider slots if the window has more seconds than `max_slots`), or exactly
+/// `slot_count` slots `slot_width` wide.
+pub fn generation_window_in_seconds(
+    cfg: &Config,
+) -> Result<(i64, i64), Box<dyn std::error::Error>> {
+    match (cfg.slot_count(), cfg.slot_width()) {
+        (Some(0), _) => Err("slot_count must be greater than zero".into()),
+        (Some(slot_count), Some(slot_width)) => {
+            let slot_width = parse_time_duration(slot_width.clone())?.num_seconds();
+            if slot_width < 1 {
+                return Err("slot_width must be at least 1 second".into());
+            }
+            Ok((*slot_count as i64 * slot_width, slot_width))
+        }
+        (Some(_), None) => Err("slot_count is set without a slot_width".into()),
+        (None, _) => {
+            let generation_duration = match cfg.generation_duration() {
+                Some(generation_duration) => generation_duration,
+                None => return Err("generation_duration is not set".into()),
+            };
+            // [lesson] also works ... cfg.generation_duration().as_ref().unwrap().clone()
+            let duration_in_seconds =
+                parse_time_duration(generation_duration.clone())?.num_seconds();
+            let slot_width = slot_width_in_seconds(duration_in_seconds, *cfg.max_slots())?;
+            Ok((duration_in_seconds, slot_width))
+        }
+    }
+}
+
+/// the warmup in seconds and its rows per second; (0, 0) without a warmup.
+///
+/// # Errors
+///
+/// If only one of `warmup_duration` and `warmup_rate` is set, the duration is shorter than 1
+/// second, or the rate does not fit in a slot.
+///
+pub fn warmup_window(cfg: &Config) -> Result<(i64, u32), Box<dyn std::error::Error>> {
+    match (cfg.warmup_duration(), cfg.warmup_rate()) {
+        (None, None) => Ok((0, 0)),
+        (Some(warmup_duration), Some(warmup_rate)) => {
+            let seconds = parse_time_duration(warmup_duration.clone())?.num_seconds();
+            if seconds < 1 {
+                return Err("warmup_duration must be at least 1 second".into());
+            }
+            if *warmup_rate > i16::MAX as u32 {
+                return Err(format!(
+                    "warmup_rate [{}] exceeds the max rows per slot [{}]",
+                    warmup_rate,
+                    i16::MAX
+                )
+                .into());
+            }
+            Ok((seconds, *warmup_rate))
+        }
+        _ => Err("warmup_duration and warmup_rate must be set together".into()),
+    }
+}
+
+/// stretch the datapoints (1 slot per second from `start_time`) into slots `slot_width` seconds wide;
+/// an end-inclusive slot beyond `end_time` (the window is not a multiple of the width) stays at `end_time`.
+fn widen_slots(
+    datapoints: &mut [DataPoint],
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    slot_width: i64,
+) {
+    for datapoint in datapoints.iter_mut() {
+        datapoint.timestamp =
+            (start_time + (datapoint.timestamp - start_time) * slot_width as i32).min(end_time);
+    }
+}
+
+/// A segment of the generation window (for the proposed `segments`); its offset from the start
+/// of the window and its duration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub offset: Duration,
+    pub duration: Duration,
+}
+
+/// Check that the segments (in order) cover the generation window of the `generation_duration`
+/// back to back; otherwise the timestamps of a segment would silently overlap (or leave a gap
+/// to) the ones of its neighbours.
+///
+/// # Errors
+///
+/// At the 1st offending segment boundary; an empty segment, an overlap, a gap, or the segments
+/// not ending at the `generation_duration`.
+///
+pub fn validate_segments(
+    segments: &[Segment],
+    generation_duration: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut end = Duration::zero();
+    for (idx, segment) in segments.iter().enumerate() {
+        if segment.duration <= Duration::zero() {
+            return Err(format!(
+                "segment [{}] duration must be positive, got [{}s]",
+                idx,
+                segment.duration.num_seconds()
+            )
+            .into());
+        }
+        if segment.offset < end {
+            return Err(format!(
+                "segment [{}] overlaps the previous segment; starts at [{}s] before its end at [{}s]",
+                idx,
+                segment.offset.num_seconds(),
+                end.num_seconds()
+            )
+            .into());
+        }
+        if segment.offset > end {
+            return Err(format!(
+                "segment [{}] leaves a gap; starts at [{}s] after the previous end at [{}s]",
+                idx,
+                segment.offset.num_seconds(),
+                end.num_seconds()
+            )
+            .into());
+        }
+        end = segment.offset + segment.duration;
+    }
+    if end != generation_duration {
+        return Err(format!(
+            "segments end at [{}s] instead of the generation_duration [{}s]",
+            end.num_seconds(),
+            generation_duration.num_seconds()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// the number of windows and the offset between them; a single window without `repeat`.
+pub(crate) fn repeat_windows(
+    cfg: &Config,
+    window: Duration,
+) -> Result<(u32, Duration), Box<dyn std::error::Error>> {
+    let repeat = match cfg.repeat() {
+        Some(repeat) => repeat,
+        None => return Ok((1, Duration::zero())),
+    };
+    if *repeat.count() == 0 {
+        return Err("repeat count must be greater than zero".into());
+    }
+    let interval = parse_time_duration(repeat.interval().clone())?;
+    if interval < window {
+        return Err(format!(
+            "repeat interval [{}] must be >= generation_duration [{}] so that the windows do not overlap",
+            repeat.interval(),
+            cfg.generation_duration().as_deref().unwrap_or_default()
+        )
+        .into());
+    }
+    Ok((*repeat.count(), interval))
+}
+
+/// raise every slot of the window to at least `min_rows` rows, whatever the distribution model; a
+/// missing slot counts as an empty one. The rows are borrowed from the heaviest slots (never below
+/// the floor), hence the total stays the same.
+///
+/// # Errors
+///
+/// If the floor is infeasible; i.e. `min_rows` x `num_slots` exceeds the total rows, or
+/// `min_rows` exceeds the per-slot `i16` range.
+///
+fn apply_min_rows_per_slot(
+    datapoints: &mut Vec<DataPoint>,
+    min_rows: u32,
+    start_time: DateTime<Utc>,
+    num_slots: i64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let min_rows_per_slot = i16::try_from(min_rows).map_err(|_| {
+        format!(
+            "min_rows_per_slot [{}] exceeds the per-slot range; at most {}",
+            min_rows,
+            i16::MAX
+        )
+    })?;
+    let total = total_rows(datapoints)?;
+    if min_rows as u64 * num_slots.max(0) as u64 > total {
+        return Err(format!(
+            "min_rows_per_slot [{}] x slots [{}] exceeds the total rows [{}]",
+            min_rows, num_slots, total
+        )
+        .into());
+    }
+    if min_rows == 0 {
+        return Ok(());
+    }
+    fill_gap_datapoints(datapoints, start_time, num_slots);
+    let min_rows = min_rows_per_slot;
+    let mut donors: BinaryHeap<(i16, usize)> = datapoints
+        .iter()
+        .enumerate()
+        .filter(|(_, datapoint)| datapoint.rows_to_add > min_rows)
+        .map(|(idx, datapoint)| (datapoint.rows_to_add, idx))
+        .collect();
+    for idx in 0..datapoints.len() {
+        while datapoints[idx].rows_to_add < min_rows {
+            let Some((rows, donor)) = donors.pop() else {
+                break;
+            };
+            let borrowed = (rows - min_rows).min(min_rows - datapoints[idx].rows_to_add);
+            datapoints[donor].rows_to_add -= borrowed;
+            datapoints[idx].rows_to_add += borrowed;
+            if datapoints[donor].rows_to_add > min_rows {
+                donors.push((datapoints[donor].rows_to_add, donor));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// reinterpret the per-slot counts as gauge values; the counts are linearly rescaled into
+/// [gauge_min, gauge_max] hence the value curve keeps the shape of the distribution model.
+/// Flat counts (nothing to shape) sit in the middle of the bounds.
+fn shape_gauge_values(datapoints: &mut [DataPoint], gauge_min: i16, gauge_max: i16) {
+    let counts = datapoints.iter().map(|datapoint| datapoint.rows_to_add);
+    let (count_min, count_max) = match (counts.clone().min(), counts.max()) {
+        (Some(count_min), Some(count_max)) => (count_min as f64, count_max as f64),
+        _ => return,
+    };
+    let (gauge_min, gauge_max) = (gauge_min as f64, gauge_max as f64);
+    for datapoint in datapoints.iter_mut() {
+        let ratio = if count_max > count_min {
+            (datapoint.rows_to_add as f64 - count_min) / (count_max - count_min)
+        } else {
+            0.5
+        };
+        datapoint.rows_to_add = (gauge_min + ratio * (gauge_max - gauge_min)).round() as i16;
+    }
+}
+
+/// inject the `anomalies` into the (1 second) slots of the window starting at `start_time`; a slot
+/// without a datapoint (e.g. left empty by the model) gets one. The affected datapoints are flagged.
+///
+/// With `preserve_total` (the `count` value mode) the rows a spike adds are taken away from the other
+/// datapoints and the rows a drop removes are handed over to them, proportionally to their rows
+/// (evenly if they are all empty); a spike is capped by the rows available in the other datapoints.
+fn apply_anomalies(
+    datapoints: &mut Vec<DataPoint>,
+    anomalies: &[Anomaly],
+    start_time: DateTime<Utc>,
+    num_slots: i64,
+    preserve_total: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for anomaly in anomalies {
+        let slot = ((anomaly.at * num_slots as f64) as i64).min(num_slots - 1);
+        let timestamp = start_time + Duration::seconds(slot);
+        let idx = match datapoints.iter().position(|d| d.timestamp == timestamp) {
+            Some(idx) => idx,
+            None => {
+                let idx = datapoints.partition_point(|d| d.timestamp < timestamp);
+                datapoints.insert(idx, DataPoint::new(timestamp, 0));
+                idx
+            }
+        };
+        let rows = datapoints[idx].rows_to_add.max(0) as f64;
+        let target = match anomaly.kind {
+            AnomalyKind::Drop => rows * (1.0 - anomaly.magnitude),
+            AnomalyKind::Spike => {
+                // an empty slot spikes from the average slot instead
+                let base = match rows > 0.0 {
+                    true => rows,
+                    false => (total_rows(datapoints)? as f64 / num_slots as f64).ceil(),
+                };
+                base * anomaly.magnitude
+            }
+        }
+        .round()
+        .min(i16::MAX as f64);
+
+        let mut target = target as i16;
+        if preserve_total && datapoints.len() > 1 {
+            let weights: Vec<f64> = datapoints
+                .iter()
+                .enumerate()
+                .map(
+                    |(i, datapoint)| match i == idx || datapoint.anomaly.is_some() {
+                        // the other anomalies stay as injected
+                        true => 0.0,
+                        false => datapoint.rows_to_add.max(0) as f64,
+                    },
+                )
+                .collect();
+            let others: f64 = weights.iter().sum();
+            let rows = rows as i16;
+            if target > rows {
+                let extra = ((target - rows) as f64).min(others) as u32;
+                target = rows + extra as i16;
+                for (datapoint, taken) in
+                    datapoints.iter_mut().zip(distribute_exact(extra, &weights))
+                {
+                    datapoint.rows_to_add -= taken as i16;
+                }
+            } else {
+                // all the other datapoints are empty; donated evenly
+                let weights: Vec<f64> = match others > 0.0 {
+                    true => weights,
+                    false => datapoints
+                        .iter()
+                        .enumerate()
+                        .map(
+                            |(i, datapoint)| match i == idx || datapoint.anomaly.is_some() {
+                                true => 0.0,
+                                false => 1.0,
+                            },
+                        )
+                        .collect(),
+                };
+                let donated = distribute_exact((rows - target) as u32, &weights);
+                for (datapoint, given) in datapoints.iter_mut().zip(donated) {
+                    datapoint.rows_to_add = datapoint.rows_to_add.saturating_add(given as i16);
+                }
+            }
+        } else if preserve_total {
+            // a single slot; no other slot to borrow from or to donate to
+            tracing::warn!(
+                module = "augmentation",
+                anomaly = anomaly.kind.name(),
+                slot,
+                "the anomaly needs another slot to keep the total; skipped"
+            );
+            continue;
+        }
+        datapoints[idx].rows_to_add = target;
+        datapoints[idx].anomaly = Some(anomaly.kind);
+    }
+    Ok(())
+}
+
+/// offset every datapoint's timestamp by a uniform random amount within +/- `jitter`;
+/// the jittered timestamps are clamped into the [start_time, end_time] window.
+fn apply_timestamp_jitter(
+    datapoints: &mut [DataPoint],
+    jitter: Duration,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) {
+    let jitter_in_nanos = jitter.num_nanoseconds().unwrap_or(i64::MAX).abs();
+    if jitter_in_nanos == 0 {
+        return;
+    }
+    for datapoint in datapoints.iter_mut() {
+        let offset = random_range(-jitter_in_nanos..=jitter_in_nanos);
+        datapoint.timestamp =
+            (datapoint.timestamp + Duration::nanoseconds(offset)).clamp(start_time, end_time);
+    }
+}
+
+fn generate_datapoints_even(
+    ctx: &GenerationContext,
+    datapoints: &mut Vec<DataPoint>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (start_time, duration_in_seconds, num_entries_to_generate) = (
+        ctx.start_time,
+        ctx.duration_in_seconds,
+        ctx.num_entries_to_generate,
+    );
+    let rounding = Rounding::from_config(ctx.cfg)?;
+    // first fill; every datapoint interval gets (approximately) the same number of rows
+    let slots = even_slot_counts(
+        num_entries_to_generate,
+        duration_in_seconds.max(0) as usize,
+        rounding,
+    );
+    for (i, rows_to_add) in slots.iter().enumerate() {
+        datapoints.push(DataPoint {
+            timestamp: start_time + Duration::seconds(i as i64),
+            rows_to_add: slot_rows(*rows_to_add, i, "even")?,
+            anomaly: None,
+            warmup: false,
+        });
+    }
+
+    // second fill (random pick and assign)
+    // rounds 2/10 of the num_of_entries_to_generate, make sure a randomness is introduced in the distribution set.
+    // (nothing to shuffle with a single slot)
+    let num_shuffles = (num_entries_to_generate as f32 * 0.2) as u32;
+    for _ in 0..num_shuffles {
+        if datapoints.len() < 2 {
+            break;
+        }
+        let (first_slot, second_slot) = pick_2_random_datapoint(duration_in_seconds);
+        // update a random additive deducted from first_slot to second_slot
+        let first_slot_row_to_add = datapoints[first_slot as usize].rows_to_add;
+        tracing::trace!(
+            module = "augmentation",
+            model = "even",
+            slot = first_slot,
+            to_slot = second_slot,
+            rows = first_slot_row_to_add,
+            "shuffling"
+        );
+        // fewer entries than slots leaves empty slots; nothing to take from
+        if first_slot_row_to_add < 2 {
+            continue;
+        }
+        // never beyond the per-datapoint range of the receiving slot
+        let delta = random_range(1..first_slot_row_to_add)
+            .min(i16::MAX - datapoints[second_slot as usize].rows_to_add);
+        datapoints[first_slot as usize].rows_to_add -= delta;
+        datapoints[second_slot as usize].rows_to_add += delta;
+    }
+    Ok(())
+}
+
+/// the first fill of the even model; `total` split into `num_slots` (almost) equal counts.
+fn even_slot_counts(total: u32, num_slots: usize, rounding: Rounding) -> Vec<u32> {
+    match rounding {
+        Rounding::Spread => distribute_exact(total, &vec![1.0; num_slots]),
+        Rounding::LastSlot => {
+            if num_slots == 0 {
+                return vec![];
+            }
+            let per_slot = total / num_slots as u32;
+            let mut slots = vec![per_slot; num_slots];
+            fill_last_slot(&mut slots, total);
+            slots
+        }
+    }
+}
+
+/// set the last slot to the remainder of `total` after the earlier slots. A would-be-negative
+/// remainder (the earlier slots already exceed `total`, e.g. a per-slot count rounded up) leaves
+/// the last slot empty and trims the excess from the earlier slots instead, the latest first;
+/// every slot stays non-negative and the slots sum up to exactly `total`.
+fn fill_last_slot(slots: &mut [u32], total: u32) {
+    let last_idx = match slots.len() {
+        0 => return,
+        len => len - 1,
+    };
+    let sum_before_last: u64 = slots[..last_idx].iter().map(|rows| *rows as u64).sum();
+    if sum_before_last <= total as u64 {
+        slots[last_idx] = total - sum_before_last as u32;
+        return;
+    }
+    slots[last_idx] = 0;
+    let mut excess = sum_before_last - total as u64;
+    for rows in slots[..last_idx].iter_mut().rev() {
+        let trimmed = (*rows as u64).min(excess);
+        *rows -= trimmed as u32;
+        excess -= trimmed;
+        if excess == 0 {
+            break;
+        }
+    }
+}
+
+/// 2 distinct random slots; requires at least 2 slots.
+fn pick_2_random_datapoint(slots_length: i64) -> (i64, i64) {
+    // slots_length = duration_in_seconds
+    let first_slot = random_range(0..slots_length);
+    let mut second_slot = random_range(0..slots_length);
+
+    loop {
+        if second_slot != first_slot {
+            break;
+        }
+        second_slot = random_range(0..slots_length);
+    }
+    (first_slot, second_slot)
+}
+
+fn generate_datapoints_early_fill(
+    ctx: &GenerationContext,
+    datapoints: &mut Vec<DataPoint>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (start_time, duration_in_seconds, num_entries_to_generate) = (
+        ctx.start_time,
+        ctx.duration_in_seconds,
+        ctx.num_entries_to_generate,
+    );
+    // loop through the slots
+    // assign a random rows_to_add value to the given slot
+    //  (remember the actual ceiling is the num_entries_to_generate; so a logical ceiling would be num_entries_to_generate * 1% per slot's rows_to_add')
+    // once the accumulated rows_to_add is greater than or equals to num_entries_to_generate, augmentation done and can't exit the allocation.
+
+    let logical_floor: u32 = 1;
+    // below 100 entries the 1% would round down to nothing
+    let logical_ceiling = ((num_entries_to_generate as f32 * 0.01) as u32).max(logical_floor);
+
+    let mut sum = 0;
+    // [deprecated] used to create `empty` datapoints, but not make sense for most use case, hence simply drop it.
+    // let mut done_allocation = false;
+    // let mut early_log = false;
+    for i in 0..duration_in_seconds {
+        let mut rows_to_add = random_range(logical_floor..=logical_ceiling);
+        // guard check; the last slot absorbs whatever the (too short) window has not filled yet
+        if sum + rows_to_add > num_entries_to_generate || i == duration_in_seconds - 1 {
+            rows_to_add = num_entries_to_generate - sum;
+            sum = num_entries_to_generate;
+        } else {
+            sum += rows_to_add;
+        }
+        // push a datapoint
+        // even though empty rows_to_add, must still have a datapoint
+        datapoints.push(DataPoint {
+            timestamp: start_time + Duration::seconds(i),
+            rows_to_add: slot_rows(rows_to_add, i as usize, "early_fill")?,
+            anomaly: None,
+            warmup: false,
+        });
+        if sum == num_entries_to_generate {
+            // [log]
+            tracing::info!(
+                module = "augmentation",
+                model = "early_fill",
+                slot = i,
+                total = num_entries_to_generate,
+                empty_slots = duration_in_seconds - i - 1,
+                "all entries early filled"
+            );
+            break;
+        } // end - if (sum == num_entries_to_generate)
+    }
+    Ok(())
+}
+
+fn generate_datapoints_sparse_fill(
+    ctx: &GenerationContext,
+) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+    // create a random number of `zones`;
+    //   each zone would be allocated a number of datapoints to be generated. (also another random value based on num_entries_to_generate)
+    // there would be a random gap between the `zones`; could be 0 - adjacent with the previous zone. Or could be a random number of seconds (etc)
+    //   however, the last zone's outer boundary must be touching the the last datapoint's timestamp.
+    //   hence the logic would make sense in this way
+    //   - calculate the first zone's boundaries
+    //   - calculate the last zone's boundaries
+    //   - the residual boundary would be shared with the remaining zone(s).
+    //   - each zone would be allocated a random rows_to_add value based on num_entries_to_generate.
+
+    let zone_slots = generate_sparse_fill_zones(
+        ctx.start_time,
+        ctx.duration_in_seconds,
+        ctx.num_entries_to_generate,
+        ctx.cfg
+            .sparse_min_zones()
+            .unwrap_or(DEFAULT_SPARSE_FILL_MIN_ZONES),
+        ctx.cfg
+            .sparse_max_zones()
+            .unwrap_or(DEFAULT_SPARSE_FILL_MAX_ZONES),
+        ctx.cfg
+            .sparse_generation_factor()
+            .unwrap_or(DEFAULT_SPARSE_FILL_ZONE_GENERATION_FACTOR),
+        SparsePlacement::from_config(ctx.cfg)?,
+    )?;
+    // loop through; if DataZone.num_rows_to_add > 0; call fn to add back DataPoint(s)
+    // hence the output would be a bunch of datapoints in which there would be gap(s) in the timestamp
+    // (since there are zones without data being generated)
+    let mut datapoints = vec![];
+    for zone in zone_slots {
+        if zone.num_rows_to_add > 0 {
+            let mut updated_datapoints = generate_sparse_fill_zone_datapoints(&zone)?;
+            datapoints.append(&mut updated_datapoints);
+        }
+    }
+    Ok(datapoints)
+}
+
+/// add a zero-row datapoint for every second of the window without a datapoint, so the
+/// datapoints (sorted by timestamp) cover the whole window second by second.
+fn fill_gap_datapoints(
+    datapoints: &mut Vec<DataPoint>,
+    start_time: DateTime<Utc>,
+    duration_in_seconds: i64,
+) {
+    let covered: HashSet<i64> = datapoints
+        .iter()
+        .map(|datapoint| (datapoint.timestamp - start_time).num_seconds())
+        .collect();
+    for second in 0..duration_in_seconds {
+        if !covered.contains(&second) {
+            datapoints.push(DataPoint::new(start_time + Duration::seconds(second), 0));
+        }
+    }
+    datapoints.sort_by_key(|datapoint| datapoint.timestamp);
+}
+
+/// create `min_zones..=max_zones` (random) data zones with their allocated rows,
+/// placed among the empty zones (gaps).
+fn generate_sparse_fill_zones(
+    start_time: DateTime<Utc>,
+    duration_in_seconds: i64,
+    num_entries_to_generate: u32,
+    min_zones: u32,
+    max_zones: u32,
+    generation_factor: u32,
+    placement: SparsePlacement,
+) -> Result<Vec<DataZone>, Box<dyn std::error::Error>> {
+    if generation_factor < 1 {
+        return Err(format!(
+            "sparse_generation_factor must be >= 1, got [{}]",
+            generation_factor
+        )
+        .into());
+    }
+    if min_zones < 1 || max_zones < 1 || min_zones > max_zones {
+        return Err(format!(
+            "sparse_min_zones [{}] and sparse_max_zones [{}] must be >= 1 and min <= max",
+            min_zones, max_zones
+        )
+        .into());
+    }
+    let mut num_of_zone = random_range(min_zones..=max_zones);
+    // more zones than entries would leave all but the last zone empty; at least 1 entry per zone
+    if num_entries_to_generate < num_of_zone {
+        tracing::info!(
+            module = "augmentation",
+            model = "sparse_fill",
+            zones = num_of_zone,
+            reduced_zones = num_entries_to_generate,
+            total = num_entries_to_generate,
+            "zones reduced; fewer entries than zones"
+        );
+        num_of_zone = num_entries_to_generate;
+    }
+    // every (zone or gap) bucket needs at least 1 second; fewer zones / gaps for a short window
+    let max_buckets = duration_in_seconds.max(1) as u32;
+    let mut generation_factor = generation_factor;
+    if num_of_zone * generation_factor > max_buckets {
+        let reduced_zones = (max_buckets / generation_factor).clamp(1, num_of_zone);
+        let reduced_factor = (max_buckets / reduced_zones).min(generation_factor);
+        tracing::info!(
+            module = "augmentation",
+            model = "sparse_fill",
+            zones = num_of_zone,
+            factor = generation_factor,
+            reduced_zones,
+            reduced_factor,
+            seconds = duration_in_seconds,
+            "zones reduced; fewer seconds than zone buckets"
+        );
+        num_of_zone = reduced_zones;
+        generation_factor = reduced_factor;
+    }
+    let zone_allocation_ceiling = num_entries_to_generate / num_of_zone;
+    let mut zone_allocations: Vec<u32> = vec![];
+
+    // first fill for zone_allocations
+    let mut sum = 0;
+    for i in 0..num_of_zone {
+        if i == num_of_zone - 1 {
+            zone_allocations.push(num_entries_to_generate - sum);
+            break;
+        } else {
+            zone_allocations.push(zone_allocation_ceiling);
+        }
+        sum += zone_allocation_ceiling;
+    }
+    // shuffling
+    // - based on num_of_zone * 5 times of shuffle (nothing to shuffle with a single zone)
+    for _ in 0..num_of_zone * 5 {
+        if num_of_zone < 2 {
+            break;
+        }
+        let (first_slot, second_slot) = pick_2_random_datapoint(num_of_zone as i64);
+        // generate a random delta
+        let upper_bound = zone_allocations[first_slot as usize];
+        if upper_bound < 2 {
+            continue;
+        }
+        let delta = random_range(1..upper_bound);
+
+        zone_allocations[first_slot as usize] -= delta;
+        zone_allocations[second_slot as usize] += delta;
+    }
+    // [log]
+    tracing::debug!(
+        module = "augmentation",
+        model = "sparse_fill",
+        zones = num_of_zone,
+        rows = ?zone_allocations,
+        "zone rows after shuffle"
+    );
+
+    // logic of slots...
+    // - num_of_zones = 6 -> slots available = num_of_zones * 6 = 36;
+    // - each slots boundary is the result of an even value of the duration_in_seconds; ie. duration_in_seconds / num_of_zone_slots (36 in this case);
     // - now each zone would pick 1 or more slots; should say a random slot occupancy per zone is calculated.
     // - But worst case is per zone would have occupied at least 1 slot.
     // - which means per zone would need to calculate the following
@@ -341,476 +2480,2656 @@ fn generate_datapoints_sparse_fill(
     // - do nothing since it is a Gap
     // - allocate the rows_to_add value evenly
 
-    // next -> zone slots and how to divide it (duration_in_seconds / (num_of_zone * 6))
-    let zone_slots = generate_sparse_fill_zone_and_boundaries(
-        &zone_allocations,
-        DEFAULT_SPARSE_FILL_ZONE_GENERATION_FACTOR,
-        start_time,
-        duration_in_seconds,
-    );
-    // loop through; if DataZone.num_rows_to_add > 0; call fn to add back DataPoint(s)
-    // hence the output would be a bunch of datapoints in which there would be gap(s) in the timestamp
-    // (since there are zones without data being generated)
-    for zone in zone_slots {
-        if zone.num_rows_to_add > 0 {
-            let mut updated_datapoints = generate_sparse_fill_zone_datapoints(&zone);
-            datapoints.append(&mut updated_datapoints);
+    // next -> zone slots and how to divide it (duration_in_seconds / (num_of_zone * 6))
+    let zone_slots = generate_sparse_fill_zone_and_boundaries(
+        &zone_allocations,
+        generation_factor,
+        placement,
+        start_time,
+        duration_in_seconds,
+    );
+    Ok(zone_slots)
+}
+
+fn generate_sparse_fill_zone_and_boundaries(
+    data_zones_to_be_generated: &[u32],
+    generation_factor: u32,
+    placement: SparsePlacement,
+    start_time: DateTime<Utc>,
+    duration_in_seconds: i64,
+) -> Vec<DataZone> {
+    // eg. generation_factor = 6
+    // num_of_data_zones = data_zones_to_be_generated.len() = 5
+    // size of vec would be 6*5 = 30; out 5 would be occupied
+    let data_zones_len = generation_factor as usize * data_zones_to_be_generated.len();
+    let mut data_zones: Vec<DataZone> = vec![
+        DataZone::new();
+        // DataZone {
+        //     start_time: Utc::now(),
+        //     end_time: Utc::now(),
+        //     num_rows_to_add: 0,
+        // };
+        data_zones_len
+    ];
+    // first iteration; fill up start_time, end_time
+    let zone_span = duration_in_seconds / data_zones_len as i64;
+    // for zone in data_zones.iter_mut() {
+    for (zone_idx, zone) in data_zones.iter_mut().enumerate() {
+        zone.start_time = start_time + Duration::seconds(zone_span * zone_idx as i64);
+
+        if zone_idx == data_zones_len - 1 {
+            zone.end_time = start_time + Duration::seconds(duration_in_seconds);
+        } else {
+            zone.end_time = zone.start_time + Duration::seconds(zone_span - 1);
+        }
+    }
+    // pick which zone to fill and which not
+    match placement {
+        SparsePlacement::Random => {
+            for zone in data_zones_to_be_generated.iter() {
+                loop {
+                    let idx = random_range(0..data_zones.len());
+                    if data_zones[idx].num_rows_to_add == 0 {
+                        data_zones[idx].num_rows_to_add = *zone;
+                        break;
+                    }
+                }
+            }
+        }
+        // the middle bucket of every `generation_factor` buckets; i.e. evenly spaced
+        SparsePlacement::Spread => {
+            for (zone_idx, zone) in data_zones_to_be_generated.iter().enumerate() {
+                let idx = zone_idx * generation_factor as usize + generation_factor as usize / 2;
+                data_zones[idx].num_rows_to_add = *zone;
+            }
+        }
+    }
+    data_zones
+}
+
+#[derive(Clone, Debug)]
+struct DataZone {
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    num_rows_to_add: u32,
+}
+
+impl DataZone {
+    fn new() -> DataZone {
+        DataZone {
+            start_time: Utc::now(),
+            end_time: Utc::now(),
+            num_rows_to_add: 0,
+        }
+    }
+}
+
+fn generate_sparse_fill_zone_datapoints(
+    data_zone: &DataZone,
+) -> Result<Vec<DataPoint>, Box<dyn std::error::Error>> {
+    let mut data_points = Vec::new();
+    // calculate the duration; a 1 second zone still gets its datapoint
+    let duration = (data_zone.end_time.timestamp() - data_zone.start_time.timestamp()).max(1);
+    // [trace] make it a trace after dev completed
+    tracing::debug!(
+        module = "augmentation",
+        model = "sparse_fill",
+        seconds = data_zone.end_time.timestamp() - data_zone.start_time.timestamp(),
+        rows = data_zone.num_rows_to_add,
+        "data zone"
+    );
+    let mut rows_to_add_per_second = data_zone.num_rows_to_add / duration as u32;
+    let mut sum = 0;
+    // first fill with equal num of rows
+    for i in 0..duration {
+        // last entry
+        if i == duration - 1 {
+            rows_to_add_per_second = data_zone.num_rows_to_add - sum;
+        }
+        data_points.push(DataPoint {
+            timestamp: data_zone.start_time + Duration::seconds(i),
+            rows_to_add: slot_rows(rows_to_add_per_second, i as usize, "sparse_fill")?,
+            anomaly: None,
+            warmup: false,
+        });
+        sum += rows_to_add_per_second;
+    }
+    // second fill is shuffling by a factor of duration * 3 (nothing to shuffle with a single datapoint);
+    for _ in 0..duration * 3 {
+        if data_points.len() < 2 {
+            break;
+        }
+        let (idx_1, idx_2) = pick_2_random_datapoint(data_points.len() as i64);
+        let rows_available = data_points[idx_1 as usize].rows_to_add;
+        if rows_available < 2 {
+            continue;
+        }
+        // never beyond the per-datapoint range of the receiving datapoint
+        let delta =
+            random_range(1..rows_available).min(i16::MAX - data_points[idx_2 as usize].rows_to_add);
+
+        data_points[idx_1 as usize].rows_to_add -= delta;
+        data_points[idx_2 as usize].rows_to_add += delta;
+    }
+    Ok(data_points)
+}
+
+fn generate_datapoints_bimodal(
+    ctx: &GenerationContext,
+    datapoints: &mut Vec<DataPoint>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (start_time, duration_in_seconds, num_entries_to_generate) = (
+        ctx.start_time,
+        ctx.duration_in_seconds,
+        ctx.num_entries_to_generate,
+    );
+    let peak1 = ctx.cfg.peak1().unwrap_or(DEFAULT_BIMODAL_PEAK1);
+    let peak2 = ctx.cfg.peak2().unwrap_or(DEFAULT_BIMODAL_PEAK2);
+    let peak1_ratio = ctx.cfg.peak1_ratio().unwrap_or(DEFAULT_BIMODAL_PEAK1_RATIO);
+    for (name, value) in [
+        ("peak1", peak1),
+        ("peak2", peak2),
+        ("peak1_ratio", peak1_ratio),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!("{} must be within [0, 1], got [{}]", name, value).into());
+        }
+    }
+    // split the entries between the 2 humps; the 2nd hump takes the remainder so the sum stays exact.
+    let peak1_entries = (num_entries_to_generate as f64 * peak1_ratio).round() as u32;
+    let peak2_entries = num_entries_to_generate - peak1_entries;
+
+    // each hump apportioned exactly on its own, then stacked
+    let num_slots = duration_in_seconds.max(0) as usize;
+    let slots: Vec<u32> = distribute_exact(peak1_entries, &gaussian_hump_weights(num_slots, peak1))
+        .iter()
+        .zip(distribute_exact(peak2_entries, &gaussian_hump_weights(num_slots, peak2)).iter())
+        .map(|(rows1, rows2)| rows1 + rows2)
+        .collect();
+
+    for (i, rows_to_add) in slots.iter().enumerate() {
+        datapoints.push(DataPoint {
+            timestamp: start_time + Duration::seconds(i as i64),
+            rows_to_add: slot_rows(*rows_to_add, i, "bimodal")?,
+            anomaly: None,
+            warmup: false,
+        });
+    }
+    Ok(())
+}
+
+/// the (normalized; sums up to 1) weights of a gaussian hump centered at the fractional `peak`
+/// position of the slots.
+fn gaussian_hump_weights(num_slots: usize, peak: f64) -> Vec<f64> {
+    if num_slots == 0 {
+        return vec![];
+    }
+    let center = peak * (num_slots - 1) as f64;
+    let sigma = (num_slots as f64 * DEFAULT_BIMODAL_PEAK_WIDTH).max(1.0);
+    let weights: Vec<f64> = (0..num_slots)
+        .map(|i| {
+            let distance = (i as f64 - center) / sigma;
+            (-0.5 * distance * distance).exp()
+        })
+        .collect();
+    let weights_sum: f64 = weights.iter().sum();
+    weights.iter().map(|weight| weight / weights_sum).collect()
+}
+
+fn generate_datapoints_decay(
+    ctx: &GenerationContext,
+    datapoints: &mut Vec<DataPoint>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (start_time, duration_in_seconds, num_entries_to_generate) = (
+        ctx.start_time,
+        ctx.duration_in_seconds,
+        ctx.num_entries_to_generate,
+    );
+    if duration_in_seconds <= 0 {
+        return Ok(());
+    }
+    // rows_to_add follows A * e^(-lambda * t); by default lambda is picked so that the last slot is ~1% of the first.
+    let lambda = match *ctx.cfg.decay_lambda() {
+        Some(lambda) if lambda < 0.0 => {
+            return Err(format!("decay_lambda must be non-negative, got [{}]", lambda).into());
+        }
+        Some(lambda) => lambda,
+        None if duration_in_seconds == 1 => 0.0,
+        None => -DEFAULT_DECAY_LAST_TO_FIRST_RATIO.ln() / (duration_in_seconds - 1) as f64,
+    };
+    let weights: Vec<f64> = (0..duration_in_seconds)
+        .map(|t| (-lambda * t as f64).exp())
+        .collect();
+    let mut slots = distribute_exact(num_entries_to_generate, &weights);
+    // the largest remainders could bump a later slot 1 row above an earlier one;
+    // sorting restores the non-increasing shape without changing the sum.
+    slots.sort_unstable_by(|a, b| b.cmp(a));
+
+    for (i, rows_to_add) in slots.iter().enumerate() {
+        datapoints.push(DataPoint {
+            timestamp: start_time + Duration::seconds(i as i64),
+            rows_to_add: slot_rows(*rows_to_add, i, "decay")?,
+            anomaly: None,
+            warmup: false,
+        });
+    }
+    Ok(())
+}
+
+fn generate_datapoints_step(
+    ctx: &GenerationContext,
+    datapoints: &mut Vec<DataPoint>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (start_time, duration_in_seconds, num_entries_to_generate) = (
+        ctx.start_time,
+        ctx.duration_in_seconds,
+        ctx.num_entries_to_generate,
+    );
+    let steps = ctx.cfg.steps().as_deref().unwrap_or_default();
+    let mut slots = generate_step_slot_rates(steps, duration_in_seconds)?;
+    if slots.is_empty() {
+        return Ok(());
+    }
+    // the final slot corrects the remainder so the sum equals num_entries_to_generate
+    let last_idx = slots.len() - 1;
+    let sum_before_last: u64 = slots[..last_idx].iter().map(|rate| *rate as u64).sum();
+    if sum_before_last > num_entries_to_generate as u64 {
+        return Err(format!(
+            "steps require at least {} rows which exceeds number_of_entries {}",
+            sum_before_last, num_entries_to_generate
+        )
+        .into());
+    }
+    slots[last_idx] = num_entries_to_generate - sum_before_last as u32;
+
+    for (i, rows_to_add) in slots.iter().enumerate() {
+        datapoints.push(DataPoint {
+            timestamp: start_time + Duration::seconds(i as i64),
+            rows_to_add: slot_rows(*rows_to_add, i, "step")?,
+            anomaly: None,
+            warmup: false,
+        });
+    }
+    Ok(())
+}
+
+/// the counts of a `timestamp,count` CSV profile in file order; blank lines and a header line are skipped.
+fn read_profile_counts(profile_path: &str) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(profile_path)
+        .map_err(|e| format!("failed to read the profile [{}]: {}", profile_path, e))?;
+    let mut counts = vec![];
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let count = match line.split_once(',') {
+            Some((_, count)) => count.trim(),
+            None => {
+                return Err(format!(
+                    "invalid profile row [{}] at line {}; expecting `timestamp,count`",
+                    line,
+                    idx + 1
+                )
+                .into())
+            }
+        };
+        match count.parse::<f64>() {
+            Ok(count) if count.is_finite() && count >= 0.0 => counts.push(count),
+            // the header line
+            Err(_) if idx == 0 => continue,
+            _ => {
+                return Err(format!(
+                    "invalid profile count [{}] at line {}; expecting a non-negative number",
+                    count,
+                    idx + 1
+                )
+                .into())
+            }
+        }
+    }
+    if counts.iter().sum::<f64>() <= 0.0 {
+        return Err(format!("the profile [{}] has no counts", profile_path).into());
+    }
+    Ok(counts)
+}
+
+/// the inline `weights` must be non-negative numbers with at least 1 positive weight.
+fn check_weights(weights: &[f64]) -> Result<(), Box<dyn std::error::Error>> {
+    for (idx, weight) in weights.iter().enumerate() {
+        if !weight.is_finite() || *weight < 0.0 {
+            return Err(format!(
+                "invalid weight [{}] at index {}; expecting a non-negative number",
+                weight, idx
+            )
+            .into());
+        }
+    }
+    if weights.iter().sum::<f64>() <= 0.0 {
+        return Err("the `weights` have no positive weight".into());
+    }
+    Ok(())
+}
+
+/// Also the generation of the `weights` model; the counts are the weights as is (the `model` names
+/// the one reported in the errors).
+fn generate_datapoints_replay_csv(
+    ctx: &GenerationContext,
+    model: &str,
+    counts: &[f64],
+    datapoints: &mut Vec<DataPoint>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (start_time, duration_in_seconds, num_entries_to_generate) = (
+        ctx.start_time,
+        ctx.duration_in_seconds,
+        ctx.num_entries_to_generate,
+    );
+    let num_slots = duration_in_seconds.max(0) as usize;
+    let slots = distribute_exact(
+        num_entries_to_generate,
+        &resample_profile_weights(counts, num_slots),
+    );
+
+    for (i, rows_to_add) in slots.iter().enumerate() {
+        datapoints.push(DataPoint {
+            timestamp: start_time + Duration::seconds(i as i64),
+            rows_to_add: slot_rows(*rows_to_add, i, model)?,
+            anomaly: None,
+            warmup: false,
+        });
+    }
+    Ok(())
+}
+
+/// Resample the profile counts onto `num_slots` slots.
+///
+/// The profile is taken as a step function stretched over the whole window; every slot weighs the
+/// area of the profile it covers. Hence a profile with fewer points than slots is spread over
+/// several slots per point, and one with more points sums up several points per slot (no point
+/// is skipped).
+fn resample_profile_weights(counts: &[f64], num_slots: usize) -> Vec<f64> {
+    let num_points = counts.len();
+    if num_points == 0 {
+        return vec![0.0; num_slots];
+    }
+    let points_per_slot = num_points as f64 / num_slots as f64;
+    (0..num_slots)
+        .map(|slot| {
+            // the slot covers [from, to) in units of profile points
+            let from = slot as f64 * points_per_slot;
+            let to = (slot + 1) as f64 * points_per_slot;
+            let mut weight = 0.0;
+            let mut point = from.floor() as usize;
+            while (point as f64) < to && point < num_points {
+                let overlap = to.min((point + 1) as f64) - from.max(point as f64);
+                weight += counts[point] * overlap.max(0.0);
+                point += 1;
+            }
+            weight
+        })
+        .collect()
+}
+
+/// the per-slot rate of the steps; slots before the 1st step get 0 rows.
+fn generate_step_slot_rates(
+    steps: &[ConfigStep],
+    duration_in_seconds: i64,
+) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+    if steps.is_empty() {
+        return Err("the step distribution model requires at least 1 entry in `steps`".into());
+    }
+    let mut slots: Vec<u32> = vec![0; duration_in_seconds.max(0) as usize];
+    let mut previous_at: Option<i64> = None;
+    for step in steps {
+        let at = parse_time_duration(step.at().clone())?.num_seconds();
+        if at < 0 || at >= duration_in_seconds {
+            return Err(format!(
+                "step at [{}] is outside of the generation window ({} seconds)",
+                step.at(),
+                duration_in_seconds
+            )
+            .into());
+        }
+        if previous_at.is_some_and(|previous_at| at <= previous_at) {
+            return Err(format!("steps must be in increasing `at` order, got [{}]", step.at()).into());
+        }
+        previous_at = Some(at);
+        // the later step simply overwrites the rates from its boundary onwards
+        for slot in slots.iter_mut().skip(at as usize) {
+            *slot = *step.rate();
+        }
+    }
+    Ok(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::app_init;
+    use crate::config::{generation_test_config, ConfigAnomaly, ConfigRepeat, ConfigStep};
+
+    #[test]
+    fn test_parse_time_duration_value_and_unit() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let result = parse_time_duration_value_and_unit("10m".to_string());
+        assert_eq!(result.is_some(), true);
+        assert_eq!(result.as_ref().unwrap().0, 10.0);
+        assert_eq!(result.as_ref().unwrap().1, "m".to_string());
+
+        // for invalid values... it still parse as is...
+        let result = parse_time_duration_value_and_unit("10m3d".to_string());
+        assert_eq!(result.is_some(), true);
+        assert_eq!(result.as_ref().unwrap().0, 10.0);
+        assert_eq!(result.as_ref().unwrap().1, "m3d".to_string());
+
+        // fractional values
+        let result = parse_time_duration_value_and_unit("1.5h".to_string());
+        assert_eq!(result, Some((1.5, "h".to_string())));
+
+        // a space in between the value and the unit
+        let result = parse_time_duration_value_and_unit(" 10 m ".to_string());
+        assert_eq!(result, Some((10.0, "m".to_string())));
+
+        // totally non-parsable value will yield NONE
+        let result = parse_time_duration_value_and_unit("m10".to_string());
+        assert_eq!(result.is_some(), false);
+        assert_eq!(result.is_none(), true);
+    }
+
+    #[test]
+    fn test_parse_time_duration() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let result = parse_time_duration("10m".to_string());
+        assert_eq!(result.is_ok(), true);
+        assert_eq!(
+            result.as_ref().unwrap().num_nanoseconds().unwrap(),
+            Duration::minutes(10).num_nanoseconds().unwrap()
+        );
+
+        let result = parse_time_duration("10s".to_string());
+        assert_eq!(result.is_ok(), true);
+        assert_eq!(
+            result.as_ref().unwrap().num_nanoseconds().unwrap(),
+            Duration::seconds(10).num_nanoseconds().unwrap()
+        );
+
+        let result = parse_time_duration("200ms".to_string());
+        assert_eq!(result.unwrap(), Duration::milliseconds(200));
+
+        // fractional values
+        assert_eq!(
+            parse_time_duration("1.5h".to_string()).unwrap(),
+            Duration::minutes(90)
+        );
+        assert_eq!(
+            parse_time_duration("0.5d".to_string()).unwrap(),
+            Duration::hours(12)
+        );
+        assert_eq!(
+            parse_time_duration("1.5x".to_string())
+                .err()
+                .unwrap()
+                .to_string(),
+            "invalid time duration unit [x]"
+        );
+
+        // too large for a Duration; no overflow
+        assert_eq!(
+            parse_time_duration("100000000d".to_string())
+                .err()
+                .unwrap()
+                .to_string(),
+            "time duration [100000000d] is too large"
+        );
+        assert!(parse_time_duration("106752.5d".to_string()).is_err());
+        assert_eq!(
+            parse_time_duration("106751d".to_string()).unwrap(),
+            Duration::days(106751)
+        );
+
+        // a space in between the value and the unit
+        assert_eq!(
+            parse_time_duration("10 m".to_string()).unwrap(),
+            Duration::minutes(10)
+        );
+        assert_eq!(
+            parse_time_duration("1 h".to_string()).unwrap(),
+            Duration::hours(1)
+        );
+        // still invalid
+        for value in ["1 0m", "10 m m", " m", "10"] {
+            assert!(parse_time_duration(value.to_string()).is_err(), "{}", value);
+        }
+
+        // totally not parsable value
+        let result = parse_time_duration("f10m".to_string());
+        assert_eq!(result.is_ok(), false);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "failed to parse time duration value and unit"
+        );
+    }
+
+    // generate_time_range()
+    // create an artifial Config struct with combos to test around
+    #[test]
+    fn test_generate_time_range() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+
+        tracing::trace!("config: {:#?}", cfg);
+
+        // [case][01] not using NOW(), provide a valid timestamp_format + start_timestamp
+        let result = generate_time_range(&cfg);
+        if result.is_err() {
+            assert_eq!(result.err().unwrap().to_string(), "whay?");
+            return;
+        }
+        // [lesson] work... but hard to understood the nanoseconds value for comparison
+        //assert_eq!(result.as_ref().unwrap().0, 1640995200000); // 2022-01-01T00:00:00.000Z
+        //assert_eq!(result.as_ref().unwrap().1, 1640995201000); // 2022-01-01T00:00:10.000Z
+        let mut start_time_test: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let mut end_time_test: DateTime<Utc> = start_time_test + Duration::minutes(10);
+        assert_eq!(
+            result.as_ref().unwrap().0.timestamp_millis(),
+            start_time_test.timestamp_millis()
+        );
+        assert_eq!(
+            result.as_ref().unwrap().1.timestamp_millis(),
+            end_time_test.timestamp_millis()
+        );
+
+        // [case][02] not using NOW(), provide a in-valid timestamp_format + start_timestamp
+        cfg.set_timestamp_format(Some("invalid-simply".to_string()));
+        let result = generate_time_range(&cfg);
+        if result.is_err() {
+            // failed to parse start_timestamp [2022-01-01T00:00:00.000+00:00] with format [invalid-simply]: input contains invalid characters
+            assert_eq!(
+                result
+                    .err()
+                    .unwrap()
+                    .to_string()
+                    .find("input contains invalid characters")
+                    .is_some(),
+                true
+            );
+        }
+
+        // [case][03] not using NOW(), provide a valid timestamp_format + in-Valid start_timestamp
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+        cfg.set_start_timestamp(Some("invalid-timestamp-value".to_string()));
+        let result = generate_time_range(&cfg);
+        if result.is_err() {
+            // failed to parse start_timestamp [2022-01-01T00:00:00.000+00:00] with format [invalid-simply]: input contains invalid characters
+            assert_eq!(
+                result
+                    .err()
+                    .unwrap()
+                    .to_string()
+                    .find("input contains invalid characters")
+                    .is_some(),
+                true
+            );
+        }
+
+        // [case][04] using NOW(), compare with current time
+        // (discrepancies should be within 1 seconds, the start_time_test should be roughly 1 sec after the acutal call)
+        //cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+        //cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        cfg.set_use_now_as_timestamp(Some(true));
+        start_time_test = Utc::now();
+        end_time_test = start_time_test + Duration::minutes(10);
+        let result = generate_time_range(&cfg);
+        if result.is_err() {
+            assert_eq!(result.err().unwrap().to_string(), "huh?");
+            return;
+        }
+        let start_diff =
+            result.as_ref().unwrap().0.timestamp_millis() - start_time_test.timestamp_millis();
+        let end_diff =
+            result.as_ref().unwrap().1.timestamp_millis() - end_time_test.timestamp_millis();
+        assert_eq!(start_diff >= 0 && start_diff <= 1000, true);
+        assert_eq!(end_diff >= 0 && end_diff <= 1000, true);
+
+        // [case][05] zero duration; an empty window unless the single-instant mode is requested
+        cfg.set_use_now_as_timestamp(Some(false));
+        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        cfg.set_generation_duration(Some("0m".to_string()));
+        let result = generate_time_range(&cfg);
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .starts_with("generation window is empty or inverted"));
+        cfg.set_single_instant(Some(true));
+        let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+        assert_eq!(start_time, end_time);
+        // exactly 1 datapoint at the start carrying all the entries
+        cfg.set_distribution_by(Some("bimodal".to_string()));
+        cfg.set_number_of_entries(Some(1000));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 1);
+        assert_eq!(datapoints[0].timestamp, start_time);
+        assert_eq!(datapoints[0].rows_to_add, 1000);
+        cfg.set_number_of_entries(Some(40000));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "single_instant carries the number_of_entries [40000] in 1 datapoint; at most 32767"
+        );
+
+        // [case][06] inverted window; never valid
+        let result = check_time_range(end_time_test, start_time_test, true);
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .starts_with("generation window is empty or inverted"));
+
+        // [case][07] date-only format; midnight UTC
+        let midnight: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        cfg.set_single_instant(None);
+        cfg.set_generation_duration(Some("10m".to_string()));
+        for (timestamp_format, start_timestamp) in
+            [("%Y-%m-%d", "2022-01-01"), ("%d/%m/%Y", "01/01/2022")]
+        {
+            cfg.set_timestamp_format(Some(timestamp_format.to_string()));
+            cfg.set_start_timestamp(Some(start_timestamp.to_string()));
+            let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+            assert_eq!(start_time, midnight);
+            assert_eq!(end_time, midnight + Duration::minutes(10));
+        }
+        cfg.set_start_timestamp(Some("2022-13-01".to_string()));
+        assert!(generate_time_range(&cfg).is_err());
+        // the full datetime path is unchanged
+        assert!(!is_date_only_format("%Y-%m-%dT%H:%M:%S%.f%:z"));
+        assert!(!is_date_only_format("%Y-%m-%d %-H:%M%z"));
+        assert!(!is_date_only_format("%s"));
+        assert!(is_date_only_format("%Y-%m-%d (%%H)"));
+
+        // [case][08] offset-less timestamp and format; taken as UTC
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S".to_string()));
+        cfg.set_start_timestamp(Some("2022-01-01T00:00:00".to_string()));
+        let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+        assert_eq!(start_time, midnight);
+        assert_eq!(end_time, midnight + Duration::minutes(10));
+        // an offset-bearing timestamp is kept as is
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%:z".to_string()));
+        cfg.set_start_timestamp(Some("2022-01-01T08:00:00+08:00".to_string()));
+        assert_eq!(generate_time_range(&cfg).unwrap().0, midnight);
+        // the format expects an offset the timestamp lacks; still an error
+        cfg.set_start_timestamp(Some("2022-01-01T00:00:00".to_string()));
+        assert!(generate_time_range(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_even_rounding() {
+        // 10003 rows into 600 slots; 16 each with a remainder of 403
+        let spread = even_slot_counts(10003, 600, Rounding::Spread);
+        assert_eq!(spread.iter().sum::<u32>(), 10003);
+        assert!(spread.iter().max().unwrap() - spread.iter().min().unwrap() <= 1);
+        // the first 403 slots get 1 extra each
+        assert!(spread[..403].iter().all(|rows| *rows == 17));
+        assert!(spread[403..].iter().all(|rows| *rows == 16));
+
+        let last_slot = even_slot_counts(10003, 600, Rounding::LastSlot);
+        assert_eq!(last_slot.iter().sum::<u32>(), 10003);
+        assert_eq!(last_slot[599], 16 + 403);
+        assert!(last_slot[..599].iter().all(|rows| *rows == 16));
+
+        // the earlier slots exceed the total (e.g. 7 rounded up from 6.6 per slot); the would-be
+        // negative last slot is trimmed from the earlier slots instead
+        let mut slots = vec![7; 10];
+        fill_last_slot(&mut slots, 60);
+        assert_eq!(slots.iter().sum::<u32>(), 60);
+        assert_eq!(slots, vec![7, 7, 7, 7, 7, 7, 7, 7, 4, 0]);
+        let mut slots = vec![50, 3, 3, 0];
+        fill_last_slot(&mut slots, 51);
+        assert_eq!(slots, vec![50, 1, 0, 0]);
+        let mut slots = vec![3, 3, 3];
+        fill_last_slot(&mut slots, 10);
+        assert_eq!(slots, vec![3, 3, 4]);
+
+        let mut cfg = Config::new();
+        assert_eq!(Rounding::from_config(&cfg).unwrap(), Rounding::LastSlot);
+        cfg.set_rounding(Some("Spread".to_string()));
+        assert_eq!(Rounding::from_config(&cfg).unwrap(), Rounding::Spread);
+        cfg.set_rounding(Some("Last_Slot".to_string()));
+        assert_eq!(Rounding::from_config(&cfg).unwrap(), Rounding::LastSlot);
+        cfg.set_rounding(Some("floor".to_string()));
+        assert_eq!(
+            Rounding::from_config(&cfg).err().unwrap().to_string(),
+            "unknown rounding [floor]; expecting \"last_slot\" or \"spread\""
+        );
+    }
+
+    #[test]
+    fn test_pick_2_random_datapoint() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        for _ in 0..20 {
+            let result = pick_2_random_datapoint(1000);
+            assert_eq!(result.0 != result.1, true);
+
+            tracing::trace!("{} vs {}", result.0, result.1);
+        }
+    }
+
+    #[test]
+    fn test_generate_datapoints_even() {
+        let cfg = generation_test_config("even", 10000, "10m");
+
+        let result = generate_datapoints(&cfg);
+        assert_eq!(result.is_err(), false);
+        tracing::trace!("{:?}", result.as_ref().unwrap());
+
+        let mut histogram = String::new();
+        let datapoints = result.as_ref().unwrap();
+        for datapoint in datapoints {
+            // [debug]
+            // [graph - histogram]
+            histogram.push_str(format!("timestamp: {} | ", datapoint.timestamp).as_str());
+            for _ in 0..datapoint.rows_to_add {
+                histogram.push_str(".");
+            }
+            histogram.push_str("\n");
+        }
+        let sum = total_rows(datapoints).unwrap();
+        tracing::info!("\n{}", histogram);
+        assert_eq!(sum, cfg.number_of_entries().unwrap() as u64);
+    }
+
+    #[test]
+    fn test_generation_context_even() {
+        let mut cfg = Config::new();
+        cfg.set_rounding(Some("last_slot".to_string()));
+        let start_time = Utc::now();
+        let ctx = GenerationContext::new(&cfg, start_time, 60, 1000);
+        assert_eq!(ctx.start_time(), start_time);
+        assert_eq!(ctx.duration_in_seconds(), 60);
+        assert_eq!(ctx.num_entries_to_generate(), 1000);
+
+        let mut datapoints = vec![];
+        generate_datapoints_even(&ctx, &mut datapoints).unwrap();
+        assert_eq!(datapoints.len(), 60);
+        assert_eq!(datapoints[0].timestamp, start_time);
+        assert_eq!(datapoints[59].timestamp, start_time + Duration::seconds(59));
+        assert_eq!(total_rows(&datapoints).unwrap(), 1000);
+
+        // the registered model fills through the very same context
+        let mut filled = vec![];
+        EvenModel.fill(&ctx, &mut filled).unwrap();
+        assert_eq!(total_rows(&filled).unwrap(), 1000);
+    }
+
+    #[test]
+    fn test_generate_datapoints_early_fill() {
+        let cfg = generation_test_config("early_fill", 10000, "10m");
+
+        let result = generate_datapoints(&cfg);
+        assert_eq!(result.is_err(), false);
+        tracing::trace!("{:?}", result.as_ref().unwrap());
+
+        let mut histogram = String::new();
+        let datapoints = result.as_ref().unwrap();
+        for datapoint in datapoints {
+            // [debug]
+            // [graph - histogram]
+            histogram.push_str(format!("timestamp: {} | ", datapoint.timestamp).as_str());
+            for _ in 0..datapoint.rows_to_add {
+                histogram.push_str(".");
+            }
+            histogram.push_str("\n");
+        }
+        let sum = total_rows(datapoints).unwrap();
+        tracing::info!("\n{}", histogram);
+        tracing::info!(
+            "sum: {} vs num_entries: {}",
+            sum,
+            cfg.number_of_entries().unwrap()
+        );
+        assert_eq!(sum, cfg.number_of_entries().unwrap() as u64);
+    }
+
+    #[test]
+    fn test_generate_datapoints_early_fill_pad_window() {
+        let mut cfg = generation_test_config("early_fill", 10000, "10m");
+        cfg.set_seed(Some(42));
+        let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+
+        // all early filled; the window is cut short
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let filled = datapoints.len();
+        assert!(filled < 600);
+
+        cfg.set_pad_window(Some(true));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 600);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        assert_eq!(datapoints[0].timestamp, start_time);
+        assert_eq!(
+            datapoints.last().unwrap().timestamp,
+            end_time - Duration::seconds(1)
+        );
+        assert!(datapoints[filled..].iter().all(|d| d.rows_to_add == 0));
+
+        // a too short window; the last slot absorbs whatever is not filled yet (no padding left)
+        cfg.set_generation_duration(Some("10s".to_string()));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 10);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        // at most 1% (100) per slot for the other 9 slots
+        assert!(datapoints[9].rows_to_add >= 10000 - 9 * 100);
+    }
+
+    #[test]
+    fn test_generate_datapoints_sparse_fill() {
+        let cfg = generation_test_config("sparse_fill", 10000, "10m");
+
+        let result = generate_datapoints(&cfg);
+        assert_eq!(result.is_err(), false);
+        tracing::trace!("{:?}", result.as_ref().unwrap());
+
+        let mut histogram = String::new();
+        let datapoints = result.as_ref().unwrap();
+        for datapoint in datapoints {
+            // [debug]
+            // [graph - histogram]
+            histogram.push_str(format!("timestamp: {} | ", datapoint.timestamp).as_str());
+            for _ in 0..datapoint.rows_to_add {
+                histogram.push_str(".");
+            }
+            histogram.push_str("\n");
+        }
+        let sum = total_rows(datapoints).unwrap();
+        tracing::info!("\n{}", histogram);
+        tracing::info!(
+            "sum: {} vs num_entries: {}",
+            sum,
+            cfg.number_of_entries().unwrap()
+        );
+        assert_eq!(sum, cfg.number_of_entries().unwrap() as u64);
+    }
+
+    #[test]
+    fn test_generate_datapoints_sparse_fill_few_entries() {
+        let mut cfg = generation_test_config("sparse_fill", 2, "10m");
+        cfg.set_sparse_min_zones(Some(6));
+        cfg.set_sparse_max_zones(Some(8));
+
+        for seed in 0..20 {
+            cfg.set_seed(Some(seed));
+            let datapoints = generate_datapoints(&cfg).unwrap();
+            assert_eq!(total_rows(&datapoints).unwrap(), 2);
+            // the zones were reduced to the 2 entries; 1 row each, none of the zones empty
+            let non_empty: Vec<&DataPoint> =
+                datapoints.iter().filter(|d| d.rows_to_add > 0).collect();
+            assert_eq!(non_empty.len(), 2, "seed [{}]: {:?}", seed, datapoints);
+            assert!(non_empty.iter().all(|d| d.rows_to_add == 1));
+        }
+
+        // a single entry; a single zone
+        cfg.set_number_of_entries(Some(1));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_generate_sparse_fill_zone_and_boundaries() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        // table test(s) / parameterized test(s)
+        // parameters
+        // 1. data_zones_to_be_generated: &Vec<u32>,
+        // 2. generation_factor: u32,
+        // 3. start_time: DateTime<Utc>,
+        // 4. duration_in_seconds: i64
+        // 5. expect error message: str
+        // 6. expect number of data zones: u32 => (1.len() x 2.)
+        // 7. sum of vec![] in 1.
+        let test_cases = vec![
+            (
+                vec![100, 190, 100, 60],
+                6,
+                Utc::now(),
+                10 * 60,
+                4 * 6,
+                100 + 190 + 100 + 60,
+            ),
+            (
+                vec![30, 80, 120],
+                8,
+                Utc::now(),
+                8 * 60,
+                3 * 8,
+                30 + 80 + 120,
+            ),
+            (
+                vec![100, 190, 100, 60],
+                3,
+                Utc::now(),
+                10 * 60,
+                4 * 3,
+                100 + 190 + 100 + 60,
+            ),
+        ];
+        // iterate the test_cases
+        for (
+            data_zones_to_be_generated,
+            generation_factor,
+            start_time,
+            duration_in_seconds,
+            expect_number_of_data_zones,
+            expect_sum,
+        ) in test_cases
+        {
+            let data_zones = generate_sparse_fill_zone_and_boundaries(
+                &data_zones_to_be_generated,
+                generation_factor,
+                SparsePlacement::Random,
+                start_time,
+                duration_in_seconds,
+            );
+            assert_eq!(
+                data_zones.len() as u32,
+                expect_number_of_data_zones,
+                "expect {} zones created with {} rows altogether",
+                expect_number_of_data_zones,
+                expect_sum
+            );
+            let mut sum = 0;
+            for data_zone in data_zones.clone() {
+                sum += data_zone.num_rows_to_add;
+            }
+            assert_eq!(
+                sum as u32, expect_sum,
+                "expect {} zones created with {} rows altogether",
+                expect_number_of_data_zones, expect_sum
+            );
+            // all is good, trace a message
+            tracing::info!(
+                "{} zones created with {} rows altogether, distribution: {:?}",
+                expect_number_of_data_zones,
+                expect_sum,
+                data_zones
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_datapoints_bimodal() {
+        let mut cfg = generation_test_config("bimodal", 10000, "10m");
+
+        let result = generate_datapoints(&cfg);
+        assert_eq!(result.is_err(), false);
+        let datapoints = result.as_ref().unwrap();
+        assert_eq!(datapoints.len(), 600);
+
+        assert_eq!(
+            total_rows(datapoints).unwrap(),
+            cfg.number_of_entries().unwrap() as u64
+        );
+
+        // collapse plateaus first, then count the local maxima
+        let mut counts: Vec<i16> = datapoints.iter().map(|d| d.rows_to_add).collect();
+        counts.dedup();
+        let mut local_maxima = vec![];
+        for i in 1..counts.len() - 1 {
+            if counts[i] > counts[i - 1] && counts[i] > counts[i + 1] {
+                local_maxima.push(i);
+            }
+        }
+        tracing::info!("bimodal local maxima (deduped idx): {:?}", local_maxima);
+        assert_eq!(local_maxima.len(), 2);
+
+        // uneven split and custom peak positions
+        cfg.set_peak1(Some(0.1));
+        cfg.set_peak2(Some(0.9));
+        cfg.set_peak1_ratio(Some(0.3));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let first_half = total_rows(&datapoints[..300]).unwrap();
+        let second_half = total_rows(&datapoints[300..]).unwrap();
+        assert_eq!(first_half + second_half, 10000);
+        assert_eq!(first_half, 3000);
+
+        // out of range peak
+        cfg.set_peak2(Some(1.5));
+        let result = generate_datapoints(&cfg);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "peak2 must be within [0, 1], got [1.5]"
+        );
+
+        // within the average but beyond the per-slot range at the humps
+        let cfg = generation_test_config("bimodal", 1_000_000, "1m");
+        let message = generate_datapoints(&cfg).err().unwrap().to_string();
+        assert!(
+            message.contains("] of the bimodal model needs ["),
+            "{}",
+            message
+        );
+        assert!(message.ends_with(
+            "at most 32767 per slot, lower the number_of_entries or raise the generation_duration"
+        ));
+    }
+
+    #[test]
+    fn test_generate_datapoints_decay() {
+        let mut cfg = generation_test_config("decay", 10000, "10m");
+
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 600);
+        assert_eq!(
+            total_rows(&datapoints).unwrap(),
+            cfg.number_of_entries().unwrap() as u64
+        );
+        for pair in datapoints.windows(2) {
+            assert!(
+                pair[0].rows_to_add >= pair[1].rows_to_add,
+                "decay must be non-increasing: {:?} vs {:?}",
+                pair[0],
+                pair[1]
+            );
+        }
+        // default lambda; last slot is roughly 1% of the first one
+        let first_default = datapoints[0].rows_to_add;
+        assert!(datapoints[599].rows_to_add <= first_default / 50);
+
+        // a steeper lambda front-loads more rows into the first slot
+        cfg.set_decay_lambda(Some(0.05));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(
+            total_rows(&datapoints).unwrap(),
+            cfg.number_of_entries().unwrap() as u64
+        );
+        assert!(datapoints[0].rows_to_add > first_default);
+
+        cfg.set_decay_lambda(Some(-1.0));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "decay_lambda must be non-negative, got [-1]"
+        );
+
+        // within the average but beyond the per-slot range at the front loaded first slot
+        let cfg = generation_test_config("decay", 1_000_000, "1m");
+        let message = generate_datapoints(&cfg).err().unwrap().to_string();
+        assert!(
+            message.starts_with("slot [0] of the decay model needs ["),
+            "{}",
+            message
+        );
+    }
+
+    /// per-minute counts of a bell-ish traffic profile (10 points).
+    const REPLAY_PROFILE_FIXTURE: &str = "./tests/replay_profile.csv";
+
+    #[test]
+    fn test_generate_datapoints_replay_csv() {
+        let mut cfg = generation_test_config("replay_csv", 10000, "10m");
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "the replay_csv distribution model requires the `profile_path` field"
+        );
+
+        cfg.set_profile_path(Some(REPLAY_PROFILE_FIXTURE.to_string()));
+        let profile = read_profile_counts(REPLAY_PROFILE_FIXTURE).unwrap();
+        assert_eq!(profile.len(), 10);
+
+        // fewer points than slots; every point (minute) spreads over 60 slots
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 600);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        let per_minute: Vec<f64> = datapoints
+            .chunks(60)
+            .map(|minute| total_rows(minute).unwrap() as f64)
+            .collect();
+        assert!(
+            pearson_correlation(&per_minute, &profile) > 0.99,
+            "{:?} vs {:?}",
+            per_minute,
+            profile
+        );
+        let profile_sum: f64 = profile.iter().sum();
+        for (rows, count) in per_minute.iter().zip(profile.iter()) {
+            assert!((rows - 10000.0 * count / profile_sum).abs() <= 60.0);
+        }
+
+        // more points than slots; every slot sums up 2 points
+        cfg.set_generation_duration(Some("5s".to_string()));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 5);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        let per_slot: Vec<f64> = datapoints
+            .iter()
+            .map(|datapoint| datapoint.rows_to_add as f64)
+            .collect();
+        let profile_pairs: Vec<f64> = profile.chunks(2).map(|pair| pair.iter().sum()).collect();
+        assert!(pearson_correlation(&per_slot, &profile_pairs) > 0.99);
+
+        // uneven ratios; every point still lands somewhere
+        for (counts, num_slots, expected) in [
+            (vec![3.0, 6.0], 3, vec![2.0, 3.0, 4.0]),
+            (vec![1.0, 2.0, 3.0], 2, vec![2.0, 4.0]),
+        ] {
+            let weights = resample_profile_weights(&counts, num_slots);
+            assert_eq!(weights.len(), expected.len());
+            for (weight, expected) in weights.iter().zip(expected.iter()) {
+                assert!((weight - expected).abs() < 1e-9, "{:?}", weights);
+            }
+        }
+
+        // within the average but beyond the per-slot range at the busiest minutes
+        let mut cfg = generation_test_config("replay_csv", 1_500_000, "1m");
+        cfg.set_profile_path(Some(REPLAY_PROFILE_FIXTURE.to_string()));
+        let message = generate_datapoints(&cfg).err().unwrap().to_string();
+        assert!(
+            message.contains("] of the replay_csv model needs ["),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_generate_datapoints_anomalies() {
+        // flat slots of 100 rows
+        let mut cfg = generation_test_config("weights", 6000, "1m");
+        cfg.set_weights(Some(vec![1.0]));
+        cfg.set_anomalies(Some(vec![
+            ConfigAnomaly::new(0.25, "spike", None),
+            ConfigAnomaly::new(0.75, "drop", None),
+        ]));
+        let (start_time, _) = generate_time_range(&cfg).unwrap();
+
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 60);
+        // the spike borrows and the drop donates; the total stays the same
+        assert_eq!(total_rows(&datapoints).unwrap(), 6000);
+        for (i, datapoint) in datapoints.iter().enumerate() {
+            match i {
+                15 => {
+                    assert_eq!(*datapoint.timestamp(), start_time + Duration::seconds(15));
+                    assert_eq!(*datapoint.anomaly(), Some(AnomalyKind::Spike));
+                    assert_eq!(*datapoint.rows_to_add(), 1000);
+                }
+                45 => {
+                    assert_eq!(*datapoint.timestamp(), start_time + Duration::seconds(45));
+                    assert_eq!(*datapoint.anomaly(), Some(AnomalyKind::Drop));
+                    assert_eq!(*datapoint.rows_to_add(), 0);
+                }
+                _ => assert_eq!(*datapoint.anomaly(), None),
+            }
+        }
+
+        // the gauges are not rebalanced
+        cfg.set_value_mode(Some("gauge".to_string()));
+        cfg.set_gauge_max(Some(1000));
+        cfg.set_anomalies(Some(vec![ConfigAnomaly::new(1.0, "drop", Some(0.5))]));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let last = datapoints.last().unwrap();
+        assert_eq!(*last.anomaly(), Some(AnomalyKind::Drop));
+        assert_eq!(*last.rows_to_add(), 250);
+        assert!(datapoints[..59].iter().all(|d| *d.rows_to_add() == 500));
+
+        cfg.set_anomalies(Some(vec![ConfigAnomaly::new(0.5, "dip", None)]));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "unknown anomaly kind [dip]; expecting \"drop\" or \"spike\""
+        );
+        cfg.set_anomalies(Some(vec![ConfigAnomaly::new(0.5, "spike", Some(0.5))]));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "spike anomaly magnitude must be >= 1, got [0.5]"
+        );
+        cfg.set_anomalies(Some(vec![ConfigAnomaly::new(1.5, "drop", None)]));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "anomaly at [1.5] must be within [0, 1]"
+        );
+    }
+
+    #[test]
+    fn test_generate_datapoints_weights() {
+        let mut cfg = generation_test_config("weights", 10007, "50s");
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "the weights distribution model requires the `weights` field"
+        );
+
+        let shape = vec![1.0, 2.0, 5.0, 2.0, 1.0];
+        cfg.set_weights(Some(shape.clone()));
+        let shape_sum: f64 = shape.iter().sum();
+
+        // fewer weights than slots; every weight stretches over 10 slots
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 50);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10007);
+        let per_weight: Vec<f64> = datapoints
+            .chunks(10)
+            .map(|chunk| total_rows(chunk).unwrap() as f64)
+            .collect();
+        for (rows, weight) in per_weight.iter().zip(shape.iter()) {
+            assert!(
+                (rows - 10007.0 * weight / shape_sum).abs() <= 10.0,
+                "{:?}",
+                per_weight
+            );
+        }
+
+        // as many weights as slots; 1 weight per slot
+        cfg.set_generation_duration(Some("5s".to_string()));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 10007);
+        for (datapoint, weight) in datapoints.iter().zip(shape.iter()) {
+            assert!((datapoint.rows_to_add as f64 - 10007.0 * weight / shape_sum).abs() <= 1.0);
+        }
+
+        // more weights than slots; the weights are summed up per slot
+        cfg.set_weights(Some(vec![1.0, 1.0, 2.0, 2.0, 5.0, 5.0, 2.0, 2.0, 1.0, 1.0]));
+        let stretched = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&stretched).unwrap(), 10007);
+        let rows = |datapoints: &[DataPoint]| -> Vec<i16> {
+            datapoints.iter().map(|d| d.rows_to_add).collect()
+        };
+        assert_eq!(rows(&stretched), rows(&datapoints));
+
+        // invalid weights
+        for (weights, expected) in [
+            (
+                vec![1.0, -2.0],
+                "invalid weight [-2] at index 1; expecting a non-negative number",
+            ),
+            (vec![0.0, 0.0], "the `weights` have no positive weight"),
+            (vec![], "the `weights` have no positive weight"),
+        ] {
+            cfg.set_weights(Some(weights));
+            assert_eq!(
+                generate_datapoints(&cfg).err().unwrap().to_string(),
+                expected
+            );
+        }
+
+        // within the average but beyond the per-slot range at the heaviest weight
+        cfg.set_weights(Some(vec![1.0, 1.0, 8.0]));
+        cfg.set_number_of_entries(Some(50_000));
+        cfg.set_generation_duration(Some("3s".to_string()));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "slot [2] of the weights model needs [40000] rows; at most 32767 per slot, lower the number_of_entries or raise the generation_duration"
+        );
+    }
+
+    fn pearson_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+        let n = xs.len() as f64;
+        let (mean_x, mean_y) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+        let covariance: f64 = xs
+            .iter()
+            .zip(ys)
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let spread_x: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum::<f64>().sqrt();
+        let spread_y: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum::<f64>().sqrt();
+        covariance / (spread_x * spread_y)
+    }
+
+    #[test]
+    fn test_distribution_models_registry() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+
+        let registry = DistributionModelRegistry::with_builtin_models();
+        let listing = registry.list();
+        assert_eq!(
+            registry.names(),
+            vec!["even", "early_fill", "sparse_fill", "bimodal", "decay", "step", "replay_csv", "weights"]
+        );
+        cfg.set_steps(Some(vec![ConfigStep::new("0s", 10)]));
+        cfg.set_profile_path(Some(REPLAY_PROFILE_FIXTURE.to_string()));
+        cfg.set_weights(Some(vec![1.0, 2.0, 5.0, 2.0, 1.0]));
+        for name in registry.names() {
+            // every registered model is dispatchable (case-insensitive) and listed
+            cfg.set_distribution_by(Some(name.to_uppercase()));
+            let datapoints = generate_datapoints(&cfg).unwrap();
+            assert_eq!(total_rows(&datapoints).unwrap(), 10000, "model [{}]", name);
+            assert!(listing.contains(name));
+            assert!(listing.contains(registry.get(name).unwrap().description()));
+        }
+        assert_eq!(listing.lines().count(), registry.names().len());
+
+        // anything not within the registry is rejected
+        cfg.set_distribution_by(Some("not_a_model".to_string()));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "unknown distribution model [not_a_model]"
+        );
+    }
+
+    /// a trivial custom model; puts every row into the first slot.
+    struct AllAtOnceModel;
+
+    impl DistributionModel for AllAtOnceModel {
+        fn fill(
+            &self,
+            ctx: &GenerationContext,
+            out: &mut Vec<DataPoint>,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            out.push(DataPoint::new(
+                ctx.start_time(),
+                ctx.num_entries_to_generate() as i16,
+            ));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_model_aliases() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_seed(Some(42));
+
+        cfg.set_distribution_by(Some("even".to_string()));
+        let even = generate_datapoints(&cfg).unwrap();
+        for alias in ["flat", "uniform", "Flat"] {
+            assert_eq!(canonical_model_name(alias), "even");
+            cfg.set_distribution_by(Some(alias.to_string()));
+            let datapoints = generate_datapoints(&cfg).unwrap();
+            assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+            // same seed, same model; the same datapoints
+            assert_eq!(
+                datapoints.iter().map(|d| d.rows_to_add).collect::<Vec<_>>(),
+                even.iter().map(|d| d.rows_to_add).collect::<Vec<_>>()
+            );
+        }
+
+        // not an alias; as is
+        assert_eq!(canonical_model_name("Sparse_Fill"), "sparse_fill");
+        assert_eq!(canonical_model_name("normal"), "normal");
+    }
+
+    #[test]
+    fn test_register_custom_distribution_model() {
+        let mut cfg = generation_test_config("all_at_once", 5000, "10m");
+
+        // not available by default
+        assert!(generate_datapoints(&cfg).is_err());
+
+        let mut registry = DistributionModelRegistry::with_builtin_models();
+        registry.register("All_At_Once", Box::new(AllAtOnceModel));
+        assert!(registry.list().contains("custom distribution model"));
+
+        let datapoints = generate_datapoints_with_registry(&cfg, &registry).unwrap();
+        assert_eq!(datapoints.len(), 1);
+        assert_eq!(*datapoints[0].rows_to_add(), 5000);
+        assert_eq!(
+            *datapoints[0].timestamp(),
+            "2022-01-01T00:00:00.000Z".parse::<DateTime<Utc>>().unwrap()
+        );
+
+        // built-in models are still there
+        cfg.set_distribution_by(Some("even".to_string()));
+        assert!(generate_datapoints_with_registry(&cfg, &registry).is_ok());
+    }
+
+    #[test]
+    fn test_total_rows() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        assert_eq!(total_rows(&[]).unwrap(), 0);
+
+        // the total is way beyond the i16 (and u16) range; 1000 * 30000
+        let start_time = Utc::now();
+        let datapoints: Vec<DataPoint> = (0..1000)
+            .map(|i| DataPoint::new(start_time + Duration::seconds(i), 30000))
+            .collect();
+        assert_eq!(total_rows(&datapoints).unwrap(), 30_000_000);
+    }
+
+    #[test]
+    fn test_total_rows_negative() {
+        let timestamp: DateTime<Utc> = "2022-01-01T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            total_rows(&[DataPoint::new(timestamp, 3), DataPoint::new(timestamp, -1)])
+                .err()
+                .unwrap(),
+            "negative rows [-1] at [2022-01-01 00:00:00 UTC]"
+        );
+    }
+
+    #[test]
+    fn test_generate_time_range_parse_error() {
+        let mut cfg = Config::new();
+        cfg.set_use_now_as_timestamp(Some(false));
+        cfg.set_generation_duration(Some("10m".to_string()));
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+
+        // a value not matching the format
+        cfg.set_start_timestamp(Some("2022-13-01T00:00:00.000+00:00".to_string()));
+        let e = generate_time_range(&cfg).err().unwrap();
+        match e.downcast_ref::<GenerationError>() {
+            Some(GenerationError::TimestampParse {
+                value,
+                format,
+                source,
+            }) => {
+                assert_eq!(value, "2022-13-01T00:00:00.000+00:00");
+                assert_eq!(format, "%Y-%m-%dT%H:%M:%S%.f%:z");
+                assert_eq!(source.kind(), chrono::format::ParseErrorKind::OutOfRange);
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert!(std::error::Error::source(e.as_ref()).is_some());
+
+        // an invalid format
+        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%Q".to_string()));
+        let e = generate_time_range(&cfg).err().unwrap();
+        match e.downcast_ref::<GenerationError>() {
+            Some(GenerationError::TimestampParse { source, .. }) => {
+                assert_eq!(source.kind(), chrono::format::ParseErrorKind::BadFormat)
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_generate_datapoints_end_inclusive() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+
+        // [start, end) by default
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 600);
+        assert_eq!(
+            datapoints.last().unwrap().timestamp,
+            end_time - Duration::seconds(1)
+        );
+
+        cfg.set_end_inclusive(Some(true));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 601);
+        assert_eq!(datapoints[0].timestamp, start_time);
+        assert_eq!(datapoints.last().unwrap().timestamp, end_time);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+
+        // coarse slots; the inclusive slot stays at the end boundary
+        cfg.set_generation_duration(Some("1000s".to_string()));
+        cfg.set_max_slots(Some(300));
+        let (_, end_time) = generate_time_range(&cfg).unwrap();
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.last().unwrap().timestamp, end_time);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+    }
+
+    #[test]
+    fn test_generate_datapoints_order() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_seed(Some(42));
+        let rows = |datapoints: &[DataPoint]| -> Vec<(DateTime<Utc>, i16)> {
+            datapoints
+                .iter()
+                .map(|datapoint| (datapoint.timestamp, datapoint.rows_to_add))
+                .collect()
+        };
+        let asc = rows(&generate_datapoints(&cfg).unwrap());
+
+        cfg.set_order(Some("desc".to_string()));
+        let desc = rows(&generate_datapoints(&cfg).unwrap());
+        for pair in desc.windows(2) {
+            assert!(pair[0].0 > pair[1].0);
+        }
+        // the same datapoints; just newest first
+        assert_eq!(desc.iter().rev().cloned().collect::<Vec<_>>(), asc);
+        // the streaming iterator too
+        let streamed: Vec<DataPoint> = generate_datapoints_iter(&cfg).unwrap().collect();
+        assert_eq!(rows(&streamed), desc);
+
+        cfg.set_order(Some("random".to_string()));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "unknown order [random]; expecting \"asc\" or \"desc\""
+        );
+    }
+
+    #[test]
+    fn test_generate_datapoints_max_slots() {
+        let mut cfg = generation_test_config("even", 10000, "1d");
+        cfg.set_max_slots(Some(100));
+        let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+
+        // PS. early_fill caps a slot at 1% of the entries; 100 slots are not enough for it
+        for model in ["even", "sparse_fill", "bimodal", "decay"] {
+            cfg.set_distribution_by(Some(model.to_string()));
+            let datapoints = generate_datapoints(&cfg).unwrap();
+            assert!(datapoints.len() <= 100, "model [{}]", model);
+            assert_eq!(total_rows(&datapoints).unwrap(), 10000, "model [{}]", model);
+            // 864 seconds wide slots spanning the whole day
+            for datapoint in datapoints.iter() {
+                let offset = (datapoint.timestamp - start_time).num_seconds();
+                assert_eq!(offset % 864, 0, "model [{}]", model);
+                assert!(datapoint.timestamp < end_time);
+            }
+        }
+        cfg.set_distribution_by(Some("even".to_string()));
+        assert_eq!(generate_datapoints(&cfg).unwrap().len(), 100);
+
+        // within the limit; 1 second slots as usual
+        cfg.set_generation_duration(Some("1m".to_string()));
+        assert_eq!(generate_datapoints(&cfg).unwrap().len(), 60);
+
+        cfg.set_max_slots(Some(0));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "max_slots must be greater than zero"
+        );
+    }
+
+    /// the fields (by name) of every event captured.
+    #[derive(Clone, Default)]
+    struct CapturedEvents(Arc<Mutex<Vec<HashMap<String, String>>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for CapturedEvents {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = CapturedFields::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    #[derive(Default)]
+    struct CapturedFields(HashMap<String, String>);
+
+    impl tracing::field::Visit for CapturedFields {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    #[test]
+    fn test_generate_datapoints_structured_logs() {
+        let mut cfg = generation_test_config("early_fill", 50, "1m");
+        cfg.set_seed(Some(42));
+
+        let captured = CapturedEvents::default();
+        let subscriber = tracing_subscriber::registry().with(captured.clone());
+        tracing::subscriber::with_default(subscriber, || generate_datapoints(&cfg).unwrap());
+
+        let events = captured.0.lock().unwrap();
+        let event = |message: &str| {
+            events
+                .iter()
+                .find(|fields| fields.get("message").map(String::as_str) == Some(message))
+                .unwrap_or_else(|| panic!("no [{}] event in {:?}", message, events))
+        };
+        let generating = event("generating");
+        assert_eq!(generating["module"], "augmentation");
+        assert_eq!(generating["seed"], "42");
+
+        // the model specific events carry the model and the slot
+        let early_filled = event("all entries early filled");
+        assert_eq!(early_filled["model"], "early_fill");
+        assert_eq!(early_filled["total"], "50");
+        let slot: i64 = early_filled["slot"].parse().unwrap();
+        assert_eq!(early_filled["empty_slots"], (60 - slot - 1).to_string());
+
+        let generated = event("generated");
+        assert_eq!(generated["model"], "early_fill");
+        assert_eq!(generated["slots"], "60");
+        assert_eq!(generated["rows"], "50");
+        assert_eq!(generated["total"], "50");
+    }
+
+    #[test]
+    fn test_generate_datapoints_scale() {
+        let mut cfg = generation_test_config("bimodal", 1000, "1m");
+        cfg.set_scale(Some(2.5));
+        assert_eq!(
+            total_rows(&generate_datapoints(&cfg).unwrap()).unwrap(),
+            2500
+        );
+
+        // the derived total of the step model too
+        cfg.set_number_of_entries(None);
+        cfg.set_distribution_by(Some("step".to_string()));
+        cfg.set_steps(Some(vec![ConfigStep::new("0s", 10)]));
+        assert_eq!(
+            total_rows(&generate_datapoints(&cfg).unwrap()).unwrap(),
+            1500
+        );
+
+        // rounded, then checked against the max_entries
+        assert_eq!(scale_entries(3, Some(0.5)).unwrap(), 2);
+        cfg.set_max_entries(Some(1000));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "number_of_entries [1500] exceeds the max_entries safety limit [1000]; raise `max_entries` explicitly if intended"
+        );
+        cfg.set_scale(Some(0.0));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "scale must be positive, got [0]"
+        );
+    }
+
+    #[test]
+    fn test_generate_datapoints_min_rows_per_slot() {
+        // huge gaps in between the zones otherwise
+        let mut cfg = generation_test_config("sparse_fill", 1000, "1m");
+        cfg.set_min_rows_per_slot(Some(5));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 60);
+        assert!(datapoints
+            .iter()
+            .all(|datapoint| datapoint.rows_to_add >= 5));
+        assert_eq!(total_rows(&datapoints).unwrap(), 1000);
+
+        // 20 x 60 slots > 1000 rows
+        cfg.set_min_rows_per_slot(Some(20));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "min_rows_per_slot [20] x slots [60] exceeds the total rows [1000]"
+        );
+        // never truncated into the i16 range of a slot
+        cfg.set_number_of_entries(Some(10000));
+        cfg.set_generation_duration(Some("10s".to_string()));
+        cfg.set_min_rows_per_slot(Some(40000));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "min_rows_per_slot [40000] exceeds the per-slot range; at most 32767"
+        );
+
+        // borrowed from the heaviest slot; the missing 3rd slot is filled up too
+        let start_time = Utc::now();
+        let mut datapoints = vec![
+            DataPoint::new(start_time, 10),
+            DataPoint::new(start_time + Duration::seconds(1), 0),
+            DataPoint::new(start_time + Duration::seconds(3), 2),
+        ];
+        apply_min_rows_per_slot(&mut datapoints, 3, start_time, 4).unwrap();
+        let rows: Vec<i16> = datapoints.iter().map(|d| d.rows_to_add).collect();
+        assert_eq!(rows, vec![3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_generate_datapoints_warmup() {
+        let mut cfg = generation_test_config("bimodal", 1000, "1m");
+        cfg.set_warmup_duration(Some("10s".to_string()));
+        cfg.set_warmup_rate(Some(2));
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 60);
+        // the first 10 seconds at the fixed rate
+        for (second, datapoint) in datapoints[..10].iter().enumerate() {
+            assert_eq!(
+                datapoint.timestamp,
+                start_time + Duration::seconds(second as i64)
+            );
+            assert_eq!(datapoint.rows_to_add, 2);
+        }
+        // the number_of_entries across the remaining 50 seconds
+        assert_eq!(datapoints[10].timestamp, start_time + Duration::seconds(10));
+        assert_eq!(total_rows(&datapoints[10..]).unwrap(), 1000);
+        assert_eq!(total_rows(&datapoints).unwrap(), 1020);
+
+        cfg.set_warmup_duration(Some("1m".to_string()));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "warmup_duration [1m] must be shorter than the generation window (60 seconds)"
+        );
+        cfg.set_warmup_duration(None);
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "warmup_duration and warmup_rate must be set together"
+        );
+    }
+
+    #[test]
+    fn test_generate_datapoints_slot_count() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let mut cfg = Config::new();
+        cfg.set_number_of_entries(Some(10000));
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+        cfg.set_use_now_as_timestamp(Some(false));
+        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        cfg.set_distribution_by(Some("even".to_string()));
+        cfg.set_slot_count(Some(50));
+        cfg.set_slot_width(Some("1m".to_string()));
+        let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+        assert_eq!(end_time - start_time, Duration::minutes(50));
+
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 50);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        for (i, datapoint) in datapoints.iter().enumerate() {
+            assert_eq!(
+                datapoint.timestamp,
+                start_time + Duration::minutes(i as i64)
+            );
         }
+
+        // max_slots does not apply to the slot_count window
+        cfg.set_max_slots(Some(10));
+        assert_eq!(generate_datapoints(&cfg).unwrap().len(), 50);
+
+        cfg.set_slot_width(Some("500ms".to_string()));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "slot_width must be at least 1 second"
+        );
+        cfg.set_slot_width(Some("1m".to_string()));
+        cfg.set_slot_count(Some(0));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "slot_count must be greater than zero"
+        );
     }
-    Ok(())
-}
 
-fn generate_sparse_fill_zone_and_boundaries(
-    data_zones_to_be_generated: &[u32],
-    generation_factor: u32,
-    start_time: DateTime<Utc>,
-    duration_in_seconds: i64,
-) -> Vec<DataZone> {
-    // eg. generation_factor = 6
-    // num_of_data_zones = data_zones_to_be_generated.len() = 5
-    // size of vec would be 6*5 = 30; out 5 would be occupied
-    let data_zones_len = generation_factor as usize * data_zones_to_be_generated.len();
-    let mut data_zones: Vec<DataZone> = vec![
-        DataZone::new();
-        // DataZone {
-        //     start_time: Utc::now(),
-        //     end_time: Utc::now(),
-        //     num_rows_to_add: 0,
-        // };
-        data_zones_len
-    ];
-    // first iteration; fill up start_time, end_time
-    let zone_span = duration_in_seconds / data_zones_len as i64;
-    // for zone in data_zones.iter_mut() {
-    for (zone_idx, zone) in data_zones.iter_mut().enumerate() {
-        zone.start_time = start_time + Duration::seconds(zone_span * zone_idx as i64);
+    #[test]
+    fn test_validate_segments() {
+        let segment = |offset: i64, duration: i64| Segment {
+            offset: Duration::minutes(offset),
+            duration: Duration::minutes(duration),
+        };
+        // a quiet hour then a busy one
+        let window = Duration::hours(2);
+        assert!(validate_segments(&[segment(0, 60), segment(60, 60)], window).is_ok());
 
-        if zone_idx == data_zones_len - 1 {
-            zone.end_time = start_time + Duration::seconds(duration_in_seconds);
-        } else {
-            zone.end_time = zone.start_time + Duration::seconds(zone_span - 1);
-        }
+        // overlap
+        assert_eq!(
+            validate_segments(&[segment(0, 60), segment(50, 70)], window)
+                .err()
+                .unwrap()
+                .to_string(),
+            "segment [1] overlaps the previous segment; starts at [3000s] before its end at [3600s]"
+        );
+        // gap
+        assert_eq!(
+            validate_segments(&[segment(0, 50), segment(60, 60)], window)
+                .err()
+                .unwrap()
+                .to_string(),
+            "segment [1] leaves a gap; starts at [3600s] after the previous end at [3000s]"
+        );
+        assert_eq!(
+            validate_segments(&[segment(10, 110)], window)
+                .err()
+                .unwrap()
+                .to_string(),
+            "segment [0] leaves a gap; starts at [600s] after the previous end at [0s]"
+        );
+        // not the whole window
+        assert_eq!(
+            validate_segments(&[segment(0, 60), segment(60, 30)], window)
+                .err()
+                .unwrap()
+                .to_string(),
+            "segments end at [5400s] instead of the generation_duration [7200s]"
+        );
+        assert_eq!(
+            validate_segments(&[segment(0, 0), segment(0, 120)], window)
+                .err()
+                .unwrap()
+                .to_string(),
+            "segment [0] duration must be positive, got [0s]"
+        );
     }
-    // pick which zone to fill and which not
-    for zone in data_zones_to_be_generated.iter() {
-        loop {
-            let idx = rand::rng().random_range(0..data_zones.len());
-            if data_zones[idx].num_rows_to_add == 0 {
-                data_zones[idx].num_rows_to_add = *zone;
-                break;
-            }
+
+    #[test]
+    fn test_generate_datapoints_repeat() {
+        let mut cfg = generation_test_config("bimodal", 10000, "1h");
+        cfg.set_start_timestamp(Some("2022-01-01T09:00:00.000+00:00".to_string()));
+        cfg.set_timestamp_jitter(Some("2s".to_string()));
+        cfg.set_repeat(Some(ConfigRepeat::new(3, "1d")));
+        let start_time: DateTime<Utc> = "2022-01-01T09:00:00.000Z".parse().unwrap();
+
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 3 * 10000);
+        // 3 clusters; 1 per day within the 9:00 - 10:00 window
+        for day in 0..3 {
+            let window_start = start_time + Duration::days(day);
+            let window_end = window_start + Duration::hours(1);
+            let cluster: Vec<DataPoint> = datapoints
+                .iter()
+                .filter(|d| d.timestamp >= window_start && d.timestamp <= window_end)
+                .cloned()
+                .collect();
+            assert_eq!(total_rows(&cluster).unwrap(), 10000, "day {}", day);
         }
+
+        // overlapping windows
+        cfg.set_repeat(Some(ConfigRepeat::new(3, "30m")));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "repeat interval [30m] must be >= generation_duration [1h] so that the windows do not overlap"
+        );
+        cfg.set_repeat(Some(ConfigRepeat::new(0, "1d")));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "repeat count must be greater than zero"
+        );
+        cfg.set_repeat(Some(ConfigRepeat::new(20, "1d")));
+        cfg.set_max_entries(Some(100000));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "number_of_entries [10000] x repeat count [20] exceeds the max_entries safety limit [100000]; raise `max_entries` explicitly if intended"
+        );
     }
-    data_zones
-}
 
-#[derive(Clone, Debug)]
-struct DataZone {
-    start_time: DateTime<Utc>,
-    end_time: DateTime<Utc>,
-    num_rows_to_add: u32,
-}
+    /// the `AllAtOnceModel` counting its fills (1 per window).
+    struct CountingModel {
+        fills: Arc<Mutex<usize>>,
+    }
 
-impl DataZone {
-    fn new() -> DataZone {
-        DataZone {
-            start_time: Utc::now(),
-            end_time: Utc::now(),
-            num_rows_to_add: 0,
+    impl DistributionModel for CountingModel {
+        fn fill(
+            &self,
+            ctx: &GenerationContext,
+            out: &mut Vec<DataPoint>,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            *self.fills.lock().unwrap() += 1;
+            AllAtOnceModel.fill(ctx, out)
         }
     }
-}
 
-fn generate_sparse_fill_zone_datapoints(data_zone: &DataZone) -> Vec<DataPoint> {
-    let mut data_points = Vec::new();
-    // calculate the duration
-    let duration = data_zone.end_time.timestamp() - data_zone.start_time.timestamp();
-    // [trace] make it a trace after dev completed
-    tracing::debug!(
-        module = "augmentation",
-        message = format!(
-            "data_zone duration: {} seconds",
-            data_zone.end_time.timestamp() - data_zone.start_time.timestamp()
-        )
-    );
-    let mut rows_to_add_per_second = data_zone.num_rows_to_add / duration as u32;
-    let mut sum = 0;
-    // first fill with equal num of rows
-    for i in 0..duration {
-        // last entry
-        if i == duration - 1 {
-            rows_to_add_per_second = data_zone.num_rows_to_add - sum;
+    #[test]
+    fn test_generate_datapoints_iter() {
+        let mut cfg = generation_test_config("bimodal", 10000, "1h");
+        cfg.set_start_timestamp(Some("2022-01-01T09:00:00.000+00:00".to_string()));
+        cfg.set_timestamp_jitter(Some("2s".to_string()));
+        cfg.set_coalesce(Some(true));
+        cfg.set_repeat(Some(ConfigRepeat::new(3, "1d")));
+        cfg.set_seed(Some(42));
+        let rows = |datapoints: &[DataPoint]| -> Vec<(DateTime<Utc>, i16)> {
+            datapoints
+                .iter()
+                .map(|datapoint| (datapoint.timestamp, datapoint.rows_to_add))
+                .collect()
+        };
+
+        // the same datapoints window by window; whatever else the thread generates meanwhile
+        let generated = rows(&generate_datapoints(&cfg).unwrap());
+        let mut streamed = vec![];
+        for datapoint in generate_datapoints_iter(&cfg).unwrap() {
+            random_range(0..100);
+            streamed.push(datapoint);
         }
-        data_points.push(DataPoint {
-            timestamp: data_zone.start_time + Duration::seconds(i),
-            rows_to_add: rows_to_add_per_second as i16,
-        });
-        sum += rows_to_add_per_second;
+        assert_eq!(rows(&streamed), generated);
+
+        // 1 window generated at a time
+        let fills = Arc::new(Mutex::new(0));
+        let mut registry = DistributionModelRegistry::new();
+        registry.register(
+            "counting",
+            Box::new(CountingModel {
+                fills: fills.clone(),
+            }),
+        );
+        cfg.set_distribution_by(Some("counting".to_string()));
+        let mut datapoints = generate_datapoints_iter_with(
+            &cfg,
+            registry,
+            TransformRegistry::with_builtin_transforms(&cfg),
+        )
+        .unwrap();
+        assert_eq!(*fills.lock().unwrap(), 1);
+        assert!(datapoints.next().is_some());
+        assert_eq!(*fills.lock().unwrap(), 1);
+        assert!(datapoints.next().is_some());
+        assert_eq!(*fills.lock().unwrap(), 2);
+        assert_eq!(datapoints.count(), 1);
+        assert_eq!(*fills.lock().unwrap(), 3);
+
+        // the config errors surface up front
+        cfg.set_distribution_by(Some("not_a_model".to_string()));
+        assert_eq!(
+            generate_datapoints_iter(&cfg).err().unwrap().to_string(),
+            "unknown distribution model [not_a_model]"
+        );
     }
-    // second fill is shuffling by a factor of duration * 3;
-    for _ in 0..duration * 3 {
-        let (idx_1, idx_2) = pick_2_random_datapoint(data_points.len() as i64);
-        let rows_available = data_points[idx_1 as usize].rows_to_add;
-        if rows_available < 2 {
-            continue;
+
+    #[test]
+    fn test_generate_datapoints_step() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let mut cfg = Config::new();
+        cfg.set_distribution_by(Some("step".to_string()));
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+        cfg.set_use_now_as_timestamp(Some(false));
+        cfg.set_generation_duration(Some("1m".to_string()));
+        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        cfg.set_steps(Some(vec![
+            ConfigStep::new("0s", 5),
+            ConfigStep::new("20s", 20),
+            ConfigStep::new("40s", 10),
+        ]));
+
+        // [case][01] number_of_entries unset; the total is derived from the steps
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 60);
+        assert_eq!(total_rows(&datapoints).unwrap(), 20 * 5 + 20 * 20 + 20 * 10);
+        for (i, datapoint) in datapoints.iter().enumerate() {
+            let expected_rate = match i {
+                0..=19 => 5,
+                20..=39 => 20,
+                _ => 10,
+            };
+            assert_eq!(datapoint.rows_to_add, expected_rate, "slot {}", i);
         }
-        let delta = rand::rng().random_range(1..rows_available);
 
-        data_points[idx_1 as usize].rows_to_add -= delta;
-        data_points[idx_2 as usize].rows_to_add += delta;
+        // [case][02] number_of_entries set; plateaus stay flat and the final slot corrects the remainder
+        cfg.set_number_of_entries(Some(710));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 710);
+        assert!(datapoints[..59].iter().all(|d| d.rows_to_add > 0));
+        assert_eq!(datapoints[19].rows_to_add, 5);
+        assert_eq!(datapoints[20].rows_to_add, 20);
+        assert_eq!(datapoints[39].rows_to_add, 20);
+        assert_eq!(datapoints[40].rows_to_add, 10);
+        assert_eq!(datapoints[59].rows_to_add, 20);
+
+        // [case][03] slots before the 1st step are empty
+        cfg.set_steps(Some(vec![ConfigStep::new("30s", 10)]));
+        cfg.set_number_of_entries(None);
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints[29].rows_to_add, 0);
+        assert_eq!(datapoints[30].rows_to_add, 10);
+        assert_eq!(total_rows(&datapoints).unwrap(), 300);
+
+        // [case][04] not enough entries for the steps
+        cfg.set_number_of_entries(Some(100));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "steps require at least 290 rows which exceeds number_of_entries 100"
+        );
+        // the final slot cannot correct a remainder beyond the per-slot range
+        cfg.set_number_of_entries(Some(50000));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "slot [59] of the step model needs [49710] rows; at most 32767 per slot, lower the number_of_entries or raise the generation_duration"
+        );
+
+        // [case][05] invalid steps
+        cfg.set_steps(Some(vec![
+            ConfigStep::new("30s", 10),
+            ConfigStep::new("10s", 10),
+        ]));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "steps must be in increasing `at` order, got [10s]"
+        );
+        cfg.set_steps(Some(vec![ConfigStep::new("2m", 10)]));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "step at [2m] is outside of the generation window (60 seconds)"
+        );
+        cfg.set_steps(None);
+        assert!(generate_datapoints(&cfg).is_err());
+
+        // [case][06] the derived total overflowing
+        cfg.set_number_of_entries(None);
+        cfg.set_steps(Some(vec![ConfigStep::new("0s", u32::MAX / 2)]));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "the rates of the steps overflow the number_of_entries range"
+        );
+        assert_eq!(
+            StepModel.derived_total(&cfg, 2).unwrap(),
+            Some(u32::MAX / 2 * 2)
+        );
+        // none for the models without a derived total
+        assert_eq!(EvenModel.derived_total(&cfg, 60).unwrap(), None);
     }
-    data_points
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::app_init;
+    #[test]
+    fn test_generate_datapoints_timestamp_jitter() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_timestamp_jitter(Some("800ms".to_string()));
+
+        let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        for datapoint in datapoints.iter() {
+            assert!(datapoint.timestamp >= start_time && datapoint.timestamp <= end_time);
+        }
+        // jitter is applied; datapoints no longer all sit on a second boundary
+        let off_boundary = datapoints
+            .iter()
+            .filter(|d| d.timestamp.timestamp_subsec_nanos() != 0)
+            .count();
+        assert!(off_boundary > 0);
+        // ... but every datapoint stays within +/- 800ms of its slot
+        for (i, datapoint) in datapoints.iter().enumerate() {
+            let slot = start_time + Duration::seconds(i as i64);
+            let offset = (datapoint.timestamp - slot).num_milliseconds().abs();
+            assert!(offset <= 800, "slot {} is off by {}ms", i, offset);
+        }
+
+        // a jitter larger than the window still never escapes it
+        cfg.set_timestamp_jitter(Some("1h".to_string()));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        for datapoint in datapoints.iter() {
+            assert!(datapoint.timestamp >= start_time && datapoint.timestamp <= end_time);
+        }
+    }
 
     #[test]
-    fn test_parse_time_duration_value_and_unit() {
-        // init loggers
-        app_init("./config/default/loggers.toml".to_string()).unwrap();
+    fn test_generate_sparse_fill_zones_count() {
+        let start_time = Utc::now();
+        for num_of_zone in [1, 4, 8] {
+            let data_zones = generate_sparse_fill_zones(
+                start_time,
+                10 * 60,
+                10000,
+                num_of_zone,
+                num_of_zone,
+                DEFAULT_SPARSE_FILL_ZONE_GENERATION_FACTOR,
+                SparsePlacement::Random,
+            )
+            .unwrap();
+            let non_empty_zones = data_zones
+                .iter()
+                .filter(|zone| zone.num_rows_to_add > 0)
+                .count();
+            assert_eq!(non_empty_zones as u32, num_of_zone);
+            assert_eq!(
+                data_zones.len() as u32,
+                num_of_zone * DEFAULT_SPARSE_FILL_ZONE_GENERATION_FACTOR
+            );
+        }
 
-        let result = parse_time_duration_value_and_unit("10m".to_string());
-        assert_eq!(result.is_some(), true);
-        assert_eq!(result.as_ref().unwrap().0, 10);
-        assert_eq!(result.as_ref().unwrap().1, "m".to_string());
+        // through the config
+        let mut cfg = generation_test_config("sparse_fill", 10000, "10m");
+        cfg.set_sparse_min_zones(Some(2));
+        cfg.set_sparse_max_zones(Some(2));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        // 2 zones out of 2 * 3 buckets are filled; 99 seconds per bucket (100 for the last bucket)
+        assert!(datapoints.len() >= 2 * 99 && datapoints.len() <= 2 * 100);
 
-        // for invalid values... it still parse as is...
-        let result = parse_time_duration_value_and_unit("10m3d".to_string());
-        assert_eq!(result.is_some(), true);
-        assert_eq!(result.as_ref().unwrap().0, 10);
-        assert_eq!(result.as_ref().unwrap().1, "m3d".to_string());
+        // invalid ranges
+        cfg.set_sparse_min_zones(Some(5));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "sparse_min_zones [5] and sparse_max_zones [2] must be >= 1 and min <= max"
+        );
+        cfg.set_sparse_min_zones(Some(0));
+        assert!(generate_datapoints(&cfg).is_err());
 
-        // totally non-parsable value will yield NONE
-        let result = parse_time_duration_value_and_unit("m10".to_string());
-        assert_eq!(result.is_some(), false);
-        assert_eq!(result.is_none(), true);
+        // more zones than seconds; the zones (and the gaps) are reduced to fit the window
+        let start_time = DateTime::parse_from_rfc3339("2022-01-01T00:00:00.000+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let data_zones = generate_sparse_fill_zones(
+            start_time,
+            5,
+            100,
+            8,
+            8,
+            DEFAULT_SPARSE_FILL_ZONE_GENERATION_FACTOR,
+            SparsePlacement::Random,
+        )
+        .unwrap();
+        assert!(data_zones.len() <= 5);
+        assert!(data_zones
+            .iter()
+            .all(|zone| zone.start_time <= zone.end_time
+                && zone.end_time <= start_time + Duration::seconds(5)));
+        assert_eq!(
+            data_zones
+                .iter()
+                .map(|zone| zone.num_rows_to_add)
+                .sum::<u32>(),
+            100
+        );
+        cfg.set_number_of_entries(Some(100));
+        cfg.set_generation_duration(Some("5s".to_string()));
+        cfg.set_sparse_min_zones(Some(8));
+        cfg.set_sparse_max_zones(Some(8));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 100);
+        assert!(datapoints.len() <= 5);
     }
 
     #[test]
-    fn test_parse_time_duration() {
-        // init loggers
-        app_init("./config/default/loggers.toml".to_string()).unwrap();
+    fn test_sparse_generation_factor() {
+        let mut cfg = generation_test_config("sparse_fill", 10000, "12m");
+        cfg.set_sparse_min_zones(Some(4));
+        cfg.set_sparse_max_zones(Some(4));
 
-        let result = parse_time_duration("10m".to_string());
-        assert_eq!(result.is_ok(), true);
+        // the gaps are the seconds of the window without any datapoint (i.e. zero rows)
+        let mut zero_row_seconds = vec![];
+        for factor in [3, 6] {
+            cfg.set_sparse_generation_factor(Some(factor));
+            let datapoints = generate_datapoints(&cfg).unwrap();
+            assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+            let non_zero_seconds = datapoints.iter().filter(|d| d.rows_to_add > 0).count();
+            zero_row_seconds.push(12 * 60 - non_zero_seconds);
+        }
+        tracing::info!("zero-row seconds for factor 3 vs 6: {:?}", zero_row_seconds);
+        // higher factor = sparser; roughly 2/3 vs 5/6 of the window is empty
+        assert!(zero_row_seconds[1] > zero_row_seconds[0]);
+        assert!(zero_row_seconds[0] >= 12 * 60 * 2 / 3);
+        assert!(zero_row_seconds[1] >= 12 * 60 * 5 / 6);
+
+        cfg.set_sparse_generation_factor(Some(0));
         assert_eq!(
-            result.as_ref().unwrap().num_nanoseconds().unwrap(),
-            Duration::minutes(10).num_nanoseconds().unwrap()
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "sparse_generation_factor must be >= 1, got [0]"
         );
+    }
 
-        let result = parse_time_duration("10s".to_string());
-        assert_eq!(result.is_ok(), true);
+    #[test]
+    fn test_zero_number_of_entries() {
+        let mut cfg = generation_test_config("even", 0, "10m");
+        cfg.set_steps(Some(vec![ConfigStep::new("0s", 10)]));
+
+        for model in DistributionModelRegistry::with_builtin_models().names() {
+            cfg.set_distribution_by(Some(model.to_string()));
+            assert_eq!(
+                generate_datapoints(&cfg).err().unwrap().to_string(),
+                "number_of_entries must be greater than zero",
+                "{}",
+                model
+            );
+        }
+        // a step model deriving a zero total from its steps too
+        cfg.set_number_of_entries(None);
+        cfg.set_distribution_by(Some("step".to_string()));
+        cfg.set_steps(Some(vec![ConfigStep::new("0s", 0)]));
         assert_eq!(
-            result.as_ref().unwrap().num_nanoseconds().unwrap(),
-            Duration::seconds(10).num_nanoseconds().unwrap()
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "number_of_entries must be greater than zero"
         );
+    }
 
-        // totally not parsable value
-        let result = parse_time_duration("f10m".to_string());
-        assert_eq!(result.is_ok(), false);
+    #[test]
+    fn test_sparse_placement_spread() {
+        let mut cfg = generation_test_config("sparse_fill", 10000, "12m");
+        cfg.set_sparse_min_zones(Some(4));
+        cfg.set_sparse_max_zones(Some(4));
+        cfg.set_sparse_generation_factor(Some(3));
+        cfg.set_sparse_placement(Some("spread".to_string()));
+
+        // 12 buckets of 60 seconds; the 4 non-empty zones are every 3rd bucket (the middle one)
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        for seed in [1, 2, 3] {
+            cfg.set_seed(Some(seed));
+            let datapoints = generate_datapoints(&cfg).unwrap();
+            assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+            let mut buckets: Vec<i64> = datapoints
+                .iter()
+                .filter(|datapoint| datapoint.rows_to_add > 0)
+                .map(|datapoint| (datapoint.timestamp - start_time).num_seconds() / 60)
+                .collect();
+            buckets.dedup();
+            assert_eq!(buckets, vec![1, 4, 7, 10], "seed {}", seed);
+        }
+
+        cfg.set_sparse_placement(Some("clustered".to_string()));
         assert_eq!(
-            result.err().unwrap().to_string(),
-            "failed to parse time duration value and unit"
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "unknown sparse_placement [clustered]; expecting \"random\" or \"spread\""
         );
     }
 
-    // generate_time_range()
-    // create an artifial Config struct with combos to test around
     #[test]
-    fn test_generate_time_range() {
-        // init loggers
-        app_init("./config/default/loggers.toml".to_string()).unwrap();
+    fn test_sparse_fill_gaps() {
+        let mut cfg = generation_test_config("sparse_fill", 10000, "10m");
+        cfg.set_seed(Some(7));
 
-        let mut cfg = Config::new();
-        cfg.set_distribution_by(Some("even".to_string()));
-        cfg.set_number_of_entries(Some(10000));
-        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
-        cfg.set_use_now_as_timestamp(Some(false));
-        cfg.set_generation_duration(Some("10m".to_string()));
-        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        // implicit gaps by default
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert!(datapoints.len() < 10 * 60);
 
-        tracing::trace!("config: {:#?}", cfg);
+        cfg.set_fill_gaps(Some(true));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        // every second of the window; in order, no duplicates
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        assert_eq!(datapoints.len(), 10 * 60);
+        for (second, datapoint) in datapoints.iter().enumerate() {
+            assert_eq!(
+                datapoint.timestamp,
+                start_time + Duration::seconds(second as i64)
+            );
+        }
+        assert!(datapoints
+            .iter()
+            .any(|datapoint| datapoint.rows_to_add == 0));
+    }
 
-        // [case][01] not using NOW(), provide a valid timestamp_format + start_timestamp
-        let result = generate_time_range(&cfg);
-        if result.is_err() {
-            assert_eq!(result.err().unwrap().to_string(), "whay?");
-            return;
+    #[test]
+    fn test_gauge_value_mode() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_value_mode(Some("gauge".to_string()));
+        cfg.set_gauge_min(Some(20));
+        cfg.set_gauge_max(Some(80));
+
+        for model in ["even", "bimodal", "decay"] {
+            cfg.set_distribution_by(Some(model.to_string()));
+            let datapoints = generate_datapoints(&cfg).unwrap();
+            assert_eq!(datapoints.len(), 10 * 60);
+            assert!(datapoints
+                .iter()
+                .all(|datapoint| (20..=80).contains(&datapoint.rows_to_add)));
+            // the model shapes the curve; both bounds are reached
+            let values: Vec<i16> = datapoints.iter().map(|d| d.rows_to_add).collect();
+            assert_eq!(*values.iter().min().unwrap(), 20, "{}", model);
+            assert_eq!(*values.iter().max().unwrap(), 80, "{}", model);
         }
-        // [lesson] work... but hard to understood the nanoseconds value for comparison
-        //assert_eq!(result.as_ref().unwrap().0, 1640995200000); // 2022-01-01T00:00:00.000Z
-        //assert_eq!(result.as_ref().unwrap().1, 1640995201000); // 2022-01-01T00:00:10.000Z
-        let mut start_time_test: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
-        let mut end_time_test: DateTime<Utc> = start_time_test + Duration::minutes(10);
+        // decay; the value decays from the max to the min
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.first().unwrap().rows_to_add, 80);
+        assert_eq!(datapoints.last().unwrap().rows_to_add, 20);
+
+        cfg.set_gauge_min(Some(90));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "gauge_min [90] must be <= gauge_max [80]"
+        );
+        cfg.set_value_mode(Some("rate".to_string()));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "unknown value_mode [rate]; expecting \"count\" or \"gauge\""
+        );
+    }
+
+    #[test]
+    fn test_expand_datapoints() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 3),
+            DataPoint::new(start_time + Duration::seconds(1), 0),
+            DataPoint::new(start_time + Duration::seconds(2), 2),
+        ];
+        let timestamps = expand_datapoints(&datapoints);
+        assert_eq!(timestamps.len() as u64, total_rows(&datapoints).unwrap());
+        assert_eq!(timestamps[0], start_time);
+        assert_eq!(timestamps[2], start_time);
+        assert_eq!(timestamps[3], start_time + Duration::seconds(2));
+        assert_eq!(expand_datapoints_iter(&datapoints).count(), 5);
+
+        // generated datapoints
+        let cfg = generation_test_config("even", 10000, "10m");
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(expand_datapoints(&datapoints).len(), 10000);
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_timestamp_jitter(Some("500ms".to_string()));
+        cfg.set_seed(Some(42));
+
+        let rows = |datapoints: &[DataPoint]| -> Vec<(DateTime<Utc>, i16)> {
+            datapoints
+                .iter()
+                .map(|datapoint| (datapoint.timestamp, datapoint.rows_to_add))
+                .collect()
+        };
+        for model in ["even", "early_fill", "sparse_fill"] {
+            cfg.set_distribution_by(Some(model.to_string()));
+            let first = generate_datapoints(&cfg).unwrap();
+            let second = generate_datapoints(&cfg).unwrap();
+            assert_eq!(rows(&first), rows(&second), "model [{}]", model);
+        }
+    }
+
+    #[test]
+    fn test_rng_algorithm() {
+        let mut cfg = generation_test_config("sparse_fill", 10000, "10m");
+        cfg.set_timestamp_jitter(Some("500ms".to_string()));
+        cfg.set_seed(Some(42));
+
+        let rows = |datapoints: &[DataPoint]| -> Vec<(DateTime<Utc>, i16)> {
+            datapoints
+                .iter()
+                .map(|datapoint| (datapoint.timestamp, datapoint.rows_to_add))
+                .collect()
+        };
+        cfg.set_rng(Some("ChaCha8".to_string()));
+        let chacha8 = rows(&generate_datapoints(&cfg).unwrap());
+        assert_eq!(chacha8, rows(&generate_datapoints(&cfg).unwrap()));
+        // the raw stream too; same seed, same values
+        seed_generation_rng_with(Some(42), RngAlgorithm::ChaCha8);
+        let first: Vec<u32> = (0..4).map(|_| random_range(0..1000)).collect();
+        seed_generation_rng_with(Some(42), RngAlgorithm::ChaCha8);
+        let second: Vec<u32> = (0..4).map(|_| random_range(0..1000)).collect();
+        assert_eq!(first, second);
+
+        cfg.set_rng(Some("pcg".to_string()));
+        let pcg = rows(&generate_datapoints(&cfg).unwrap());
+        assert_eq!(pcg, rows(&generate_datapoints(&cfg).unwrap()));
+        assert_ne!(pcg, chacha8);
+
+        cfg.set_rng(Some("mersenne".to_string()));
         assert_eq!(
-            result.as_ref().unwrap().0.timestamp_millis(),
-            start_time_test.timestamp_millis()
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "unknown rng [mersenne]; expecting \"thread\", \"chacha8\" or \"pcg\""
         );
+    }
+
+    #[test]
+    fn test_coalesce_datapoints() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let at = |seconds: i64| start_time + Duration::seconds(seconds);
+        let datapoints = vec![
+            DataPoint::new(at(2), 5),
+            DataPoint::new(at(0), 3),
+            DataPoint::new(at(2), 7),
+            DataPoint::new(at(1), 1),
+            DataPoint::new(at(0), 4),
+            DataPoint::new(at(3), i16::MAX - 10),
+            DataPoint::new(at(3), 10),
+        ];
+        let coalesced = coalesce_datapoints(&datapoints).unwrap();
+        let rows: Vec<(DateTime<Utc>, i16)> = coalesced
+            .iter()
+            .map(|datapoint| (datapoint.timestamp, datapoint.rows_to_add))
+            .collect();
         assert_eq!(
-            result.as_ref().unwrap().1.timestamp_millis(),
-            end_time_test.timestamp_millis()
+            rows,
+            vec![
+                (at(0), 7),
+                (at(1), 1),
+                (at(2), 12),
+                // right at the i16 range
+                (at(3), i16::MAX),
+            ]
+        );
+        assert_eq!(
+            total_rows(&coalesced).unwrap(),
+            total_rows(&datapoints).unwrap()
         );
 
-        // [case][02] not using NOW(), provide a in-valid timestamp_format + start_timestamp
-        cfg.set_timestamp_format(Some("invalid-simply".to_string()));
-        let result = generate_time_range(&cfg);
-        if result.is_err() {
-            // failed to parse start_timestamp [2022-01-01T00:00:00.000+00:00] with format [invalid-simply]: input contains invalid characters
-            assert_eq!(
-                result
-                    .err()
-                    .unwrap()
-                    .to_string()
-                    .find("input contains invalid characters")
-                    .is_some(),
-                true
-            );
-        }
-
-        // [case][03] not using NOW(), provide a valid timestamp_format + in-Valid start_timestamp
-        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
-        cfg.set_start_timestamp(Some("invalid-timestamp-value".to_string()));
-        let result = generate_time_range(&cfg);
-        if result.is_err() {
-            // failed to parse start_timestamp [2022-01-01T00:00:00.000+00:00] with format [invalid-simply]: input contains invalid characters
-            assert_eq!(
-                result
-                    .err()
-                    .unwrap()
-                    .to_string()
-                    .find("input contains invalid characters")
-                    .is_some(),
-                true
-            );
-        }
+        // beyond the i16 range
+        let datapoints = vec![DataPoint::new(at(3), i16::MAX), DataPoint::new(at(3), 10)];
+        assert_eq!(
+            coalesce_datapoints(&datapoints).err().unwrap().to_string(),
+            "coalesced rows [32777] at [2022-01-01 00:00:03 UTC] are beyond the per-datapoint range of [-32768, 32767]"
+        );
 
-        // [case][04] using NOW(), compare with current time
-        // (discrepancies should be within 1 seconds, the start_time_test should be roughly 1 sec after the acutal call)
-        //cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
-        //cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
-        cfg.set_use_now_as_timestamp(Some(true));
-        start_time_test = Utc::now();
-        end_time_test = start_time_test + Duration::minutes(10);
-        let result = generate_time_range(&cfg);
-        if result.is_err() {
-            assert_eq!(result.err().unwrap().to_string(), "huh?");
-            return;
+        // wired into the generation; the jittered datapoints come out sorted and unique
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_timestamp_jitter(Some("2s".to_string()));
+        cfg.set_coalesce(Some(true));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        for pair in datapoints.windows(2) {
+            assert!(pair[0].timestamp < pair[1].timestamp);
         }
-        let start_diff =
-            result.as_ref().unwrap().0.timestamp_millis() - start_time_test.timestamp_millis();
-        let end_diff =
-            result.as_ref().unwrap().1.timestamp_millis() - end_time_test.timestamp_millis();
-        assert_eq!(start_diff >= 0 && start_diff <= 1000, true);
-        assert_eq!(end_diff >= 0 && end_diff <= 1000, true);
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
     }
 
     #[test]
-    fn test_pick_2_random_datapoint() {
-        // init loggers
-        app_init("./config/default/loggers.toml".to_string()).unwrap();
+    fn test_datapoint_stats() {
+        let cfg = generation_test_config("even", 10000, "10m");
+        let datapoints = generate_datapoints(&cfg).unwrap();
 
-        for _ in 0..20 {
-            let result = pick_2_random_datapoint(1000);
-            assert_eq!(result.0 != result.1, true);
+        let stats = datapoint_stats(&datapoints);
+        assert!((stats.mean - 10000.0 / 600.0).abs() < 1e-9, "{:?}", stats);
+        assert!(stats.min <= stats.max);
+        assert!(stats.median >= stats.min as f64 && stats.p95 <= stats.max as f64);
 
-            tracing::trace!("{} vs {}", result.0, result.1);
-        }
+        // a known small set
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints: Vec<DataPoint> = [0, 2, 4, 4, 5, 5, 7, 9]
+            .iter()
+            .enumerate()
+            .map(|(i, rows)| DataPoint::new(start_time + Duration::seconds(i as i64), *rows))
+            .collect();
+        assert_eq!(
+            datapoint_stats(&datapoints),
+            DistributionStats {
+                min: 0,
+                max: 9,
+                mean: 4.5,
+                median: 4.5,
+                p95: 9.0,
+                std_dev: (6.75_f64).sqrt(),
+                empty_slots: 1,
+            }
+        );
+        assert_eq!(datapoint_stats(&[]), DistributionStats::default());
     }
 
     #[test]
-    fn test_generate_datapoints_even() {
-        // init loggers
-        app_init("./config/default/loggers.toml".to_string()).unwrap();
+    fn test_histogram_bucketed() {
+        let cfg = generation_test_config("bimodal", 100000, "1h");
+        let datapoints = generate_datapoints(&cfg).unwrap();
 
-        let mut cfg = Config::new();
-        cfg.set_distribution_by(Some("even".to_string()));
-        cfg.set_number_of_entries(Some(10000));
-        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
-        cfg.set_use_now_as_timestamp(Some(false));
-        cfg.set_generation_duration(Some("10m".to_string()));
-        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        for bars in [1, 7, 20, 3600, 5000] {
+            let histogram = histogram_bucketed(&datapoints, bars);
+            assert_eq!(histogram.len(), bars);
+            assert_eq!(histogram.iter().sum::<u64>(), 100000, "{} bars", bars);
+        }
+        // 3 minutes per bar
+        let histogram = histogram_bucketed(&datapoints, 20);
+        assert_eq!(histogram[0], total_rows(&datapoints[..180]).unwrap());
 
-        let result = generate_datapoints(&cfg);
-        assert_eq!(result.is_err(), false);
-        tracing::trace!("{:?}", result.as_ref().unwrap());
+        assert_eq!(histogram_bucketed(&datapoints, 0), Vec::<u64>::new());
+        assert_eq!(histogram_bucketed(&[], 3), vec![0, 0, 0]);
+    }
 
-        let mut sum = 0;
-        let mut histogram = String::new();
-        let datapoints = result.as_ref().unwrap();
-        for datapoint in datapoints {
-            sum += datapoint.rows_to_add;
-            // [debug]
-            // [graph - histogram]
-            histogram.push_str(format!("timestamp: {} | ", datapoint.timestamp).as_str());
-            for _ in 0..datapoint.rows_to_add {
-                histogram.push_str(".");
+    #[test]
+    fn test_distribute_exact() {
+        let weights = [0.1, 0.25, 0.3, 0.05, 0.3];
+        for total in [0, 1, 7, 999, 10000] {
+            let slots = distribute_exact(total, &weights);
+            assert_eq!(slots.iter().sum::<u32>(), total);
+            // within 1 row of the exact share
+            for (slot, weight) in slots.iter().zip(weights.iter()) {
+                assert!((*slot as f64 - total as f64 * weight).abs() < 1.0);
             }
-            histogram.push_str("\n");
         }
-        tracing::info!("\n{}", histogram);
-        assert_eq!(sum as u32 == cfg.number_of_entries().unwrap(), true);
+        // relative weights need not sum up to 1; largest remainder first, ties to the earlier slot
+        assert_eq!(distribute_exact(10, &[1.0, 1.0, 1.0]), vec![4, 3, 3]);
+        assert_eq!(distribute_exact(10, &[2.0, 1.0, 2.0]), vec![4, 2, 4]);
+        assert_eq!(distribute_exact(5, &[0.0, 0.0]), vec![3, 2]);
+        assert_eq!(distribute_exact(3, &[-1.0, f64::NAN, 1.0]), vec![0, 0, 3]);
+        assert!(distribute_exact(10, &[]).is_empty());
     }
 
     #[test]
-    fn test_generate_datapoints_early_fill() {
+    fn test_expand_rows() {
         // init loggers
         app_init("./config/default/loggers.toml".to_string()).unwrap();
 
-        let mut cfg = Config::new();
-        cfg.set_distribution_by(Some("early_fill".to_string()));
-        cfg.set_number_of_entries(Some(10000));
-        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
-        cfg.set_use_now_as_timestamp(Some(false));
-        cfg.set_generation_duration(Some("10m".to_string()));
-        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        let cfg: Config = toml::from_str(
+            r#"
+            number_of_entries = 1000
+            timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+            use_now_as_timestamp = false
+            generation_duration = "1m"
+            start_timestamp = "2022-01-01T00:00:00.000+00:00"
+            distribution_by = "even"
 
-        let result = generate_datapoints(&cfg);
-        assert_eq!(result.is_err(), false);
-        tracing::trace!("{:?}", result.as_ref().unwrap());
+            [attributes]
+            "service.name" = ["cart", "checkout", "payment"]
+            host = ["host-1", "host-2"]
+            region = ["ap-southeast-1"]
+            unused = []
 
-        let mut sum = 0;
-        let mut histogram = String::new();
-        let datapoints = result.as_ref().unwrap();
-        for datapoint in datapoints {
-            sum += datapoint.rows_to_add;
-            // [debug]
-            // [graph - histogram]
-            histogram.push_str(format!("timestamp: {} | ", datapoint.timestamp).as_str());
-            for _ in 0..datapoint.rows_to_add {
-                histogram.push_str(".");
+            [templates]
+            message = "GET /api/{path} -> {status}"
+
+            [template_values]
+            path = ["users", "orders"]
+            status = ["200", "404", "500"]
+            "#,
+        )
+        .unwrap();
+        let pools = cfg.attributes().as_ref().unwrap();
+        let templates = RowTemplates::from_config(&cfg);
+        let datapoints = generate_datapoints(&cfg).unwrap();
+
+        let rows: Vec<ExpandedRow> = expand_rows(&datapoints, pools, &templates).collect();
+        assert_eq!(rows.len(), 1000);
+        let mut seen_services = std::collections::HashSet::new();
+        for row in rows.iter() {
+            let keys: Vec<&str> = row.attributes.iter().map(|(k, _)| k.as_str()).collect();
+            assert_eq!(keys, vec!["host", "region", "service.name"]);
+            for (key, value) in row.attributes.iter() {
+                assert!(pools[key].contains(value), "{} = {}", key, value);
             }
-            histogram.push_str("\n");
+            seen_services.insert(row.attributes[2].1.clone());
+
+            // only the configured values are substituted
+            assert_eq!(row.fields.len(), 1);
+            let (field, message) = &row.fields[0];
+            assert_eq!(field, "message");
+            let (path, status) = message
+                .strip_prefix("GET /api/")
+                .and_then(|rest| rest.split_once(" -> "))
+                .unwrap();
+            assert!(["users", "orders"].contains(&path), "{}", message);
+            assert!(["200", "404", "500"].contains(&status), "{}", message);
         }
-        tracing::info!("\n{}", histogram);
-        tracing::info!(
-            "sum: {} vs num_entries: {}",
-            sum,
-            cfg.number_of_entries().unwrap()
-        );
-        assert_eq!(sum as u32 == cfg.number_of_entries().unwrap(), true);
+        // randomly chosen; every candidate shows up across 1000 rows
+        assert_eq!(seen_services.len(), 3);
     }
 
     #[test]
-    fn test_generate_datapoints_sparse_fill() {
-        // init loggers
-        app_init("./config/default/loggers.toml".to_string()).unwrap();
+    fn test_max_entries() {
+        let mut cfg = generation_test_config("even", 200_000_000, "1h");
 
-        let mut cfg = Config::new();
-        cfg.set_distribution_by(Some("sparse_fill".to_string()));
+        // the default ceiling
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "number_of_entries [200000000] exceeds the max_entries safety limit [100000000]; raise `max_entries` explicitly if intended"
+        );
+        // a lowered ceiling
         cfg.set_number_of_entries(Some(10000));
-        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
-        cfg.set_use_now_as_timestamp(Some(false));
-        cfg.set_generation_duration(Some("10m".to_string()));
-        cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        cfg.set_max_entries(Some(5000));
+        assert!(generate_datapoints(&cfg).is_err());
+        // raised explicitly
+        cfg.set_max_entries(Some(10000));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
 
-        let result = generate_datapoints(&cfg);
-        assert_eq!(result.is_err(), false);
-        tracing::trace!("{:?}", result.as_ref().unwrap());
+        // the slot count is bounded too
+        cfg.set_generation_duration(Some("200d".to_string()));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "generation_duration of 17280000 seconds exceeds the maximum of 10000000 slots (1 per second); shorten `generation_duration`"
+        );
+    }
 
-        let mut sum = 0;
-        let mut histogram = String::new();
-        let datapoints = result.as_ref().unwrap();
-        for datapoint in datapoints {
-            sum += datapoint.rows_to_add;
-            // [debug]
-            // [graph - histogram]
-            histogram.push_str(format!("timestamp: {} | ", datapoint.timestamp).as_str());
-            for _ in 0..datapoint.rows_to_add {
-                histogram.push_str(".");
+    #[test]
+    fn test_rows_per_slot() {
+        // the shipped default config; 50M entries over 600 slots is beyond the per-slot range
+        let cfg = crate::config::load_config(
+            "config/default".to_string(),
+            "config/default".to_string(),
+            "config.toml".to_string(),
+            "config.toml".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "number_of_entries [50000000] over [600] slots needs at least [83334] rows per slot; at most 32767 per slot, lower the number_of_entries or raise the generation_duration"
+        );
+
+        // within the average but not within the front loaded slots of the early_fill model
+        let cfg = generation_test_config("early_fill", 300_000, "10s");
+        let message = generate_datapoints(&cfg).err().unwrap().to_string();
+        assert!(
+            message.starts_with("slot [") && message.contains("] of the early_fill model needs ["),
+            "{}",
+            message
+        );
+        assert!(message.ends_with(
+            "at most 32767 per slot, lower the number_of_entries or raise the generation_duration"
+        ));
+
+        // right at the edge every slot is full, yet never wrapped
+        for model in ["even", "sparse_fill"] {
+            let cfg = generation_test_config(model, 32767 * 10, "10s");
+            match generate_datapoints(&cfg) {
+                Ok(datapoints) => {
+                    assert_eq!(total_rows(&datapoints).unwrap(), 327670);
+                    assert!(datapoints.iter().all(|dp| dp.rows_to_add >= 0));
+                }
+                Err(e) => assert!(e.to_string().contains("at most 32767 per slot"), "{}", e),
             }
-            histogram.push_str("\n");
         }
-        tracing::info!("\n{}", histogram);
-        tracing::info!(
-            "sum: {} vs num_entries: {}",
-            sum,
-            cfg.number_of_entries().unwrap()
-        );
-        assert_eq!(sum as u32 == cfg.number_of_entries().unwrap(), true);
     }
+}
 
-    #[test]
-    fn test_generate_sparse_fill_zone_and_boundaries() {
-        // init loggers
-        app_init("./config/default/loggers.toml".to_string()).unwrap();
+/// property tests of the universal invariants over random (bounded) configs of every model.
+#[cfg(test)]
+mod proptests {
+    use proptest::prelude::*;
 
-        // table test(s) / parameterized test(s)
-        // parameters
-        // 1. data_zones_to_be_generated: &Vec<u32>,
-        // 2. generation_factor: u32,
-        // 3. start_time: DateTime<Utc>,
-        // 4. duration_in_seconds: i64
-        // 5. expect error message: str
-        // 6. expect number of data zones: u32 => (1.len() x 2.)
-        // 7. sum of vec![] in 1.
-        let test_cases = vec![
-            (
-                vec![100, 190, 100, 60],
-                6,
-                Utc::now(),
-                10 * 60,
-                4 * 6,
-                100 + 190 + 100 + 60,
-            ),
-            (
-                vec![30, 80, 120],
-                8,
-                Utc::now(),
-                8 * 60,
-                3 * 8,
-                30 + 80 + 120,
-            ),
-            (
-                vec![100, 190, 100, 60],
-                3,
-                Utc::now(),
-                10 * 60,
-                4 * 3,
-                100 + 190 + 100 + 60,
-            ),
-        ];
-        // iterate the test_cases
-        for (
-            data_zones_to_be_generated,
-            generation_factor,
-            start_time,
-            duration_in_seconds,
-            expect_number_of_data_zones,
-            expect_sum,
-        ) in test_cases
-        {
-            let data_zones = generate_sparse_fill_zone_and_boundaries(
-                &data_zones_to_be_generated,
-                generation_factor,
-                start_time,
-                duration_in_seconds,
-            );
-            assert_eq!(
-                data_zones.len() as u32,
-                expect_number_of_data_zones,
-                "expect {} zones created with {} rows altogether",
-                expect_number_of_data_zones,
-                expect_sum
-            );
-            let mut sum = 0;
-            for data_zone in data_zones.clone() {
-                sum += data_zone.num_rows_to_add;
+    use super::*;
+    use crate::config::{generation_test_config, ConfigStep};
+
+    // well beyond the i16 range of a slot over the shorter windows
+    const MAX_ENTRIES: u32 = 2_000_000;
+    const MAX_DURATION_IN_SECONDS: i64 = 600;
+
+    fn model_strategy() -> impl Strategy<Value = String> {
+        let names: Vec<String> = DistributionModelRegistry::with_builtin_models()
+            .names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        proptest::sample::select(names)
+    }
+
+    fn property_config(model: &str, entries: u32, duration_in_seconds: i64, seed: u64) -> Config {
+        let mut cfg =
+            generation_test_config(model, entries, format!("{}s", duration_in_seconds).as_str());
+        cfg.set_seed(Some(seed));
+        // the settings some models require
+        // valid steps never require more rows than the entries
+        let rate = (entries as i64 / duration_in_seconds) as u32;
+        cfg.set_steps(Some(vec![ConfigStep::new("0s", rate)]));
+        cfg.set_profile_path(Some("./tests/replay_profile.csv".to_string()));
+        cfg.set_weights(Some(vec![1.0, 2.0, 5.0, 2.0, 1.0]));
+        cfg
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn test_distribution_invariants(
+            model in model_strategy(),
+            entries in 1..=MAX_ENTRIES,
+            duration_in_seconds in 1..=MAX_DURATION_IN_SECONDS,
+            seed in any::<u64>(),
+        ) {
+            let cfg = property_config(&model, entries, duration_in_seconds, seed);
+            let (start_time, end_time) = generate_time_range(&cfg).unwrap();
+            // terminates (i.e. returns at all) with either the exact datapoints or a clean per-slot
+            // range error; never a wrapped slot
+            match generate_datapoints(&cfg) {
+                Ok(datapoints) => check_invariants(&datapoints, entries as u64, start_time, end_time)
+                    .map_err(|e| TestCaseError::fail(format!("[{}] {}", model, e)))?,
+                Err(e) => prop_assert!(
+                    e.to_string().contains("at most 32767 per slot"),
+                    "[{}] {}",
+                    model,
+                    e
+                ),
             }
-            assert_eq!(
-                sum as u32, expect_sum,
-                "expect {} zones created with {} rows altogether",
-                expect_number_of_data_zones, expect_sum
-            );
-            // all is good, trace a message
-            tracing::info!(
-                "{} zones created with {} rows altogether, distribution: {:?}",
-                expect_number_of_data_zones,
-                expect_sum,
-                data_zones
-            );
         }
     }
 }
@@ -1,41 +1,206 @@
 use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
 
+use chrono_tz::Tz;
 use getset::{Getters, Setters};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tracing::level_filters::LevelFilter;
 
 use robjetives_config::{read_config_folder, BackFillable};
 
+use crate::augmentation::{parse_start_timestamp, GenerationError};
+
 /// The configuration for the application.
 ///
 /// Most of the fields are optional as the configuration system is designed to be
 /// back-filled with the default values in case of missing custom values.
-#[derive(Debug, Deserialize, Getters, Setters)]
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Getters, Setters)]
 pub struct Config {
     #[getset(get = "pub", set = "pub")]
     number_of_entries: Option<u32>,
 
+    #[getset(get = "pub", set = "pub")]
+    scale: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    max_entries: Option<u64>,
+
+    #[getset(get = "pub", set = "pub")]
+    max_slots: Option<u32>,
+
     #[getset(get = "pub", set = "pub")]
     timestamp_format: Option<String>,
 
+    #[getset(get = "pub", set = "pub")]
+    timezone: Option<String>,
+
     #[getset(get = "pub", set = "pub")]
     use_now_as_timestamp: Option<bool>,
 
+    #[getset(get = "pub", set = "pub")]
+    prefer_start_timestamp: Option<bool>,
+
     #[getset(get = "pub", set = "pub")]
     generation_duration: Option<String>,
 
+    #[getset(get = "pub", set = "pub")]
+    slot_count: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    slot_width: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    warmup_duration: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    warmup_rate: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    single_instant: Option<bool>,
+
+    #[getset(get = "pub", set = "pub")]
+    end_inclusive: Option<bool>,
+
+    #[getset(get = "pub", set = "pub")]
+    order: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    repeat: Option<ConfigRepeat>,
+
     #[getset(get = "pub", set = "pub")]
     start_timestamp: Option<String>,
 
     #[getset(get = "pub", set = "pub")]
     distribution_by: Option<String>,
 
+    #[getset(get = "pub", set = "pub")]
+    min_rows_per_slot: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    timestamp_jitter: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    coalesce: Option<bool>,
+
+    #[getset(get = "pub", set = "pub")]
+    timestamp_truncate: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    export_from: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    export_to: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    seed: Option<u64>,
+
+    #[getset(get = "pub", set = "pub")]
+    rng: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    rounding: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    pad_window: Option<bool>,
+
+    #[getset(get = "pub", set = "pub")]
+    sparse_min_zones: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    sparse_max_zones: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    sparse_generation_factor: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    fill_gaps: Option<bool>,
+
+    #[getset(get = "pub", set = "pub")]
+    sparse_placement: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    peak1: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    peak2: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    peak1_ratio: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    decay_lambda: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    steps: Option<Vec<ConfigStep>>,
+
+    #[getset(get = "pub", set = "pub")]
+    anomalies: Option<Vec<ConfigAnomaly>>,
+
+    #[getset(get = "pub", set = "pub")]
+    profile_path: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    weights: Option<Vec<f64>>,
+
+    #[getset(get = "pub", set = "pub")]
+    transforms: Option<Vec<String>>,
+
+    #[getset(get = "pub", set = "pub")]
+    spike_factor: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    value_mode: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    unit: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    cumulative: Option<bool>,
+
+    #[getset(get = "pub", set = "pub")]
+    gauge_min: Option<i16>,
+
+    #[getset(get = "pub", set = "pub")]
+    gauge_max: Option<i16>,
+
+    #[getset(get = "pub", set = "pub")]
+    attributes: Option<HashMap<String, Vec<String>>>,
+
+    #[getset(get = "pub", set = "pub")]
+    templates: Option<HashMap<String, String>>,
+
+    #[getset(get = "pub", set = "pub")]
+    template_values: Option<HashMap<String, Vec<String>>>,
+
+    #[getset(get = "pub", set = "pub")]
+    static_attributes: Option<HashMap<String, String>>,
+
+    #[getset(get = "pub", set = "pub")]
+    replay: Option<bool>,
+
+    #[getset(get = "pub", set = "pub")]
+    speed: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    manifest_path: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    histogram_bars: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    verbosity: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    signals: Option<Vec<String>>,
+
     #[getset(get = "pub", set = "pub")]
     #[serde(rename = "exporter")]
     exporters: Option<Vec<ConfigExporter>>,
 }
 
 /// The configuration for the exporter(s) section.
-#[derive(Debug, Deserialize, Getters, Setters)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Getters, Setters)]
 pub struct ConfigExporter {
     #[getset(get = "pub", set = "pub")]
     name: Option<String>,
@@ -46,37 +211,683 @@ pub struct ConfigExporter {
     #[getset(get = "pub", set = "pub")]
     enabled: Option<bool>,
 
+    #[getset(get = "pub", set = "pub")]
+    field_merge: Option<String>,
+
+    #[getset(get = "pub", set = "pub")]
+    weight: Option<u32>,
+
+    #[getset(get = "pub", set = "pub")]
+    max_export_rate: Option<f64>,
+
+    #[getset(get = "pub", set = "pub")]
+    flag_anomalies: Option<bool>,
+
     #[getset(get = "pub", set = "pub")]
     fields: Option<HashMap<String, String>>,
 }
 
+/// A level of the `step` distribution model; from `at` (offset from the start) onwards,
+/// every slot gets `rate` rows until the next step.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Getters, Setters)]
+pub struct ConfigStep {
+    #[getset(get = "pub", set = "pub")]
+    at: String,
+
+    #[getset(get = "pub", set = "pub")]
+    rate: u32,
+}
+
+impl ConfigStep {
+    pub fn new(at: &str, rate: u32) -> Self {
+        ConfigStep {
+            at: at.to_string(),
+            rate,
+        }
+    }
+}
+
+/// An anomaly injected at the fractional position `at` (0..1) of the window; a `drop` or a `spike`
+/// of the given `magnitude` (see `Anomaly`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Getters, Setters)]
+pub struct ConfigAnomaly {
+    #[getset(get = "pub", set = "pub")]
+    at: f64,
+
+    #[getset(get = "pub", set = "pub")]
+    kind: String,
+
+    #[getset(get = "pub", set = "pub")]
+    magnitude: Option<f64>,
+}
+
+impl ConfigAnomaly {
+    pub fn new(at: f64, kind: &str, magnitude: Option<f64>) -> Self {
+        ConfigAnomaly {
+            at,
+            kind: kind.to_string(),
+            magnitude,
+        }
+    }
+}
+
+/// Repeats the generation window `count` times, each window offset by `interval` from the
+/// previous one (e.g. the same daily pattern over 3 days).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Getters, Setters)]
+pub struct ConfigRepeat {
+    #[getset(get = "pub", set = "pub")]
+    count: u32,
+
+    #[getset(get = "pub", set = "pub")]
+    interval: String,
+}
+
+impl ConfigRepeat {
+    pub fn new(count: u32, interval: &str) -> Self {
+        ConfigRepeat {
+            count,
+            interval: interval.to_string(),
+        }
+    }
+}
+
+/// The CLI flag enabling just the named exporter (and disabling the rest) at runtime.
+pub const ONLY_EXPORTER_ARG: &str = "--only-exporter";
+
+/// The CLI flag silencing the tracing output below the errors; same as `--verbosity error`.
+pub const QUIET_ARG: &str = "--quiet";
+
+/// The CLI flag loading and validating the config (then exiting) without generating anything.
+pub const VALIDATE_ONLY_ARG: &str = "--validate-only";
+
+/// The prefix of the environment variables overriding the config (e.g. `OTEL_BROCCOLI_SEED`).
+pub const ENV_OVERRIDE_PREFIX: &str = "OTEL_BROCCOLI_";
+
+/// Values overriding the loaded config; only the `Some` values are applied (see `Config::apply_overrides`).
+///
+/// The precedence is: back-fill < custom < env < CLI; i.e. the loaded (back-filled) config is
+/// overridden by the env overrides which in turn are overridden by the CLI overrides.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConfigOverrides {
+    pub number_of_entries: Option<u32>,
+    pub use_now_as_timestamp: Option<bool>,
+    pub prefer_start_timestamp: Option<bool>,
+    pub generation_duration: Option<String>,
+    pub start_timestamp: Option<String>,
+    pub distribution_by: Option<String>,
+    pub timestamp_jitter: Option<String>,
+    pub seed: Option<u64>,
+    pub replay: Option<bool>,
+    pub speed: Option<f64>,
+    pub manifest_path: Option<String>,
+    pub verbosity: Option<String>,
+}
+
+impl ConfigOverrides {
+    /// The overrides from the environment variables; the config field name in upper case
+    /// prefixed with `OTEL_BROCCOLI_` (e.g. `OTEL_BROCCOLI_NUMBER_OF_ENTRIES=1000`).
+    ///
+    /// # Errors
+    ///
+    /// If a value cannot be parsed into the field's type.
+    ///
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        ConfigOverrides::from_lookup(|name| {
+            let key = format!("{}{}", ENV_OVERRIDE_PREFIX, name.to_uppercase());
+            std::env::var(&key).ok().map(|value| (key, value))
+        })
+    }
+
+    /// The overrides from the command line args; the config field name in kebab case as a flag
+    /// followed by the value (e.g. `--number-of-entries 1000`). Unrelated args are ignored.
+    ///
+    /// # Errors
+    ///
+    /// If a value is missing or cannot be parsed into the field's type.
+    ///
+    pub fn from_args(args: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut missing_value: Option<String> = None;
+        let overrides = ConfigOverrides::from_lookup(|name| {
+            let flag = format!("--{}", name.replace('_', "-"));
+            let idx = args.iter().position(|arg| *arg == flag)?;
+            match args.get(idx + 1) {
+                Some(value) => Some((flag, value.clone())),
+                None => {
+                    missing_value.get_or_insert(flag);
+                    None
+                }
+            }
+        })?;
+        match missing_value {
+            Some(flag) => Err(format!("missing value of the [{}] arg", flag).into()),
+            None => Ok(overrides),
+        }
+    }
+
+    /// Build the overrides by looking up every field by its name; the lookup returns the
+    /// source (env var / flag, for the error message) and the raw value.
+    fn from_lookup<F>(mut lookup: F) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: FnMut(&str) -> Option<(String, String)>,
+    {
+        fn parse<T: std::str::FromStr>(
+            lookup: &mut dyn FnMut(&str) -> Option<(String, String)>,
+            name: &str,
+        ) -> Result<Option<T>, Box<dyn std::error::Error>> {
+            match lookup(name) {
+                Some((source, value)) => value
+                    .trim()
+                    .parse::<T>()
+                    .map(Some)
+                    .map_err(|_| format!("invalid value [{}] of [{}]", value, source).into()),
+                None => Ok(None),
+            }
+        }
+
+        let lookup: &mut dyn FnMut(&str) -> Option<(String, String)> = &mut lookup;
+        Ok(ConfigOverrides {
+            number_of_entries: parse(lookup, "number_of_entries")?,
+            use_now_as_timestamp: parse(lookup, "use_now_as_timestamp")?,
+            prefer_start_timestamp: parse(lookup, "prefer_start_timestamp")?,
+            generation_duration: parse(lookup, "generation_duration")?,
+            start_timestamp: parse(lookup, "start_timestamp")?,
+            distribution_by: parse(lookup, "distribution_by")?,
+            timestamp_jitter: parse(lookup, "timestamp_jitter")?,
+            seed: parse(lookup, "seed")?,
+            replay: parse(lookup, "replay")?,
+            speed: parse(lookup, "speed")?,
+            manifest_path: parse(lookup, "manifest_path")?,
+            verbosity: parse(lookup, "verbosity")?,
+        })
+    }
+}
+
 impl Config {
+    /// A config with every field unset; the same as `Config::default()`.
     pub fn new() -> Self {
-        Config {
-            number_of_entries: None,
-            timestamp_format: None,
-            use_now_as_timestamp: None,
-            generation_duration: None,
-            start_timestamp: None,
-            distribution_by: None,
-            exporters: None,
+        Config::default()
+    }
+
+    /// Parse the custom and the backfill configs from TOML strings and return a Config object (back-filled).
+    ///
+    /// Same as `load_config` minus the filesystem; handy for embedding and testing.
+    pub fn from_str(custom: &str, backfill: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        Config::from_str_with(custom, ConfigFormat::Toml, backfill, ConfigFormat::Toml)
+    }
+
+    /// Same as `from_str` with the format of each config given.
+    pub fn from_str_with(
+        custom: &str,
+        custom_format: ConfigFormat,
+        backfill: &str,
+        backfill_format: ConfigFormat,
+    ) -> Result<Config, Box<dyn std::error::Error>> {
+        let mut config = custom_format.parse(custom)?;
+        // what is written in the custom config; the back-filled values are not ambiguous
+        config.validate_timestamp_preference()?;
+        let backfill_config = backfill_format.parse(backfill)?;
+
+        config.back_fill(&backfill_config);
+        config.validate()?;
+        config.assert_invariants()?;
+        config.validate_exporters()?;
+        Ok(config)
+    }
+
+    /// Check a config as written (i.e. the custom config before the back-fill) for an ambiguous
+    /// start; a back-filled `start_timestamp` (e.g. the default one) is never ambiguous, now wins.
+    ///
+    /// # Errors
+    ///
+    /// If both `use_now_as_timestamp = true` and `start_timestamp` are set without an explicit
+    /// `prefer_start_timestamp` (true = `start_timestamp` wins, false = now wins).
+    ///
+    pub fn validate_timestamp_preference(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.use_now_as_timestamp == Some(true)
+            && self.start_timestamp.is_some()
+            && self.prefer_start_timestamp.is_none()
+        {
+            return Err(
+                "both use_now_as_timestamp = true and start_timestamp are set; \
+                set prefer_start_timestamp (true = start_timestamp wins, false = now wins)"
+                    .into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Check the config for conflicting settings.
+    ///
+    /// # Errors
+    ///
+    /// If the window is not exactly one of `generation_duration` or `slot_count` + `slot_width`;
+    /// if only one of `warmup_duration` and `warmup_rate` is set; or see `validate_start_timestamp`.
+    ///
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.slot_count.is_some() != self.slot_width.is_some() {
+            return Err("slot_count and slot_width must be set together".into());
+        }
+        if self.slot_count.is_some() && self.generation_duration.is_some() {
+            return Err(
+                "set either generation_duration or slot_count + slot_width, not both".into(),
+            );
+        }
+        if self.warmup_duration.is_some() != self.warmup_rate.is_some() {
+            return Err("warmup_duration and warmup_rate must be set together".into());
         }
+        self.validate_start_timestamp()
+    }
+
+    /// Trial parse the `start_timestamp` with the `timestamp_format` (the same parse as the
+    /// generation) when both are set and the `start_timestamp` is in use; i.e. a mismatched pair
+    /// fails before any generation work.
+    ///
+    /// # Errors
+    ///
+    /// `GenerationError::TimestampParse` if the `start_timestamp` does not match the format; or
+    /// if the `start_timestamp` is in use but not set.
+    ///
+    pub fn validate_start_timestamp(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let in_use = match self.use_now_as_timestamp {
+            Some(use_now) => !use_now || self.prefer_start_timestamp.unwrap_or(false),
+            None => false,
+        };
+        if in_use && self.start_timestamp.is_none() {
+            return Err(
+                "start_timestamp is not set; required unless use_now_as_timestamp = true".into(),
+            );
+        }
+        if let (true, Some(start_timestamp), Some(timestamp_format)) =
+            (in_use, &self.start_timestamp, &self.timestamp_format)
+        {
+            parse_start_timestamp(start_timestamp, timestamp_format).map_err(|source| {
+                GenerationError::TimestampParse {
+                    value: start_timestamp.clone(),
+                    format: timestamp_format.clone(),
+                    source,
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Check the exporters (after the back-fill merge) for duplicated names; a duplicate would be
+    /// dispatched twice.
+    ///
+    /// # Errors
+    ///
+    /// The 1st exporter name shared by several exporters; weighted shards (every exporter sharing
+    /// the name has a `weight`) are the exception as they split the rows instead. Or the 1st
+    /// unknown `field_merge`.
+    ///
+    pub fn validate_exporters(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let exporters = self.exporters.as_deref().unwrap_or_default();
+        for exporter in exporters {
+            exporter.field_merge_strategy()?;
+            let same_name: Vec<&ConfigExporter> = exporters
+                .iter()
+                .filter(|e| e.name == exporter.name)
+                .collect();
+            if same_name.len() > 1 && same_name.iter().any(|e| e.weight.is_none()) {
+                return Err(format!(
+                    "duplicate exporter [{}]; exporter names must be unique (except weighted shards)",
+                    exporter.name.as_deref().unwrap_or_default()
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the back-fill merge left no exporter with an unset `name`, `verbose`, `enabled`
+    /// or `fields`; i.e. "all NONE values must be gone by now".
+    ///
+    /// # Errors
+    ///
+    /// Every violating exporter (by name, or by position if unnamed) with its unset values.
+    ///
+    pub fn assert_invariants(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let violations: Vec<String> = self
+            .exporters
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, exporter)| {
+                let unset: Vec<&str> = [
+                    ("name", exporter.name.is_none()),
+                    ("verbose", exporter.verbose.is_none()),
+                    ("enabled", exporter.enabled.is_none()),
+                    ("fields", exporter.fields.is_none()),
+                ]
+                .into_iter()
+                .filter(|(_, unset)| *unset)
+                .map(|(value, _)| value)
+                .collect();
+                if unset.is_empty() {
+                    return None;
+                }
+                let exporter_name = match exporter.name.as_deref() {
+                    Some(name) => format!("[{}]", name),
+                    None => format!("#{}", idx + 1),
+                };
+                Some(format!("{} {}", exporter_name, unset.join(", ")))
+            })
+            .collect();
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "exporter(s) with unset values after the back-fill merge: {}",
+            violations.join("; ")
+        )
+        .into())
+    }
+
+    /// The timezone to render the exported timestamps in; UTC if `timezone` is not set.
+    ///
+    /// # Errors
+    ///
+    /// If `timezone` is not a known IANA timezone name (e.g. "Asia/Hong_Kong").
+    ///
+    pub fn timezone_or_utc(&self) -> Result<Tz, Box<dyn std::error::Error>> {
+        match self.timezone() {
+            Some(timezone) => timezone.parse::<Tz>().map_err(|_| {
+                format!(
+                    "unknown timezone [{}]; expecting an IANA name like \"Asia/Hong_Kong\"",
+                    timezone
+                )
+                .into()
+            }),
+            None => Ok(Tz::UTC),
+        }
+    }
+
+    /// Apply the overrides; only the `Some` values replace the current ones.
+    ///
+    /// Both the env and the CLI overrides funnel through here; apply them in the precedence
+    /// order (back-fill < custom < env < CLI), i.e. the env overrides first then the CLI ones.
+    pub fn apply_overrides(&mut self, overrides: ConfigOverrides) {
+        if overrides.number_of_entries.is_some() {
+            self.set_number_of_entries(overrides.number_of_entries);
+        }
+        if overrides.use_now_as_timestamp.is_some() {
+            self.set_use_now_as_timestamp(overrides.use_now_as_timestamp);
+        }
+        if overrides.prefer_start_timestamp.is_some() {
+            self.set_prefer_start_timestamp(overrides.prefer_start_timestamp);
+        }
+        if overrides.generation_duration.is_some() {
+            self.set_generation_duration(overrides.generation_duration);
+            // replaces a slot_count + slot_width window
+            self.set_slot_count(None);
+            self.set_slot_width(None);
+        }
+        if overrides.start_timestamp.is_some() {
+            self.set_start_timestamp(overrides.start_timestamp);
+        }
+        if overrides.distribution_by.is_some() {
+            self.set_distribution_by(overrides.distribution_by);
+        }
+        if overrides.timestamp_jitter.is_some() {
+            self.set_timestamp_jitter(overrides.timestamp_jitter);
+        }
+        if overrides.seed.is_some() {
+            self.set_seed(overrides.seed);
+        }
+        if overrides.replay.is_some() {
+            self.set_replay(overrides.replay);
+        }
+        if overrides.speed.is_some() {
+            self.set_speed(overrides.speed);
+        }
+        if overrides.manifest_path.is_some() {
+            self.set_manifest_path(overrides.manifest_path);
+        }
+        if overrides.verbosity.is_some() {
+            self.set_verbosity(overrides.verbosity);
+        }
+    }
+
+    /// Layer the env overrides and then the CLI overrides (see `ConfigOverrides`) over the
+    /// loaded config, completing the precedence: back-fill < custom < env < CLI.
+    ///
+    /// `--only-exporter <name>` then enables just the named exporter (see `enable_only_exporter`).
+    /// `--quiet` sets the `verbosity` to `error`.
+    ///
+    /// # Errors
+    ///
+    /// If an env / CLI value cannot be parsed or the `--only-exporter` is not configured.
+    ///
+    pub fn merge_cli(&mut self, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.apply_overrides(ConfigOverrides::from_env()?);
+        self.apply_overrides(ConfigOverrides::from_args(args)?);
+        if let Some(idx) = args.iter().position(|arg| arg == ONLY_EXPORTER_ARG) {
+            let name = args
+                .get(idx + 1)
+                .ok_or(format!("missing value of the [{}] arg", ONLY_EXPORTER_ARG))?;
+            self.enable_only_exporter(name)?;
+        }
+        if args.iter().any(|arg| arg == QUIET_ARG) {
+            self.set_verbosity(Some("error".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The tracing level filter of the `verbosity`; `None` if unset (the loggers.toml levels
+    /// apply).
+    ///
+    /// # Errors
+    ///
+    /// If `verbosity` is none of `off`, `error`, `warn`, `info`, `debug` and `trace`.
+    ///
+    pub fn verbosity_level(&self) -> Result<Option<LevelFilter>, Box<dyn std::error::Error>> {
+        match self.verbosity.as_deref() {
+            None => Ok(None),
+            Some(verbosity) => match verbosity.to_lowercase().as_str() {
+                "off" => Ok(Some(LevelFilter::OFF)),
+                "error" => Ok(Some(LevelFilter::ERROR)),
+                "warn" => Ok(Some(LevelFilter::WARN)),
+                "info" => Ok(Some(LevelFilter::INFO)),
+                "debug" => Ok(Some(LevelFilter::DEBUG)),
+                "trace" => Ok(Some(LevelFilter::TRACE)),
+                _ => Err(format!(
+                    "unknown verbosity [{}]; expecting \"off\", \"error\", \"warn\", \"info\", \"debug\" or \"trace\"",
+                    verbosity
+                )
+                .into()),
+            },
+        }
+    }
+
+    /// Enable the named exporter(s) and disable the rest regardless of their `enabled` flags;
+    /// handy for debugging a single sink.
+    ///
+    /// # Errors
+    ///
+    /// If no exporter has the name; the configured names are listed.
+    ///
+    pub fn enable_only_exporter(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let exporters = self.exporters.get_or_insert_with(Vec::new);
+        if !exporters.iter().any(|e| e.name.as_deref() == Some(name)) {
+            return Err(format!(
+                "unknown exporter [{}] of [{}]; configured exporters: {}",
+                name,
+                ONLY_EXPORTER_ARG,
+                exporters
+                    .iter()
+                    .map(|e| e.name.clone().unwrap_or_default())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+            .into());
+        }
+        for exporter in exporters.iter_mut() {
+            let enabled = exporter.name.as_deref() == Some(name);
+            exporter.set_enabled(Some(enabled));
+        }
+        Ok(())
+    }
+
+    /// Serialize the config (typically the effective one after back-fill and overrides) into TOML.
+    ///
+    /// The output round-trips; parsing it back with `toml::from_str` yields an equal `Config`.
+    pub fn to_toml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(toml::to_string(self)?)
     }
 }
 
+/// Fluent construction of a `Config` in code (e.g. in tests); the fields not set stay unset.
+///
+/// ```
+/// use otel_broccoli::config::ConfigBuilder;
+///
+/// let cfg = ConfigBuilder::new()
+///     .number_of_entries(1000)
+///     .distribution("even")
+///     .duration("10m")
+///     .build()
+///     .unwrap();
+/// assert_eq!(*cfg.number_of_entries(), Some(1000));
+/// ```
+#[derive(Debug, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        ConfigBuilder::default()
+    }
+
+    pub fn number_of_entries(mut self, number_of_entries: u32) -> Self {
+        self.config.set_number_of_entries(Some(number_of_entries));
+        self
+    }
+
+    /// the `distribution_by` model (or an alias of it).
+    pub fn distribution(mut self, distribution_by: &str) -> Self {
+        self.config
+            .set_distribution_by(Some(distribution_by.to_string()));
+        self
+    }
+
+    /// the `generation_duration`, e.g. "10m".
+    pub fn duration(mut self, generation_duration: &str) -> Self {
+        self.config
+            .set_generation_duration(Some(generation_duration.to_string()));
+        self
+    }
+
+    /// Generate from `start_timestamp` (parsed with `timestamp_format`) instead of now.
+    pub fn start_timestamp(mut self, start_timestamp: &str, timestamp_format: &str) -> Self {
+        self.config.set_use_now_as_timestamp(Some(false));
+        self.config
+            .set_start_timestamp(Some(start_timestamp.to_string()));
+        self.config
+            .set_timestamp_format(Some(timestamp_format.to_string()));
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.set_seed(Some(seed));
+        self
+    }
+
+    pub fn add_exporter(mut self, exporter: ConfigExporter) -> Self {
+        self.config
+            .exporters
+            .get_or_insert_with(Vec::new)
+            .push(exporter);
+        self
+    }
+
+    /// The built config; checked by `Config::validate_timestamp_preference`, `Config::validate`
+    /// and `Config::validate_exporters`.
+    ///
+    /// # Errors
+    ///
+    /// See `Config::validate_timestamp_preference`, `Config::validate` and
+    /// `Config::validate_exporters`.
+    ///
+    pub fn build(self) -> Result<Config, Box<dyn std::error::Error>> {
+        self.config.validate_timestamp_preference()?;
+        self.config.validate()?;
+        self.config.validate_exporters()?;
+        Ok(self.config)
+    }
+}
+
+/// The config of the generation tests (the loggers initialized); `number_of_entries` rows of the
+/// `distribution_by` model over the `generation_duration` from 2022-01-01T00:00:00Z.
+#[cfg(test)]
+pub(crate) fn generation_test_config(
+    distribution_by: &str,
+    number_of_entries: u32,
+    generation_duration: &str,
+) -> Config {
+    crate::app_init("./config/default/loggers.toml".to_string()).unwrap();
+    ConfigBuilder::new()
+        .distribution(distribution_by)
+        .number_of_entries(number_of_entries)
+        .duration(generation_duration)
+        .start_timestamp("2022-01-01T00:00:00.000+00:00", "%Y-%m-%dT%H:%M:%S%.f%:z")
+        .build()
+        .unwrap()
+}
+
 impl BackFillable for Config {
     fn back_fill(&mut self, from: &Self) {
-        if self.number_of_entries.is_none() {
+        // the steps (of the step model) derive the total; a default total would override them
+        if self.number_of_entries.is_none() && self.steps.is_none() {
             self.set_number_of_entries(from.number_of_entries);
         }
+        if self.scale.is_none() {
+            self.set_scale(from.scale);
+        }
+        if self.max_entries.is_none() {
+            self.set_max_entries(from.max_entries);
+        }
+        if self.max_slots.is_none() {
+            self.set_max_slots(from.max_slots);
+        }
         if self.timestamp_format.is_none() {
             self.set_timestamp_format(from.timestamp_format.clone());
         }
+        if self.timezone.is_none() {
+            self.set_timezone(from.timezone.clone());
+        }
         if self.use_now_as_timestamp.is_none() {
             self.set_use_now_as_timestamp(from.use_now_as_timestamp);
         }
-        if self.generation_duration.is_none() {
-            self.set_generation_duration(from.generation_duration.clone());
+        if self.prefer_start_timestamp.is_none() {
+            self.set_prefer_start_timestamp(from.prefer_start_timestamp);
+        }
+        // the window is either the generation_duration or the slot_count + slot_width; never both
+        if self.generation_duration.is_none() && self.slot_count.is_none() {
+            if from.slot_count.is_some() {
+                self.set_slot_count(from.slot_count);
+                self.set_slot_width(from.slot_width.clone());
+            } else {
+                self.set_generation_duration(from.generation_duration.clone());
+            }
+        }
+        // the warmup is a pair too
+        if self.warmup_duration.is_none() && self.warmup_rate.is_none() {
+            self.set_warmup_duration(from.warmup_duration.clone());
+            self.set_warmup_rate(from.warmup_rate);
+        }
+        if self.single_instant.is_none() {
+            self.set_single_instant(from.single_instant);
+        }
+        if self.end_inclusive.is_none() {
+            self.set_end_inclusive(from.end_inclusive);
+        }
+        if self.order.is_none() {
+            self.set_order(from.order.clone());
+        }
+        if self.repeat.is_none() {
+            self.set_repeat(from.repeat.clone());
         }
         if self.start_timestamp.is_none() {
             self.set_start_timestamp(from.start_timestamp.clone());
@@ -84,15 +895,137 @@ impl BackFillable for Config {
         if self.distribution_by.is_none() {
             self.set_distribution_by(from.distribution_by.clone());
         }
+        if self.timestamp_jitter.is_none() {
+            self.set_timestamp_jitter(from.timestamp_jitter.clone());
+        }
+        if self.min_rows_per_slot.is_none() {
+            self.set_min_rows_per_slot(from.min_rows_per_slot);
+        }
+        if self.coalesce.is_none() {
+            self.set_coalesce(from.coalesce);
+        }
+        if self.timestamp_truncate.is_none() {
+            self.set_timestamp_truncate(from.timestamp_truncate.clone());
+        }
+        if self.export_from.is_none() {
+            self.set_export_from(from.export_from.clone());
+        }
+        if self.export_to.is_none() {
+            self.set_export_to(from.export_to.clone());
+        }
+        if self.seed.is_none() {
+            self.set_seed(from.seed);
+        }
+        if self.rng.is_none() {
+            self.set_rng(from.rng.clone());
+        }
+        if self.rounding.is_none() {
+            self.set_rounding(from.rounding.clone());
+        }
+        if self.pad_window.is_none() {
+            self.set_pad_window(from.pad_window);
+        }
+        if self.sparse_min_zones.is_none() {
+            self.set_sparse_min_zones(from.sparse_min_zones);
+        }
+        if self.sparse_max_zones.is_none() {
+            self.set_sparse_max_zones(from.sparse_max_zones);
+        }
+        if self.sparse_generation_factor.is_none() {
+            self.set_sparse_generation_factor(from.sparse_generation_factor);
+        }
+        if self.fill_gaps.is_none() {
+            self.set_fill_gaps(from.fill_gaps);
+        }
+        if self.sparse_placement.is_none() {
+            self.set_sparse_placement(from.sparse_placement.clone());
+        }
+        if self.peak1.is_none() {
+            self.set_peak1(from.peak1);
+        }
+        if self.peak2.is_none() {
+            self.set_peak2(from.peak2);
+        }
+        if self.peak1_ratio.is_none() {
+            self.set_peak1_ratio(from.peak1_ratio);
+        }
+        if self.decay_lambda.is_none() {
+            self.set_decay_lambda(from.decay_lambda);
+        }
+        if self.steps.is_none() {
+            self.set_steps(from.steps.clone());
+        }
+        if self.anomalies.is_none() {
+            self.set_anomalies(from.anomalies.clone());
+        }
+        if self.profile_path.is_none() {
+            self.set_profile_path(from.profile_path.clone());
+        }
+        if self.weights.is_none() {
+            self.set_weights(from.weights.clone());
+        }
+        if self.transforms.is_none() {
+            self.set_transforms(from.transforms.clone());
+        }
+        if self.spike_factor.is_none() {
+            self.set_spike_factor(from.spike_factor);
+        }
+        if self.value_mode.is_none() {
+            self.set_value_mode(from.value_mode.clone());
+        }
+        if self.unit.is_none() {
+            self.set_unit(from.unit.clone());
+        }
+        if self.cumulative.is_none() {
+            self.set_cumulative(from.cumulative);
+        }
+        if self.gauge_min.is_none() {
+            self.set_gauge_min(from.gauge_min);
+        }
+        if self.gauge_max.is_none() {
+            self.set_gauge_max(from.gauge_max);
+        }
+        if self.attributes.is_none() {
+            self.set_attributes(from.attributes.clone());
+        }
+        if self.templates.is_none() {
+            self.set_templates(from.templates.clone());
+        }
+        if self.template_values.is_none() {
+            self.set_template_values(from.template_values.clone());
+        }
+        if self.static_attributes.is_none() {
+            self.set_static_attributes(from.static_attributes.clone());
+        }
+        if self.replay.is_none() {
+            self.set_replay(from.replay);
+        }
+        if self.speed.is_none() {
+            self.set_speed(from.speed);
+        }
+        if self.manifest_path.is_none() {
+            self.set_manifest_path(from.manifest_path.clone());
+        }
+        if self.histogram_bars.is_none() {
+            self.set_histogram_bars(from.histogram_bars);
+        }
+        if self.verbosity.is_none() {
+            self.set_verbosity(from.verbosity.clone());
+        }
+        if self.signals.is_none() {
+            self.set_signals(from.signals.clone());
+        }
         // not that simple; kind of merge logic instead...
+        // a layer without any `[[exporter]]` has none to back-fill
+        let from_exporters = from.exporters.as_deref().unwrap_or_default();
         if self.exporters.is_none() {
             let mut list: Vec<ConfigExporter> = vec![];
-            for e in from.exporters.as_ref().unwrap() {
+            for e in from_exporters {
                 let mut exporter = ConfigExporter {
-                    name: None,
                     verbose: Some(false),
                     enabled: Some(false),
                     fields: Some(HashMap::new()),
+                    ..Default::default()
                 };
                 exporter.back_fill(e);
                 list.push(exporter);
@@ -109,6 +1042,10 @@ impl BackFillable for Config {
             // });
             for e in self_exporters.as_mut().unwrap().iter_mut() {
                 types_in_string.push(e.name.as_ref().unwrap().clone());
+                // same exporter within the back-fill; merge with it (fields based on `field_merge`)
+                if let Some(from_e) = from_exporters.iter().find(|from_e| from_e.name == e.name) {
+                    e.back_fill(from_e);
+                }
                 // make sure the exporter components are non None at this point
                 if e.verbose.is_none() {
                     e.set_verbose(Some(false));
@@ -121,16 +1058,11 @@ impl BackFillable for Config {
                 }
             }
 
-            for e in from.exporters.as_ref().unwrap() {
+            for e in from_exporters {
                 if !types_in_string.contains(&e.name.as_ref().unwrap().clone()) {
                     let mut exporter = ConfigExporter {
-                        name: None,
-                        verbose: None,
-                        enabled: None,
                         fields: Some(HashMap::new()),
-                        // verbose: None,
-                        // enabled: None,
-                        // fields: None,
+                        ..Default::default()
                     };
                     exporter.back_fill(e);
                     self.exporters.as_mut().unwrap().push(exporter);
@@ -143,6 +1075,36 @@ impl BackFillable for Config {
     }
 }
 
+/// How the exporter `fields` are back-filled when both the custom and the back-fill exporters have them.
+#[derive(Debug, PartialEq)]
+pub enum FieldMergeStrategy {
+    /// key-by-key merge, the custom value wins on the same key (default).
+    Merge,
+    /// the custom fields entirely replace the back-fill ones.
+    Replace,
+}
+
+impl ConfigExporter {
+    /// The `field_merge` strategy; `merge` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `field_merge` is neither `merge` nor `replace`.
+    ///
+    pub fn field_merge_strategy(&self) -> Result<FieldMergeStrategy, Box<dyn std::error::Error>> {
+        match self.field_merge.as_deref() {
+            None | Some("merge") => Ok(FieldMergeStrategy::Merge),
+            Some("replace") => Ok(FieldMergeStrategy::Replace),
+            Some(unknown) => Err(format!(
+                "unknown field_merge [{}] for exporter [{}]; expecting \"merge\" or \"replace\"",
+                unknown,
+                self.name.as_deref().unwrap_or_default()
+            )
+            .into()),
+        }
+    }
+}
+
 impl BackFillable for ConfigExporter {
     fn back_fill(&mut self, from: &Self) {
         if self.name.is_none() {
@@ -162,6 +1124,18 @@ impl BackFillable for ConfigExporter {
                 self.enabled = Some(false);
             }
         }
+        if self.field_merge.is_none() {
+            self.set_field_merge(from.field_merge.clone());
+        }
+        if self.weight.is_none() {
+            self.set_weight(from.weight);
+        }
+        if self.max_export_rate.is_none() {
+            self.set_max_export_rate(from.max_export_rate);
+        }
+        if self.flag_anomalies.is_none() {
+            self.set_flag_anomalies(from.flag_anomalies);
+        }
         // not that easy... it is more of combining the keys within the map
         if self.fields.is_none() {
             self.set_fields(from.fields.clone());
@@ -173,6 +1147,11 @@ impl BackFillable for ConfigExporter {
                 // only necessary to back-fill if the `from` struct has fields
                 return;
             }
+            // an unknown strategy is rejected by `validate_exporters` once merged
+            if matches!(self.field_merge_strategy(), Ok(FieldMergeStrategy::Replace)) {
+                // the custom fields entirely override the back-fill ones
+                return;
+            }
             let from_ref = from.fields.as_ref().unwrap();
             let from_keys = from_ref.keys();
             for k in from_keys {
@@ -189,11 +1168,53 @@ impl BackFillable for ConfigExporter {
     }
 }
 
+/// The format of a config file; decided by the file extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    /// `.toml`; also the fallback of any other extension.
+    Toml,
+    /// `.json`; same fields (and nesting) as the TOML config, e.g. `{"number_of_entries": 1000, "exporter": [...]}`.
+    Json,
+}
+
+impl ConfigFormat {
+    /// The format of the config file by its extension (case-insensitive); TOML unless `.json`.
+    pub fn from_file(file: &str) -> Self {
+        match std::path::Path::new(file)
+            .extension()
+            .map(|extension| extension.to_ascii_lowercase())
+        {
+            Some(extension) if extension == "json" => ConfigFormat::Json,
+            _ => ConfigFormat::Toml,
+        }
+    }
+
+    /// the file extension looked up in the config folders.
+    fn extension(&self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+        }
+    }
+
+    fn parse(&self, content: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        match self {
+            ConfigFormat::Toml => Ok(toml::from_str(content)?),
+            ConfigFormat::Json => Ok(serde_json::from_str(content)?),
+        }
+    }
+}
+
+/// The custom config filename meaning "read the custom config (TOML) from stdin".
+pub const STDIN_CONFIG_FILE: &str = "-";
+
 /// Load the config files and return a Config object (back-filled).
 /// # Arguments
 /// * `backfill_config_folder` - The path to the folder containing the backfill config files.
 /// * `config_folder` - The path to the folder containing the custom config files.
-/// * `file` - The name of the config file to load.
+/// * `file` - The name of the config file to load; `-` reads the custom config from stdin instead
+///   (`config_folder` is ignored then, the back-fill config is still loaded from disk).
+///   A `.json` file is parsed as JSON, anything else as TOML (see `ConfigFormat`).
 /// # Returns
 /// A Config object (back-filled).
 ///
@@ -215,29 +1236,743 @@ pub fn load_config(
     config_folder: String,
     backfill_config_file: String,
     config_file: String,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    load_config_with_stdin(
+        backfill_config_folder,
+        config_folder,
+        backfill_config_file,
+        config_file,
+        std::io::stdin(),
+    )
+}
+
+/// Same as `load_config` with the given reader as the stdin (read only for the `-` config file).
+pub fn load_config_with_stdin<R: Read>(
+    backfill_config_folder: String,
+    config_folder: String,
+    backfill_config_file: String,
+    config_file: String,
+    stdin: R,
 ) -> Result<Config, Box<dyn std::error::Error>> {
     // load backfill config(s)
-    let backfill_result = read_config_folder(
+    let backfill = read_config_file(
+        "back-fill",
         backfill_config_folder.as_str(),
-        "toml",
         backfill_config_file.as_str(),
     )?;
     // load custom config(s)
-    let custom_result = read_config_folder(config_folder.as_str(), "toml", config_file.as_str())?;
+    let (custom, custom_format) = match config_file.as_str() {
+        STDIN_CONFIG_FILE => (read_stdin_config(stdin)?, ConfigFormat::Toml),
+        _ => (
+            read_config_file("custom", config_folder.as_str(), config_file.as_str())?,
+            ConfigFormat::from_file(config_file.as_str()),
+        ),
+    };
 
-    // created a mutable Config object
-    let mut config: Config = toml::from_str(custom_result.get(config_file.as_str()).unwrap())?;
-    let backfill_config: Config =
-        toml::from_str(backfill_result.get(backfill_config_file.as_str()).unwrap())?;
+    Config::from_str_with(
+        custom.as_str(),
+        custom_format,
+        backfill.as_str(),
+        ConfigFormat::from_file(backfill_config_file.as_str()),
+    )
+}
 
-    config.back_fill(&backfill_config);
+/// Load and layer any number of config files; a later file overrides the earlier ones, i.e. each
+/// file is back-filled by the (already layered) files before it. Generalizes `load_config`, which
+/// is the 2 files case (the back-fill config then the custom config).
+///
+/// A `.json` file is parsed as JSON, anything else as TOML (see `ConfigFormat`).
+///
+/// # Errors
+///
+/// If no file is given, a file cannot be read or parsed, the last file is ambiguous (see
+/// `Config::validate_timestamp_preference`) or the layered config is not valid (see
+/// `Config::validate`, `Config::assert_invariants` and `Config::validate_exporters`).
+///
+pub fn load_configs(paths: &[&Path]) -> Result<Config, Box<dyn std::error::Error>> {
+    let mut layers = vec![];
+    for path in paths {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read the config file [{}]: {}", path.display(), e))?;
+        let format = ConfigFormat::from_file(path.to_string_lossy().as_ref());
+        let layer = format.parse(content.as_str()).map_err(|e| {
+            format!(
+                "failed to parse the config file [{}]: {}",
+                path.display(),
+                e
+            )
+        })?;
+        layers.push(layer);
+    }
+    // the last file wins; back-filled by the earlier files, the latest first
+    let mut config = layers.pop().ok_or("no config files to load")?;
+    // what is written in the last file; the back-filled values are not ambiguous
+    config.validate_timestamp_preference()?;
+    for layer in layers.iter().rev() {
+        config.back_fill(layer);
+    }
+    config.validate()?;
+    config.assert_invariants()?;
+    config.validate_exporters()?;
     Ok(config)
 }
 
+/// read the custom toml config from the stdin; an empty stdin (e.g. nothing piped) is an error.
+fn read_stdin_config<R: Read>(mut stdin: R) -> Result<String, Box<dyn std::error::Error>> {
+    let mut custom = String::new();
+    stdin
+        .read_to_string(&mut custom)
+        .map_err(|e| format!("failed to read the custom config from stdin: {}", e))?;
+    if custom.trim().is_empty() {
+        return Err("custom config from stdin is empty; pipe a TOML config in (e.g. `otel_broccoli --config - < config.toml`)".into());
+    }
+    Ok(custom)
+}
+
+/// read the named toml config file under the folder; a missing folder / file is an error
+/// naming both (a common first-run mistake).
+fn read_config_file(
+    kind: &str,
+    folder: &str,
+    file: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let extension = ConfigFormat::from_file(file).extension();
+    let mut configs = read_config_folder(folder, extension, file).map_err(|e| {
+        format!(
+            "failed to read the {} config folder [{}] (looking for [{}]): {}",
+            kind, folder, file, e
+        )
+    })?;
+    configs.remove(file).ok_or(
+        format!(
+            "{} config file [{}] not found in folder [{}]",
+            kind, file, folder
+        )
+        .into(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_apply_overrides_precedence() {
+        let backfill = r#"
+            number_of_entries = 100
+            generation_duration = "10m"
+            distribution_by = "even"
+            seed = 1
+
+            [[exporter]]
+            name = "stdout"
+            enabled = true
+            "#;
+        let custom = r#"
+            generation_duration = "1h"
+            distribution_by = "decay"
+            seed = 2
+            "#;
+        // back-fill < custom
+        let mut config = Config::from_str(custom, backfill).unwrap();
+        assert_eq!(*config.number_of_entries(), Some(100));
+        assert_eq!(config.generation_duration().as_deref(), Some("1h"));
+
+        // custom < env
+        let env = ConfigOverrides::from_lookup(|name| match name {
+            "distribution_by" => Some(("OTEL_BROCCOLI_DISTRIBUTION_BY".into(), "bimodal".into())),
+            "seed" => Some(("OTEL_BROCCOLI_SEED".into(), "3".into())),
+            _ => None,
+        })
+        .unwrap();
+        config.apply_overrides(env);
+        assert_eq!(config.distribution_by().as_deref(), Some("bimodal"));
+
+        // env < CLI
+        let args: Vec<String> = ["otel_broccoli", "--seed", "4", "--replay", "true"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        config.apply_overrides(ConfigOverrides::from_args(&args).unwrap());
+        assert_eq!(*config.seed(), Some(4));
+        assert_eq!(*config.replay(), Some(true));
+
+        // the values not overridden by any layer stay
+        assert_eq!(*config.number_of_entries(), Some(100));
+        assert_eq!(config.generation_duration().as_deref(), Some("1h"));
+        assert_eq!(config.distribution_by().as_deref(), Some("bimodal"));
+
+        // an empty override changes nothing
+        let before = config.to_toml().unwrap();
+        config.apply_overrides(ConfigOverrides::default());
+        assert_eq!(config.to_toml().unwrap(), before);
+
+        let args: Vec<String> = vec!["--seed".to_string(), "abc".to_string()];
+        assert_eq!(
+            ConfigOverrides::from_args(&args).err().unwrap().to_string(),
+            "invalid value [abc] of [--seed]"
+        );
+        let args: Vec<String> = vec!["--number-of-entries".to_string()];
+        assert_eq!(
+            ConfigOverrides::from_args(&args).err().unwrap().to_string(),
+            "missing value of the [--number-of-entries] arg"
+        );
+    }
+
+    #[test]
+    fn test_only_exporter() {
+        let backfill = std::fs::read_to_string("config/default/config.toml").unwrap();
+        let custom = r#"
+            [[exporter]]
+            name = "file"
+            enabled = true
+        "#;
+        let enabled_exporters = |config: &Config| -> Vec<String> {
+            config
+                .exporters()
+                .as_ref()
+                .unwrap()
+                .iter()
+                .filter(|e| e.enabled().unwrap_or(false))
+                .map(|e| e.name().clone().unwrap())
+                .collect()
+        };
+        let mut config = Config::from_str(custom, backfill.as_str()).unwrap();
+        assert_eq!(enabled_exporters(&config), vec!["file", "stdout"]);
+
+        let args: Vec<String> = vec!["--only-exporter".to_string(), "stdout".to_string()];
+        config.merge_cli(&args).unwrap();
+        assert_eq!(enabled_exporters(&config), vec!["stdout"]);
+        // regardless of the config; a disabled exporter gets enabled
+        config.enable_only_exporter("clickhouse").unwrap();
+        assert_eq!(enabled_exporters(&config), vec!["clickhouse"]);
+
+        let args: Vec<String> = vec!["--only-exporter".to_string(), "kafka".to_string()];
+        assert_eq!(
+            config.merge_cli(&args).err().unwrap().to_string(),
+            "unknown exporter [kafka] of [--only-exporter]; configured exporters: file, stdout, clickhouse"
+        );
+        let args: Vec<String> = vec!["--only-exporter".to_string()];
+        assert_eq!(
+            config.merge_cli(&args).err().unwrap().to_string(),
+            "missing value of the [--only-exporter] arg"
+        );
+    }
+
+    #[test]
+    fn test_verbosity_level() {
+        let mut config = Config::new();
+        assert_eq!(config.verbosity_level().unwrap(), None);
+        config.set_verbosity(Some("WARN".to_string()));
+        assert_eq!(config.verbosity_level().unwrap(), Some(LevelFilter::WARN));
+        let args: Vec<String> = vec!["--verbosity".to_string(), "debug".to_string()];
+        config.merge_cli(&args).unwrap();
+        assert_eq!(config.verbosity_level().unwrap(), Some(LevelFilter::DEBUG));
+        // --quiet wins over --verbosity
+        let args: Vec<String> = vec![
+            "--verbosity".to_string(),
+            "debug".to_string(),
+            "--quiet".to_string(),
+        ];
+        config.merge_cli(&args).unwrap();
+        assert_eq!(config.verbosity_level().unwrap(), Some(LevelFilter::ERROR));
+
+        config.set_verbosity(Some("loud".to_string()));
+        assert_eq!(
+            config.verbosity_level().err().unwrap().to_string(),
+            "unknown verbosity [loud]; expecting \"off\", \"error\", \"warn\", \"info\", \"debug\" or \"trace\""
+        );
+    }
+
+    #[test]
+    fn test_validate_conflicting_timestamps() {
+        let mut config = Config::new();
+        config.set_use_now_as_timestamp(Some(true));
+        config.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        assert_eq!(
+            config
+                .validate_timestamp_preference()
+                .err()
+                .unwrap()
+                .to_string(),
+            "both use_now_as_timestamp = true and start_timestamp are set; \
+            set prefer_start_timestamp (true = start_timestamp wins, false = now wins)"
+        );
+        config.set_prefer_start_timestamp(Some(true));
+        assert!(config.validate_timestamp_preference().is_ok());
+
+        // not conflicting
+        config.set_prefer_start_timestamp(None);
+        config.set_use_now_as_timestamp(Some(false));
+        assert!(config.validate_timestamp_preference().is_ok());
+
+        // the custom config is validated on loading
+        let backfill = std::fs::read_to_string("config/default/config.toml").unwrap();
+        let custom = r#"
+            use_now_as_timestamp = true
+            start_timestamp = "2022-01-01T00:00:00.000+00:00"
+        "#;
+        assert!(Config::from_str(custom, backfill.as_str()).is_err());
+        // the back-filled values are not ambiguous; the default start_timestamp against the
+        // use_now_as_timestamp = true (now wins) or false (the default start_timestamp wins)
+        let custom = r#"
+            use_now_as_timestamp = true
+        "#;
+        assert!(Config::from_str(custom, backfill.as_str()).is_ok());
+        let custom = r#"
+            use_now_as_timestamp = false
+        "#;
+        let config = Config::from_str(custom, backfill.as_str()).unwrap();
+        assert_eq!(
+            config.start_timestamp().as_ref().unwrap(),
+            "2022-01-01T00:00:00.000+00:00"
+        );
+        let custom = r#"
+            start_timestamp = "2022-01-01T00:00:00.000+00:00"
+            prefer_start_timestamp = true
+        "#;
+        assert!(Config::from_str(custom, backfill.as_str()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_start_timestamp() {
+        let mut config = Config::new();
+        config.set_use_now_as_timestamp(Some(false));
+        config.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+        config.set_start_timestamp(Some("01/01/2022 00:00:00".to_string()));
+        assert_eq!(
+            config.validate().err().unwrap().to_string(),
+            "failed to parse start_timestamp [01/01/2022 00:00:00] with format \
+            [%Y-%m-%dT%H:%M:%S%.f%:z]: input contains invalid characters"
+        );
+
+        // now wins; the start_timestamp is never parsed
+        config.set_use_now_as_timestamp(Some(true));
+        config.set_prefer_start_timestamp(Some(false));
+        assert!(config.validate().is_ok());
+
+        config.set_use_now_as_timestamp(Some(false));
+        config.set_prefer_start_timestamp(None);
+        config.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        assert!(config.validate().is_ok());
+
+        // in use but not set
+        config.set_start_timestamp(None);
+        assert_eq!(
+            config.validate().err().unwrap().to_string(),
+            "start_timestamp is not set; required unless use_now_as_timestamp = true"
+        );
+
+        // fails on loading (before any generation); the format comes from the back-fill
+        let backfill = std::fs::read_to_string("config/default/config.toml").unwrap();
+        let custom = r#"
+            use_now_as_timestamp = false
+            start_timestamp = "01/01/2022 00:00:00"
+        "#;
+        assert!(Config::from_str(custom, backfill.as_str())
+            .err()
+            .unwrap()
+            .to_string()
+            .starts_with("failed to parse start_timestamp [01/01/2022 00:00:00] with format"));
+    }
+
+    #[test]
+    fn test_load_configs() {
+        let folder = tempfile::tempdir().unwrap();
+        let middle = folder.path().join("middle.json");
+        std::fs::write(
+            &middle,
+            r#"{
+                "number_of_entries": 500,
+                "generation_duration": "5m",
+                "seed": 1,
+                "exporter": [{"name": "stdout", "verbose": true}]
+            }"#,
+        )
+        .unwrap();
+        let top = folder.path().join("top.toml");
+        std::fs::write(
+            &top,
+            r#"
+            number_of_entries = 100
+            [[exporter]]
+            name = "stdout"
+            enabled = false
+            "#,
+        )
+        .unwrap();
+        let base = Path::new("config/default/config.toml");
+
+        let config = load_configs(&[base, middle.as_path(), top.as_path()]).unwrap();
+        // the top file wins over the other 2
+        assert_eq!(*config.number_of_entries(), Some(100));
+        // the middle file wins over the base file
+        assert_eq!(config.generation_duration().as_deref(), Some("5m"));
+        assert_eq!(*config.seed(), Some(1));
+        // only set in the base file
+        assert_eq!(config.distribution_by().as_deref(), Some("even"));
+        // per exporter field too
+        let stdout = config
+            .exporters()
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|e| e.name().as_deref() == Some("stdout"))
+            .unwrap();
+        assert_eq!(*stdout.enabled(), Some(false));
+        assert_eq!(*stdout.verbose(), Some(true));
+        assert!(config
+            .exporters()
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|e| e.name().as_deref() == Some("file")));
+
+        // reordered; the base file is the last, hence wins
+        let config = load_configs(&[middle.as_path(), top.as_path(), base]).unwrap();
+        assert_eq!(*config.number_of_entries(), Some(50000000));
+        assert_eq!(config.generation_duration().as_deref(), Some("10m"));
+
+        // a layer without any `[[exporter]]`; at the top, in the middle and at the bottom
+        let plain = folder.path().join("plain.toml");
+        std::fs::write(&plain, "seed = 7\n").unwrap();
+        for paths in [
+            [base, middle.as_path(), plain.as_path()],
+            [base, plain.as_path(), top.as_path()],
+            [plain.as_path(), middle.as_path(), base],
+        ] {
+            let config = load_configs(&paths).unwrap();
+            assert!(config
+                .exporters()
+                .as_ref()
+                .unwrap()
+                .iter()
+                .any(|e| e.name().as_deref() == Some("file")));
+        }
+        let config = load_configs(&[base, middle.as_path(), plain.as_path()]).unwrap();
+        assert_eq!(*config.seed(), Some(7));
+        assert_eq!(*config.number_of_entries(), Some(500));
+
+        assert_eq!(
+            load_configs(&[]).err().unwrap().to_string(),
+            "no config files to load"
+        );
+        assert!(load_configs(&[Path::new("config/default/missing.toml")])
+            .err()
+            .unwrap()
+            .to_string()
+            .starts_with("failed to read the config file [config/default/missing.toml]"));
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let mut stdout = ConfigExporter::default();
+        stdout.set_name(Some("stdout".to_string()));
+        let cfg = ConfigBuilder::new()
+            .number_of_entries(1000)
+            .distribution("even")
+            .duration("10m")
+            .start_timestamp("2022-01-01", "%Y-%m-%d")
+            .seed(42)
+            .add_exporter(stdout.clone())
+            .build()
+            .unwrap();
+        assert_eq!(*cfg.number_of_entries(), Some(1000));
+        assert_eq!(cfg.distribution_by().as_deref(), Some("even"));
+        assert_eq!(cfg.generation_duration().as_deref(), Some("10m"));
+        assert_eq!(*cfg.use_now_as_timestamp(), Some(false));
+        assert_eq!(*cfg.seed(), Some(42));
+        assert_eq!(cfg.exporters().as_deref(), Some(&[stdout.clone()][..]));
+        // the fields not set stay unset
+        assert!(cfg.max_entries().is_none());
+
+        // validated
+        assert_eq!(
+            ConfigBuilder::new()
+                .add_exporter(stdout.clone())
+                .add_exporter(stdout)
+                .build()
+                .err()
+                .unwrap()
+                .to_string(),
+            "duplicate exporter [stdout]; exporter names must be unique (except weighted shards)"
+        );
+        assert!(ConfigBuilder::new()
+            .start_timestamp("2022-13-01", "%Y-%m-%d")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_config_default() {
+        assert_eq!(Config::default(), Config::new());
+        // field by field; every field is unset
+        let defaults = serde_json::to_value(Config::default()).unwrap();
+        let news = serde_json::to_value(Config::new()).unwrap();
+        let fields = defaults.as_object().unwrap();
+        assert_eq!(fields.len(), news.as_object().unwrap().len());
+        for (field, value) in fields {
+            assert_eq!(Some(value), news.get(field), "{}", field);
+            assert!(value.is_null(), "{} = {}", field, value);
+        }
+
+        let exporter = ConfigExporter::default();
+        assert!(exporter.name().is_none() && exporter.fields().is_none());
+    }
+
+    #[test]
+    fn test_validate_slot_count() {
+        let mut config = Config::new();
+        config.set_slot_count(Some(50));
+        assert_eq!(
+            config.validate().err().unwrap().to_string(),
+            "slot_count and slot_width must be set together"
+        );
+        config.set_slot_width(Some("1m".to_string()));
+        assert!(config.validate().is_ok());
+        config.set_warmup_rate(Some(5));
+        assert_eq!(
+            config.validate().err().unwrap().to_string(),
+            "warmup_duration and warmup_rate must be set together"
+        );
+        config.set_warmup_duration(Some("1m".to_string()));
+        assert!(config.validate().is_ok());
+        config.set_generation_duration(Some("10m".to_string()));
+        assert_eq!(
+            config.validate().err().unwrap().to_string(),
+            "set either generation_duration or slot_count + slot_width, not both"
+        );
+
+        // the back-filled generation_duration does not conflict with the custom slot_count
+        let backfill = std::fs::read_to_string("config/default/config.toml").unwrap();
+        let custom = r#"
+            slot_count = 50
+            slot_width = "1m"
+        "#;
+        let config = Config::from_str(custom, backfill.as_str()).unwrap();
+        assert_eq!(config.generation_duration().as_deref(), None);
+        assert_eq!(*config.slot_count(), Some(50));
+
+        // the generation_duration override replaces the slot_count window
+        let mut config = config;
+        config.apply_overrides(ConfigOverrides {
+            generation_duration: Some("1h".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(*config.slot_count(), None);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_assert_invariants() {
+        let backfill = std::fs::read_to_string("config/default/config.toml").unwrap();
+        // partial custom exporters; no verbose / enabled / fields of their own
+        let custom = r#"
+            [[exporter]]
+            name = "file"
+
+            [[exporter]]
+            name = "memory"
+        "#;
+        let config = Config::from_str(custom, backfill.as_str()).unwrap();
+        assert!(config.assert_invariants().is_ok());
+        let memory = config
+            .exporters()
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|e| e.name().as_deref() == Some("memory"))
+            .unwrap();
+        assert_eq!(*memory.verbose(), Some(false));
+        assert_eq!(*memory.enabled(), Some(false));
+        assert_eq!(*memory.fields(), Some(HashMap::new()));
+
+        let mut config = Config::new();
+        let mut unnamed: ConfigExporter = toml::from_str("verbose = true").unwrap();
+        unnamed.set_enabled(Some(true));
+        config.set_exporters(Some(vec![
+            toml::from_str("name = \"file\"").unwrap(),
+            unnamed,
+        ]));
+        assert_eq!(
+            config.assert_invariants().err().unwrap().to_string(),
+            "exporter(s) with unset values after the back-fill merge: \
+            [file] verbose, enabled, fields; #2 name, fields"
+        );
+    }
+
+    #[test]
+    fn test_validate_exporters_unique() {
+        let backfill = std::fs::read_to_string("config/default/config.toml").unwrap();
+        let custom = r#"
+            [[exporter]]
+            name = "file"
+            enabled = true
+            [exporter.fields]
+            filename = "a.log"
+
+            [[exporter]]
+            name = "file"
+            enabled = true
+            [exporter.fields]
+            filename = "b.log"
+        "#;
+        assert_eq!(
+            Config::from_str(custom, backfill.as_str())
+                .err()
+                .unwrap()
+                .to_string(),
+            "duplicate exporter [file]; exporter names must be unique (except weighted shards)"
+        );
+
+        // weighted shards share the name on purpose
+        let custom = r#"
+            [[exporter]]
+            name = "clickhouse"
+            weight = 1
+
+            [[exporter]]
+            name = "clickhouse"
+            weight = 3
+        "#;
+        let config = Config::from_str(custom, backfill.as_str()).unwrap();
+        assert_eq!(config.exporters().as_ref().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_timezone_or_utc() {
+        let mut config = Config::new();
+        assert_eq!(config.timezone_or_utc().unwrap(), Tz::UTC);
+        config.set_timezone(Some("Asia/Hong_Kong".to_string()));
+        assert_eq!(config.timezone_or_utc().unwrap(), Tz::Asia__Hong_Kong);
+        config.set_timezone(Some("Mars/Olympus_Mons".to_string()));
+        assert_eq!(
+            config.timezone_or_utc().err().unwrap().to_string(),
+            "unknown timezone [Mars/Olympus_Mons]; expecting an IANA name like \"Asia/Hong_Kong\""
+        );
+    }
+
+    #[test]
+    fn test_load_config_missing_files() {
+        // nonexistent folder
+        let e = load_config(
+            "config/default".to_string(),
+            "nonexistent_folder".to_string(),
+            "config.toml".to_string(),
+            "stdout_test.toml".to_string(),
+        )
+        .err()
+        .unwrap()
+        .to_string();
+        assert!(
+            e.starts_with(
+                "failed to read the custom config folder [nonexistent_folder] (looking for [stdout_test.toml])"
+            ),
+            "{}",
+            e
+        );
+
+        // folder missing the named file
+        let e = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "missing.toml".to_string(),
+            "stdout_test.toml".to_string(),
+        )
+        .err()
+        .unwrap()
+        .to_string();
+        assert_eq!(
+            e,
+            "back-fill config file [missing.toml] not found in folder [config/default]"
+        );
+        let e = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "missing.toml".to_string(),
+        )
+        .err()
+        .unwrap()
+        .to_string();
+        assert_eq!(
+            e,
+            "custom config file [missing.toml] not found in folder [tests]"
+        );
+    }
+
+    #[test]
+    fn test_load_config_json() {
+        assert_eq!(ConfigFormat::from_file("config.JSON"), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_file("config.toml"), ConfigFormat::Toml);
+        assert_eq!(ConfigFormat::from_file("config"), ConfigFormat::Toml);
+
+        // the json equivalent of tests/stdout_test.toml
+        let config = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "stdout_test.json".to_string(),
+        )
+        .unwrap();
+        let expected = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "stdout_test.toml".to_string(),
+        )
+        .unwrap();
+        assert_eq!(config, expected);
+
+        let e = Config::from_str_with(
+            "number_of_entries = 1000",
+            ConfigFormat::Json,
+            "",
+            ConfigFormat::Toml,
+        )
+        .err()
+        .unwrap();
+        assert!(e.to_string().contains("line 1 column"), "{}", e);
+    }
+
+    #[test]
+    fn test_load_config_from_stdin() {
+        let custom = std::fs::read("tests/stdout_test.toml").unwrap();
+        let config = load_config_with_stdin(
+            "config/default".to_string(),
+            "nonexistent_folder".to_string(),
+            "config.toml".to_string(),
+            STDIN_CONFIG_FILE.to_string(),
+            custom.as_slice(),
+        )
+        .unwrap();
+        // same as loading tests/stdout_test.toml from the folder
+        let expected = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "stdout_test.toml".to_string(),
+        )
+        .unwrap();
+        assert_eq!(config, expected);
+        assert_eq!(config.number_of_entries().unwrap(), 1000);
+
+        // empty (or blank) stdin
+        for stdin in ["", " \n"] {
+            let e = load_config_with_stdin(
+                "config/default".to_string(),
+                "tests".to_string(),
+                "config.toml".to_string(),
+                STDIN_CONFIG_FILE.to_string(),
+                stdin.as_bytes(),
+            )
+            .err()
+            .unwrap()
+            .to_string();
+            assert!(e.starts_with("custom config from stdin is empty"), "{}", e);
+        }
+    }
+
     #[test]
     fn test_load_config() {
         let result = load_config(
@@ -319,4 +2054,202 @@ mod tests {
             "password"
         );
     }
+
+    #[test]
+    fn test_to_toml() {
+        let mut config = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "stdout_test.toml".to_string(),
+        )
+        .unwrap();
+        config.set_number_of_entries(Some(2500));
+        config.set_distribution_by(Some("bimodal".to_string()));
+
+        let serialized = config.to_toml().unwrap();
+        let parsed: Config = toml::from_str(serialized.as_str()).unwrap();
+        assert_eq!(parsed, config);
+        assert_eq!(parsed.number_of_entries().unwrap(), 2500);
+        assert_eq!(parsed.exporters().as_ref().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_config_from_str() {
+        let backfill = r#"
+            number_of_entries = 50000000
+            timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+            use_now_as_timestamp = true
+            generation_duration = "10m"
+            start_timestamp = "2022-01-01T00:00:00.000+00:00"
+            distribution_by = "even"
+
+            [[exporter]]
+            name = "stdout"
+            verbose = false
+            enabled = true
+
+            [[exporter]]
+            name = "file"
+            enabled = false
+            [exporter.fields]
+            path = "./generated/"
+            filename = "log.log"
+
+            [[exporter]]
+            name = "clickhouse"
+            enabled = false
+            [exporter.fields]
+            url = "http://localhost:3125"
+            user = "root"
+            password = "password"
+        "#;
+        let custom = r#"
+            number_of_entries = 1000
+            use_now_as_timestamp = true
+            generation_duration = "10m"
+            distribution_by = "even"
+
+            [[exporter]]
+            name = "file"
+            enabled = true
+            [exporter.fields]
+            path = "./generated/"
+            filename = "stdout_test.log"
+        "#;
+        let result = Config::from_str(custom, backfill);
+        assert!(result.is_ok());
+        let config = result.unwrap();
+        assert_eq!(config.exporters().as_ref().unwrap().len(), 3);
+        assert_eq!(config.number_of_entries().unwrap(), 1000);
+        assert_eq!(
+            config.timestamp_format().as_ref().unwrap(),
+            "%Y-%m-%dT%H:%M:%S%.f%:z"
+        );
+        assert!(config.use_now_as_timestamp().unwrap());
+        assert_eq!(config.generation_duration().as_ref().unwrap(), "10m");
+        assert_eq!(
+            config.start_timestamp().as_ref().unwrap(),
+            "2022-01-01T00:00:00.000+00:00"
+        );
+        assert_eq!(config.distribution_by().as_ref().unwrap(), "even");
+
+        let exporters = config.exporters().as_ref().unwrap();
+        let e_1_file = &exporters[0];
+        assert_eq!(e_1_file.name().as_ref().unwrap(), "file");
+        assert!(!e_1_file.verbose().unwrap());
+        assert!(e_1_file.enabled().unwrap());
+        assert_eq!(e_1_file.fields().as_ref().unwrap().len(), 2);
+        assert_eq!(
+            e_1_file.fields().as_ref().unwrap().get("filename").unwrap(),
+            "stdout_test.log"
+        );
+
+        let e_2_stdout = &exporters[1];
+        assert_eq!(e_2_stdout.name().as_ref().unwrap(), "stdout");
+        assert!(e_2_stdout.enabled().unwrap());
+        assert_eq!(e_2_stdout.fields().as_ref().unwrap().len(), 0);
+
+        let e_3_clickhouse = &exporters[2];
+        assert_eq!(e_3_clickhouse.name().as_ref().unwrap(), "clickhouse");
+        assert!(!e_3_clickhouse.enabled().unwrap());
+        assert_eq!(e_3_clickhouse.fields().as_ref().unwrap().len(), 3);
+        assert_eq!(
+            e_3_clickhouse.fields().as_ref().unwrap().get("url").unwrap(),
+            "http://localhost:3125"
+        );
+
+        // the steps derive the total; not back-filled with the default number_of_entries
+        let steps = r#"
+            distribution_by = "step"
+            steps = [{ at = "0s", rate = 5 }]
+        "#;
+        let config = Config::from_str(steps, backfill).unwrap();
+        assert_eq!(*config.number_of_entries(), None);
+        assert_eq!(config.steps().as_ref().unwrap().len(), 1);
+
+        // invalid toml is an error, not a panic
+        assert!(Config::from_str("number_of_entries = ", backfill).is_err());
+    }
+
+    #[test]
+    fn test_exporter_field_merge() {
+        let backfill = r#"
+            number_of_entries = 1000
+
+            [[exporter]]
+            name = "clickhouse"
+            enabled = false
+            [exporter.fields]
+            url = "http://localhost:3125"
+            user = "root"
+            password = "password"
+        "#;
+        // [case][01] merge (default); custom wins on overlapping keys, the rest is back-filled
+        let custom = r#"
+            [[exporter]]
+            name = "clickhouse"
+            enabled = true
+            [exporter.fields]
+            url = "http://clickhouse:8123"
+            database = "otel"
+        "#;
+        let config = Config::from_str(custom, backfill).unwrap();
+        let clickhouse = &config.exporters().as_ref().unwrap()[0];
+        assert_eq!(
+            clickhouse.field_merge_strategy().unwrap(),
+            FieldMergeStrategy::Merge
+        );
+        assert!(clickhouse.enabled().unwrap());
+        let fields = clickhouse.fields().as_ref().unwrap();
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields.get("url").unwrap(), "http://clickhouse:8123");
+        assert_eq!(fields.get("database").unwrap(), "otel");
+        assert_eq!(fields.get("user").unwrap(), "root");
+        assert_eq!(fields.get("password").unwrap(), "password");
+
+        // [case][02] replace; only the custom fields remain
+        let custom = r#"
+            [[exporter]]
+            name = "clickhouse"
+            enabled = true
+            field_merge = "replace"
+            [exporter.fields]
+            url = "http://clickhouse:8123"
+            database = "otel"
+        "#;
+        let config = Config::from_str(custom, backfill).unwrap();
+        let clickhouse = &config.exporters().as_ref().unwrap()[0];
+        assert_eq!(
+            clickhouse.field_merge_strategy().unwrap(),
+            FieldMergeStrategy::Replace
+        );
+        let fields = clickhouse.fields().as_ref().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields.get("url").unwrap(), "http://clickhouse:8123");
+        assert_eq!(fields.get("database").unwrap(), "otel");
+        assert!(fields.get("user").is_none());
+
+        // [case][03] replace without custom fields; nothing to replace with, hence back-filled
+        let custom = r#"
+            [[exporter]]
+            name = "clickhouse"
+            field_merge = "replace"
+        "#;
+        let config = Config::from_str(custom, backfill).unwrap();
+        let clickhouse = &config.exporters().as_ref().unwrap()[0];
+        assert_eq!(clickhouse.fields().as_ref().unwrap().len(), 3);
+        assert!(!clickhouse.enabled().unwrap());
+
+        // [case][04] unknown strategy; rejected on loading instead of falling back to merge
+        let custom = r#"
+            [[exporter]]
+            name = "clickhouse"
+            field_merge = "overwrite"
+        "#;
+        assert_eq!(
+            Config::from_str(custom, backfill).err().unwrap().to_string(),
+            "unknown field_merge [overwrite] for exporter [clickhouse]; expecting \"merge\" or \"replace\""
+        );
+    }
 }
@@ -0,0 +1,2564 @@
+pub mod aggregate;
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod batching;
+pub mod clickhouse;
+pub mod file;
+mod http;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod memory;
+pub mod otlp_http_json;
+pub mod progress;
+#[cfg(feature = "prom_remote_write")]
+pub mod prom_remote_write;
+pub mod stdout;
+pub mod timestamps;
+pub mod trace_ids;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, DurationRound, SecondsFormat, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::augmentation::{
+    cumulative_rows, expand_rows, generated_time_range, generation_window_in_seconds,
+    parse_start_timestamp, repeat_windows, seed_generation_rng_with, total_rows, AnomalyKind,
+    DataPoint, RngAlgorithm, Unit,
+};
+use crate::config::{Config, ConfigExporter};
+use crate::templates::RowTemplates;
+use progress::ProgressReporter;
+
+const DEFAULT_REPLAY_SPEED: f64 = 1.0;
+
+/// The exporter field overriding how the row timestamps are rendered (a chrono format string).
+pub const TIMESTAMP_FORMAT_FIELD: &str = "timestamp_format";
+
+/// The exporter field tagging the signal (`logs`, `traces` or `metrics`) the exporter emits.
+pub const SIGNAL_FIELD: &str = "signal";
+
+/// What an exporter has exported.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportSummary {
+    /// number of rows (individual events) exported.
+    pub rows_exported: u64,
+
+    /// number of batches flushed; 0 if the exporter does not batch.
+    pub batches: usize,
+
+    /// the export stopped early on cancellation (e.g. SIGINT); the rows consumed so far were flushed.
+    pub cancelled: bool,
+}
+
+/// A cancellation flag shared by the signal handler and the generation / export loops; the loops
+/// check it between datapoints and stop cleanly (flushing what they hold) once it trips.
+///
+/// Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Trip the token on SIGINT (Ctrl-C) instead of terminating the process right away.
+    ///
+    /// # Errors
+    ///
+    /// If a signal handler was already installed for the process.
+    ///
+    pub fn install_sigint_handler(&self) -> Result<(), String> {
+        let token = self.clone();
+        ctrlc::set_handler(move || token.cancel())
+            .map_err(|e| format!("failed to install the SIGINT handler: {}", e))
+    }
+}
+
+/// The runtime state of a run shared by every exporter; derived from the top level config (and
+/// the seed actually used), hence never configured per `[[exporter]]` entry.
+#[derive(Debug, Clone, Default)]
+pub struct ExportContext {
+    /// the top level `static_attributes` (sorted by key); see `static_attributes_of`.
+    pub static_attributes: Vec<(String, String)>,
+
+    /// the top level `cumulative`; the row based exporters emit 1 running total per slot.
+    pub cumulative: bool,
+
+    /// the top level `timezone` the exported timestamps are rendered in; UTC by default.
+    pub timezone: Tz,
+
+    /// the top level `unit`; what 1 row stands for (e.g. the prom_remote_write metric).
+    pub unit: Unit,
+
+    /// the hash of the seed, the resolved config and the time range of the run; see `run_key`.
+    pub run_key: u64,
+
+    /// the top level `attributes`; every row picks 1 value per key (see `expand_rows`).
+    pub attributes: HashMap<String, Vec<String>>,
+
+    /// the top level `templates`; every row renders its own field values (see `expand_rows`).
+    pub templates: RowTemplates,
+
+    /// the seed actually used and the configured `rng`; every exporter re-seeds the generation
+    /// rng of its thread with them, so the picked `attributes` (and rendered `templates`) are
+    /// reproducible too.
+    pub seed: u64,
+    pub rng: RngAlgorithm,
+
+    /// the cancellation of the run (see `run_exporters`); the exporters stop consuming the
+    /// datapoints once it trips (see `export_stream`).
+    pub cancellation: CancellationToken,
+
+    /// the `replay_speed`; the exporters consume the datapoints in (scaled) real time.
+    pub replay_speed: Option<f64>,
+
+    /// the progress of the exporter consuming the datapoints (see `export_stream`); set per
+    /// exporter by `run_exporters`.
+    pub progress: Option<Arc<ProgressReporter>>,
+
+    /// the `max_export_rate` of the exporter consuming the datapoints (see `export_stream`); set
+    /// per exporter by `run_exporters`.
+    pub max_export_rate: Option<f64>,
+
+    /// the index of the batch and the rows of the earlier batches, when the exporter is fed by a
+    /// `BatchingSink`; the batch's idempotency key and the base of its `cumulative` running totals.
+    pub batch_index: usize,
+    pub rows_before: u64,
+
+    /// the slots of the generation; the aggregate exporter writes the gap slots off them.
+    pub slot_grid: SlotGrid,
+}
+
+impl ExportContext {
+    /// The context of a run of the `cfg` generated with the `seed` into the `datapoints`.
+    ///
+    /// # Errors
+    ///
+    /// See `static_attributes_of`, `attributes_of`, `templates_of`, `run_key`,
+    /// `Config::timezone_or_utc`, `Unit::from_config`, `RngAlgorithm::from_config`,
+    /// `replay_speed` and `SlotGrid::from_config`.
+    ///
+    pub fn from_config(
+        cfg: &Config,
+        seed: u64,
+        datapoints: &[DataPoint],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(ExportContext {
+            static_attributes: static_attributes_of(cfg)?,
+            cumulative: cfg.cumulative().unwrap_or(false),
+            timezone: cfg.timezone_or_utc()?,
+            unit: Unit::from_config(cfg)?,
+            run_key: run_key(cfg, seed, datapoints)?,
+            attributes: attributes_of(cfg)?,
+            templates: templates_of(cfg)?,
+            seed,
+            rng: RngAlgorithm::from_config(cfg)?,
+            cancellation: CancellationToken::new(),
+            replay_speed: replay_speed(cfg)?,
+            progress: None,
+            max_export_rate: None,
+            batch_index: 0,
+            rows_before: 0,
+            slot_grid: SlotGrid::from_config(cfg)?,
+        })
+    }
+
+    /// The fields the rows carry on top of the timestamp (see `row_records`); the `attributes`
+    /// keys in key order (without the empty pools), then the `templates` fields in field order.
+    /// None in the `cumulative` mode.
+    pub fn row_fields(&self) -> Vec<&str> {
+        if self.cumulative {
+            return vec![];
+        }
+        let mut keys: Vec<&str> = self
+            .attributes
+            .iter()
+            .filter(|(_, pool)| !pool.is_empty())
+            .map(|(key, _)| key.as_str())
+            .collect();
+        keys.sort();
+        keys.extend(self.templates.fields());
+        keys
+    }
+}
+
+/// A sink of the generated datapoints; configured by its `[[exporter]]` entry.
+pub trait Exporter: Send + Sync {
+    /// the `name` of the `[[exporter]]` entries handled.
+    fn name(&self) -> &str;
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String>;
+}
+
+/// The exporter for the `[[exporter]]` name; None if unknown (or its feature is not enabled).
+pub fn exporter_for(name: &str) -> Option<Box<dyn Exporter>> {
+    match name {
+        stdout::STDOUT_EXPORTER => Some(Box::new(stdout::StdoutExporter)),
+        file::FILE_EXPORTER => Some(Box::new(file::FileExporter)),
+        clickhouse::CLICKHOUSE_EXPORTER => Some(Box::new(clickhouse::ClickhouseExporter)),
+        memory::MEMORY_EXPORTER => Some(Box::new(memory::MemoryExporter::new())),
+        aggregate::AGGREGATE_EXPORTER => Some(Box::new(aggregate::AggregateExporter)),
+        otlp_http_json::OTLP_HTTP_JSON_EXPORTER => {
+            Some(Box::new(otlp_http_json::OtlpHttpJsonExporter))
+        }
+        #[cfg(feature = "prom_remote_write")]
+        prom_remote_write::PROM_REMOTE_WRITE_EXPORTER => {
+            Some(Box::new(prom_remote_write::PromRemoteWriteExporter))
+        }
+        #[cfg(feature = "kafka")]
+        kafka::KAFKA_EXPORTER => Some(Box::new(kafka::KafkaExporter)),
+        #[cfg(feature = "avro")]
+        avro::AVRO_EXPORTER => Some(Box::new(avro::AvroExporter)),
+        _ => None,
+    }
+}
+
+/// What an exporter run did; for the run report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExporterRun {
+    pub name: String,
+    pub rows_exported: u64,
+    pub elapsed: Duration,
+}
+
+/// Run every enabled exporter (built by `exporter_for`) over the datapoints.
+///
+/// # Returns
+/// The runs of the exporters, in the order of the exporter configs.
+///
+/// An exporter not started yet once `cancellation` trips is skipped (and not in the runs); the
+/// running ones stop before their next datapoint (see `export_stream`), flushing what they hold.
+///
+/// Each exporter logs its progress (see `ProgressReporter::logging`).
+///
+/// # Errors
+///
+/// See `dispatch_exporters`; an unknown exporter name (or an invalid `max_export_rate`) fails that
+/// exporter.
+///
+pub fn run_exporters(
+    exporters: &[ConfigExporter],
+    datapoints: Arc<Vec<DataPoint>>,
+    ctx: &ExportContext,
+    cancellation: &CancellationToken,
+) -> Result<Vec<ExporterRun>, Box<dyn std::error::Error>> {
+    run_exporters_with(exporters, datapoints, ctx, cancellation, exporter_for)
+}
+
+fn run_exporters_with<B>(
+    exporters: &[ConfigExporter],
+    datapoints: Arc<Vec<DataPoint>>,
+    ctx: &ExportContext,
+    cancellation: &CancellationToken,
+    build_exporter: B,
+) -> Result<Vec<ExporterRun>, Box<dyn std::error::Error>>
+where
+    B: Fn(&str) -> Option<Box<dyn Exporter>> + Sync,
+{
+    let ctx = &ExportContext {
+        cancellation: cancellation.clone(),
+        ..ctx.clone()
+    };
+    let runs: Mutex<Vec<ExporterRun>> = Mutex::new(vec![]);
+    dispatch_exporters(exporters, datapoints, |cfg, datapoints| {
+        let name = cfg.name().clone().unwrap_or_default();
+        if cancellation.is_cancelled() {
+            tracing::warn!("exporter [{}] skipped; interrupted", name);
+            return Ok(());
+        }
+        let exporter =
+            build_exporter(name.as_str()).ok_or(format!("unknown exporter [{}]", name))?;
+        let max_export_rate = max_export_rate_of(cfg)?;
+        // each exporter runs on its own thread; the same picks whatever the exporter
+        seed_generation_rng_with(Some(ctx.seed), ctx.rng);
+        let ctx = &ExportContext {
+            progress: Some(Arc::new(ProgressReporter::logging(
+                name.as_str(),
+                total_rows(datapoints)?,
+            ))),
+            max_export_rate,
+            ..ctx.clone()
+        };
+        let started = Instant::now();
+        let summary = exporter.export(datapoints, cfg, ctx)?;
+        if cancellation.is_cancelled() {
+            tracing::warn!(
+                "exporter [{}] interrupted after {} rows",
+                name,
+                summary.rows_exported
+            );
+        } else {
+            tracing::info!(
+                "exporter [{}] exported {} rows",
+                name,
+                summary.rows_exported
+            );
+        }
+        runs.lock().unwrap().push(ExporterRun {
+            name,
+            rows_exported: summary.rows_exported,
+            elapsed: started.elapsed(),
+        });
+        Ok(())
+    })?;
+    // the exporters run concurrently; back to the order of the configs
+    let mut runs = runs.into_inner().unwrap();
+    runs.sort_by_key(|run| {
+        exporters
+            .iter()
+            .position(|e| e.name().as_deref() == Some(run.name.as_str()))
+    });
+    Ok(runs)
+}
+
+/// Renders a timestamp with the given chrono format string (e.g. `%Y-%m-%dT%H:%M:%S%.9f%:z`).
+///
+/// Without a format the timestamp is rendered as RFC3339 with nanoseconds
+/// (e.g. `2022-01-01T00:00:00.123456789Z`) so the sub-second jitter is never truncated.
+pub fn format_timestamp<Tz: TimeZone>(timestamp: &DateTime<Tz>, format: Option<&str>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match format {
+        Some(format) => timestamp.format(format).to_string(),
+        None => timestamp.to_rfc3339_opts(SecondsFormat::Nanos, true),
+    }
+}
+
+/// The `timestamp_format` field of the exporter, if set.
+pub fn timestamp_format_of(cfg: &ConfigExporter) -> Option<&str> {
+    cfg.fields()
+        .as_ref()
+        .and_then(|fields| fields.get(TIMESTAMP_FORMAT_FIELD))
+        .map(String::as_str)
+}
+
+/// The line / message of a generated row shared by the row based exporters; e.g.
+/// `{"timestamp":"2022-01-01T00:00:00.000000000Z","dataset":"loadtest"}`. See `format_timestamp`
+/// for the `format` (rendered in the `timezone`); the `static_attributes` (see `ExportContext`)
+/// follow the timestamp in order.
+pub fn row_payload(
+    timestamp: &DateTime<Utc>,
+    format: Option<&str>,
+    timezone: &Tz,
+    static_attributes: &[(String, String)],
+) -> String {
+    json_payload(
+        timestamp,
+        format,
+        timezone,
+        None,
+        None,
+        &[],
+        static_attributes,
+    )
+}
+
+/// The `row_payload` of a slot in the `cumulative` mode; the running total follows the timestamp
+/// as its `value`, e.g. `{"timestamp":"2022-01-01T00:00:00.000000000Z","value":1500}`.
+pub fn slot_payload(
+    timestamp: &DateTime<Utc>,
+    format: Option<&str>,
+    timezone: &Tz,
+    value: u64,
+    static_attributes: &[(String, String)],
+) -> String {
+    json_payload(
+        timestamp,
+        format,
+        timezone,
+        Some(value),
+        None,
+        &[],
+        static_attributes,
+    )
+}
+
+fn json_payload(
+    timestamp: &DateTime<Utc>,
+    format: Option<&str>,
+    timezone: &Tz,
+    value: Option<u64>,
+    anomaly: Option<AnomalyKind>,
+    fields: &[(String, String)],
+    static_attributes: &[(String, String)],
+) -> String {
+    let mut payload = format!(
+        "{{\"timestamp\":\"{}\"",
+        format_timestamp(&timestamp.with_timezone(timezone), format)
+    );
+    if let Some(value) = value {
+        payload.push_str(format!(",\"value\":{}", value).as_str());
+    }
+    if let Some(anomaly) = anomaly {
+        payload.push_str(format!(",\"{}\":\"{}\"", ANOMALY_FIELD, anomaly.name()).as_str());
+    }
+    for (key, value) in fields.iter().chain(static_attributes) {
+        payload.push_str(
+            format!(
+                ",{}:{}",
+                serde_json::Value::from(key.as_str()),
+                serde_json::Value::from(value.as_str())
+            )
+            .as_str(),
+        );
+    }
+    payload.push('}');
+    payload
+}
+
+/// The records of the row based exporters; (timestamp, payload) pairs.
+///
+/// 1 `row_payload` per row, carrying its own pick of the `attributes` and rendering of the
+/// `templates` (see `expand_rows`) between the timestamp and the `static_attributes`; in the
+/// `cumulative` mode, 1 `slot_payload` per datapoint (slot) instead, carrying the running total of
+/// rows up to and including the slot (and none of the `row_fields`; a slot sums up rows of
+/// different picks).
+///
+/// With the exporter's `flag_anomalies`, the rows (slots) of an injected anomaly also carry its
+/// kind as the `anomaly` field, e.g. `{"timestamp":"...","anomaly":"spike"}`.
+///
+/// The datapoints are consumed through the `export_stream`.
+pub fn row_records<'a>(
+    datapoints: &'a [DataPoint],
+    cfg: &'a ConfigExporter,
+    ctx: &'a ExportContext,
+    format: Option<&'a str>,
+) -> Box<dyn Iterator<Item = (DateTime<Utc>, String)> + 'a> {
+    let (static_attributes, timezone) = (&ctx.static_attributes, &ctx.timezone);
+    let flag_anomalies = cfg.flag_anomalies().unwrap_or(false);
+    let anomaly_of = move |datapoint: &DataPoint| match flag_anomalies {
+        true => *datapoint.anomaly(),
+        false => None,
+    };
+    let datapoints = export_stream(datapoints, ctx);
+    if ctx.cumulative {
+        let rows_before = ctx.rows_before;
+        Box::new(cumulative_rows(datapoints).map(move |(datapoint, total)| {
+            let timestamp = *datapoint.timestamp();
+            let payload = json_payload(
+                &timestamp,
+                format,
+                timezone,
+                Some(rows_before + total),
+                anomaly_of(datapoint),
+                &[],
+                static_attributes,
+            );
+            (timestamp, payload)
+        }))
+    } else if !ctx.row_fields().is_empty() {
+        let (attributes, templates) = (&ctx.attributes, &ctx.templates);
+        Box::new(datapoints.flat_map(move |datapoint| {
+            let anomaly = anomaly_of(datapoint);
+            expand_rows(std::slice::from_ref(datapoint), attributes, templates).map(move |row| {
+                let mut fields = row.attributes().clone();
+                fields.extend(row.fields().iter().cloned());
+                let payload = json_payload(
+                    row.timestamp(),
+                    format,
+                    timezone,
+                    None,
+                    anomaly,
+                    &fields,
+                    static_attributes,
+                );
+                (*row.timestamp(), payload)
+            })
+        }))
+    } else {
+        // no picks; the rows of a datapoint share 1 payload
+        Box::new(datapoints.flat_map(move |datapoint| {
+            let payload = json_payload(
+                datapoint.timestamp(),
+                format,
+                timezone,
+                None,
+                anomaly_of(datapoint),
+                &[],
+                static_attributes,
+            );
+            std::iter::repeat_n(
+                (*datapoint.timestamp(), payload),
+                (*datapoint.rows_to_add()).max(0) as usize,
+            )
+        }))
+    }
+}
+
+/// The datapoints an exporter consumes (in order); paced by the `replay_speed` and the
+/// `max_export_rate` of the `ctx` from the call on, and stops before the next datapoint once the
+/// cancellation of the `ctx` trips (see `paced_datapoints`). The rows consumed advance the
+/// `progress` of the `ctx`.
+pub fn export_stream<'a>(
+    datapoints: &'a [DataPoint],
+    ctx: &'a ExportContext,
+) -> impl Iterator<Item = &'a DataPoint> + 'a {
+    paced_datapoints(
+        datapoints,
+        ctx.replay_speed,
+        ctx.max_export_rate,
+        SystemClock::new(),
+        &ctx.cancellation,
+    )
+    .inspect(|datapoint| {
+        if let Some(progress) = ctx.progress.as_ref() {
+            progress.advance((*datapoint.rows_to_add()).max(0) as u64);
+        }
+    })
+}
+
+/// The `max_export_rate` (rows/sec) of the exporter, if set.
+///
+/// # Errors
+///
+/// If the rate is not a positive (finite) number.
+///
+pub fn max_export_rate_of(cfg: &ConfigExporter) -> Result<Option<f64>, String> {
+    match *cfg.max_export_rate() {
+        Some(rate) if !rate.is_finite() || rate <= 0.0 => Err(format!(
+            "max_export_rate of exporter [{}] must be positive, got [{}]",
+            cfg.name().as_deref().unwrap_or_default(),
+            rate
+        )),
+        rate => Ok(rate),
+    }
+}
+
+/// The number of records `row_records` yields (without a cancellation).
+///
+/// # Errors
+///
+/// See `total_rows`.
+///
+pub fn row_records_count(datapoints: &[DataPoint], ctx: &ExportContext) -> Result<u64, String> {
+    if ctx.cumulative {
+        Ok(datapoints.len() as u64)
+    } else {
+        total_rows(datapoints)
+    }
+}
+
+// the 64 bit FNV-1a parameters; a hash stable across builds and platforms (unlike the std hasher).
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// The run key; the hash of the `seed` actually used, the resolved config and the time range (the
+/// 1st and the last timestamps) of the `datapoints`, the base of the `batch_idempotency_key`s.
+///
+/// The time range tells apart the runs of the same seed and config starting at different times
+/// (e.g. `use_now_as_timestamp`); their rows are not duplicates of each other.
+///
+/// # Errors
+///
+/// If the config cannot be serialized.
+///
+pub fn run_key(
+    cfg: &Config,
+    seed: u64,
+    datapoints: &[DataPoint],
+) -> Result<u64, Box<dyn std::error::Error>> {
+    // through a json Value; its keys are sorted whatever the HashMap order of the config
+    let resolved = serde_json::to_value(cfg)?.to_string();
+    let mut hash = fnv1a(
+        fnv1a(FNV_OFFSET_BASIS, &seed.to_le_bytes()),
+        resolved.as_bytes(),
+    );
+    let timestamps = datapoints.iter().map(|datapoint| *datapoint.timestamp());
+    if let (Some(first), Some(last)) = (timestamps.clone().min(), timestamps.max()) {
+        for timestamp in [first, last] {
+            hash = fnv1a(hash, &timestamp.timestamp_micros().to_le_bytes());
+        }
+    }
+    Ok(hash)
+}
+
+/// The idempotency key of the `batch_index`-th batch of the exporter (16 hex digits); the same
+/// seed, config and batch always give the same key, hence a retried (or re-run) batch can be
+/// deduplicated by the sink.
+pub fn batch_idempotency_key(
+    cfg: &ConfigExporter,
+    ctx: &ExportContext,
+    batch_index: usize,
+) -> String {
+    let hash = fnv1a(
+        fnv1a(
+            fnv1a(FNV_OFFSET_BASIS, &ctx.run_key.to_le_bytes()),
+            cfg.name().as_deref().unwrap_or_default().as_bytes(),
+        ),
+        &(batch_index as u64).to_le_bytes(),
+    );
+    format!("{:016x}", hash)
+}
+
+/// The fields every exported row carries; a `static_attributes` key must not collide with them.
+pub const GENERATED_FIELDS: [&str; 5] = ["timestamp", "index", "value", "count", ANOMALY_FIELD];
+
+/// The field flagging the rows of an injected anomaly (see `row_records`).
+pub const ANOMALY_FIELD: &str = "anomaly";
+
+/// The top level `attributes` (candidate value pools); none if unset.
+///
+/// # Errors
+///
+/// If a key collides with the `GENERATED_FIELDS`.
+///
+pub fn attributes_of(
+    cfg: &Config,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let attributes = cfg.attributes().clone().unwrap_or_default();
+    if let Some(key) = attributes
+        .keys()
+        .find(|key| GENERATED_FIELDS.contains(&key.as_str()))
+    {
+        return Err(format!("attribute [{}] collides with a generated field", key).into());
+    }
+    Ok(attributes)
+}
+
+/// The top level `templates` (with their `template_values`); none if unset.
+///
+/// # Errors
+///
+/// If a field collides with the `GENERATED_FIELDS` or an `attributes` key.
+///
+pub fn templates_of(cfg: &Config) -> Result<RowTemplates, Box<dyn std::error::Error>> {
+    let templates = RowTemplates::from_config(cfg);
+    let attributes = cfg.attributes().clone().unwrap_or_default();
+    if let Some(field) = templates
+        .fields()
+        .find(|field| GENERATED_FIELDS.contains(field) || attributes.contains_key(*field))
+    {
+        return Err(format!("template [{}] collides with a generated field", field).into());
+    }
+    Ok(templates)
+}
+
+/// The top level `static_attributes` sorted by key; carried verbatim by each exported row (or
+/// aggregate line).
+///
+/// # Errors
+///
+/// If a key collides with a generated field; the `GENERATED_FIELDS`, an `attributes` key or a
+/// `templates` field.
+///
+pub fn static_attributes_of(
+    cfg: &Config,
+) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let static_attributes = match cfg.static_attributes() {
+        Some(static_attributes) => static_attributes,
+        None => return Ok(vec![]),
+    };
+    let mut pairs: Vec<(String, String)> = static_attributes
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    pairs.sort();
+    for (key, _) in pairs.iter() {
+        let collides = GENERATED_FIELDS.contains(&key.as_str())
+            || cfg
+                .attributes()
+                .as_ref()
+                .is_some_and(|attributes| attributes.contains_key(key))
+            || cfg
+                .templates()
+                .as_ref()
+                .is_some_and(|templates| templates.contains_key(key));
+        if collides {
+            return Err(
+                format!("static attribute [{}] collides with a generated field", key).into(),
+            );
+        }
+    }
+    Ok(pairs)
+}
+
+/// A telemetry signal; the exporters tagged with the `signal` field emit it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    Logs,
+    Traces,
+    Metrics,
+}
+
+impl Signal {
+    pub fn parse(signal: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match signal.to_lowercase().as_str() {
+            "logs" => Ok(Signal::Logs),
+            "traces" => Ok(Signal::Traces),
+            "metrics" => Ok(Signal::Metrics),
+            _ => Err(format!(
+                "unknown signal [{}]; expecting \"logs\", \"traces\" or \"metrics\"",
+                signal
+            )
+            .into()),
+        }
+    }
+}
+
+/// The exporters to run for the configured `signals`; the datapoints are generated once and
+/// fanned out to every signal's exporters, hence the counts stay consistent across the signals.
+///
+/// An exporter tagged (`signal` field) with a signal not in `signals` is disabled; the untagged
+/// ones are not signal specific and run as configured. Without `signals` the exporters are
+/// returned as-is.
+///
+/// # Errors
+///
+/// If a signal (or an exporter's `signal` field) is unknown, or a configured signal has no
+/// enabled exporter tagged with it.
+///
+pub fn signal_exporters(cfg: &Config) -> Result<Vec<ConfigExporter>, Box<dyn std::error::Error>> {
+    let mut exporters = cfg.exporters().clone().unwrap_or_default();
+    let signals = match cfg.signals() {
+        Some(signals) => signals
+            .iter()
+            .map(|name| Ok((name.as_str(), Signal::parse(name)?)))
+            .collect::<Result<Vec<(&str, Signal)>, Box<dyn std::error::Error>>>()?,
+        None => return Ok(exporters),
+    };
+    let mut covered: Vec<Signal> = vec![];
+    for exporter in exporters.iter_mut() {
+        let signal = match exporter
+            .fields()
+            .as_ref()
+            .and_then(|fields| fields.get(SIGNAL_FIELD))
+        {
+            Some(signal) => Signal::parse(signal)?,
+            None => continue,
+        };
+        if !signals.iter().any(|(_, configured)| *configured == signal) {
+            exporter.set_enabled(Some(false));
+        } else if exporter.enabled().unwrap_or(false) {
+            covered.push(signal);
+        }
+    }
+    for (name, signal) in signals {
+        if !covered.contains(&signal) {
+            return Err(format!(
+                "no enabled exporter for the [{}] signal; tag one with the `{}` field",
+                name, SIGNAL_FIELD
+            )
+            .into());
+        }
+    }
+    Ok(exporters)
+}
+
+/// The boundary the datapoint timestamps are truncated to before export (see `truncate_datapoints`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimestampTruncate {
+    None,
+    Minute,
+    Hour,
+}
+
+impl TimestampTruncate {
+    /// The configured `timestamp_truncate`; `none` if not set.
+    ///
+    /// # Errors
+    ///
+    /// If `timestamp_truncate` is none of `none`, `minute` and `hour`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        match cfg.timestamp_truncate().as_deref() {
+            None => Ok(TimestampTruncate::None),
+            Some(truncate) => match truncate.to_lowercase().as_str() {
+                "none" => Ok(TimestampTruncate::None),
+                "minute" => Ok(TimestampTruncate::Minute),
+                "hour" => Ok(TimestampTruncate::Hour),
+                _ => Err(format!(
+                    "unknown timestamp_truncate [{}]; expecting \"none\", \"minute\" or \"hour\"",
+                    truncate
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+/// Truncate every datapoint's timestamp to the whole minute / hour; the datapoints collapsing
+/// into the same bucket merge their rows (time ordered).
+///
+/// PS. a merged bucket beyond the per-datapoint `i16` range is carried by several datapoints
+/// of the same timestamp, hence no rows are lost.
+pub fn truncate_datapoints(
+    datapoints: &[DataPoint],
+    truncate: TimestampTruncate,
+) -> Vec<DataPoint> {
+    let bucket = match truncate {
+        TimestampTruncate::None => return datapoints.to_vec(),
+        TimestampTruncate::Minute => TimeDelta::minutes(1),
+        TimestampTruncate::Hour => TimeDelta::hours(1),
+    };
+    let mut buckets: BTreeMap<DateTime<Utc>, u64> = BTreeMap::new();
+    for datapoint in datapoints {
+        let timestamp = datapoint
+            .timestamp()
+            .duration_trunc(bucket)
+            .unwrap_or(*datapoint.timestamp());
+        *buckets.entry(timestamp).or_default() += (*datapoint.rows_to_add()).max(0) as u64;
+    }
+    let mut truncated = vec![];
+    for (timestamp, mut rows) in buckets {
+        loop {
+            let rows_taken = rows.min(i16::MAX as u64);
+            truncated.push(DataPoint::new(timestamp, rows_taken as i16));
+            rows -= rows_taken;
+            if rows == 0 {
+                break;
+            }
+        }
+    }
+    truncated
+}
+
+/// The inclusive (`export_from`, `export_to`) bounds; `None` = open ended.
+pub type ExportRange = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// The configured `export_from` / `export_to` bounds parsed with the `timestamp_format` (the same
+/// parse as the `start_timestamp`); `None` for an unset (open ended) bound.
+///
+/// # Errors
+///
+/// If a bound does not match the `timestamp_format` or `export_from` is after `export_to`.
+///
+pub fn export_range(cfg: &Config) -> Result<ExportRange, Box<dyn std::error::Error>> {
+    let timestamp_format = cfg
+        .timestamp_format()
+        .as_deref()
+        .unwrap_or("%Y-%m-%dT%H:%M:%S%.f%:z");
+    let parse = |name: &str, value: &Option<String>| match value {
+        Some(value) => parse_start_timestamp(value, timestamp_format)
+            .map(Some)
+            .map_err(|e| {
+                format!(
+                    "failed to parse {} [{}] with format [{}]: {}",
+                    name, value, timestamp_format, e
+                )
+            }),
+        None => Ok(None),
+    };
+    let export_from = parse("export_from", cfg.export_from())?;
+    let export_to = parse("export_to", cfg.export_to())?;
+    if let (Some(from), Some(to)) = (export_from, export_to) {
+        if from > to {
+            return Err(format!(
+                "export_from [{}] must be <= export_to [{}]",
+                cfg.export_from().as_deref().unwrap_or_default(),
+                cfg.export_to().as_deref().unwrap_or_default()
+            )
+            .into());
+        }
+    }
+    Ok((export_from, export_to))
+}
+
+/// Keep only the datapoints within the inclusive [`export_from`, `export_to`] range (see
+/// `export_range`); warns if the range is entirely outside the generated datapoints.
+pub fn filter_export_range(
+    datapoints: &[DataPoint],
+    export_from: Option<DateTime<Utc>>,
+    export_to: Option<DateTime<Utc>>,
+) -> Vec<DataPoint> {
+    if export_from.is_none() && export_to.is_none() {
+        return datapoints.to_vec();
+    }
+    let within = |timestamp: &DateTime<Utc>| {
+        export_from.is_none_or(|from| *timestamp >= from)
+            && export_to.is_none_or(|to| *timestamp <= to)
+    };
+    let filtered: Vec<DataPoint> = datapoints
+        .iter()
+        .filter(|datapoint| within(datapoint.timestamp()))
+        .cloned()
+        .collect();
+    let first = datapoints
+        .iter()
+        .map(|datapoint| *datapoint.timestamp())
+        .min();
+    let last = datapoints
+        .iter()
+        .map(|datapoint| *datapoint.timestamp())
+        .max();
+    if let (Some(first), Some(last)) = (first, last) {
+        if export_to.is_some_and(|to| to < first) || export_from.is_some_and(|from| from > last) {
+            tracing::warn!(
+                "the export range [{:?}, {:?}] is entirely outside the generation window [{}, {}]; nothing is exported",
+                export_from,
+                export_to,
+                first,
+                last
+            );
+        }
+    }
+    filtered
+}
+
+/// The slots of the generation as configured; the (repeated) windows and the slot interval of
+/// the `slot_width` (see `SlotGrid::from_config`). Tells the slots left empty (the gap slots)
+/// apart from the ones outside of the generation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SlotGrid {
+    /// the [start, end) of every (repeated) window, on the slot boundaries; within the export
+    /// range.
+    pub windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+
+    /// the slot interval; the `timestamp_truncate` bucket if wider than the `slot_width`.
+    pub interval: TimeDelta,
+}
+
+impl SlotGrid {
+    /// The slots of the generation of the `cfg` (see `generated_time_range`).
+    ///
+    /// # Errors
+    ///
+    /// See `generated_time_range`, `export_range` and `TimestampTruncate::from_config`.
+    ///
+    pub fn from_config(cfg: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        let (start_time, end_time) = generated_time_range(cfg)?;
+        let (_, slot_width) = generation_window_in_seconds(cfg)?;
+        let (repeat_count, repeat_interval) = repeat_windows(cfg, end_time - start_time)?;
+        let mut interval = TimeDelta::seconds(slot_width);
+        // the last slot right at the end boundary
+        let end_time = match cfg.end_inclusive().unwrap_or(false) {
+            true => end_time + interval,
+            false => end_time,
+        };
+        let (start_time, end_time) = match TimestampTruncate::from_config(cfg)? {
+            TimestampTruncate::Minute if interval < TimeDelta::minutes(1) => {
+                interval = TimeDelta::minutes(1);
+                truncated_window(start_time, end_time, interval)
+            }
+            TimestampTruncate::Hour if interval < TimeDelta::hours(1) => {
+                interval = TimeDelta::hours(1);
+                truncated_window(start_time, end_time, interval)
+            }
+            _ => (start_time, end_time),
+        };
+        let (export_from, export_to) = export_range(cfg)?;
+        let windows = (0..repeat_count as i32)
+            .map(|window| {
+                let offset = repeat_interval * window;
+                let (mut start, mut end) = (start_time + offset, end_time + offset);
+                // the 1st slot at or after the `export_from`, the last one at or before the
+                // `export_to`
+                if let Some(from) = export_from.filter(|from| *from > start) {
+                    let slots = (from - start).num_milliseconds() as f64
+                        / interval.num_milliseconds() as f64;
+                    start += interval * slots.ceil() as i32;
+                }
+                if let Some(to) = export_to.filter(|to| *to < end) {
+                    end = to + TimeDelta::nanoseconds(1);
+                }
+                (start, end)
+            })
+            .filter(|(start, end)| start < end)
+            .collect();
+        Ok(SlotGrid { windows, interval })
+    }
+}
+
+/// the window widened to the whole `bucket`s it overlaps.
+fn truncated_window(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    bucket: TimeDelta,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = start_time.duration_trunc(bucket).unwrap_or(start_time);
+    let end = match end_time.duration_trunc(bucket).unwrap_or(end_time) {
+        end if end < end_time => end + bucket,
+        end => end,
+    };
+    (start, end)
+}
+
+/// The time source of the replay mode; abstracted so the replay timing is testable.
+pub trait ReplayClock {
+    /// time elapsed since the clock started.
+    fn elapsed(&self) -> Duration;
+
+    fn sleep(&self, duration: Duration);
+}
+
+/// The wall-clock implementation of `ReplayClock`.
+pub struct SystemClock {
+    started: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock {
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl<C: ReplayClock + ?Sized> ReplayClock for &C {
+    fn elapsed(&self) -> Duration {
+        (**self).elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+impl ReplayClock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// A token-bucket throttle; refilled at `rate` tokens (rows) per second up to 1 second's worth.
+///
+/// Starts empty so the very 1st rows are already paced; an idle period (e.g. a replay wait)
+/// refills it, hence a burst right after is let through up to the capacity.
+pub struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Duration,
+}
+
+impl TokenBucket {
+    pub fn new(rate: f64, clock: &dyn ReplayClock) -> Self {
+        TokenBucket {
+            rate,
+            capacity: rate,
+            tokens: 0.0,
+            refilled_at: clock.elapsed(),
+        }
+    }
+
+    /// Take `tokens` from the bucket; sleeps on the `clock` until the bucket has refilled the
+    /// missing tokens.
+    pub fn acquire(&mut self, tokens: u64, clock: &dyn ReplayClock) {
+        let now = clock.elapsed();
+        let refill = now.saturating_sub(self.refilled_at).as_secs_f64() * self.rate;
+        self.tokens = (self.tokens + refill).min(self.capacity) - tokens as f64;
+        self.refilled_at = now;
+        if self.tokens < 0.0 {
+            clock.sleep(Duration::from_secs_f64(-self.tokens / self.rate));
+            self.tokens = 0.0;
+            self.refilled_at = clock.elapsed();
+        }
+    }
+}
+
+/// The configured replay speed (e.g. 10 = 10x faster than real time); None without `replay`.
+///
+/// # Errors
+///
+/// If `speed` is not a positive (finite) number.
+///
+pub fn replay_speed(cfg: &Config) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+    let speed = cfg.speed().unwrap_or(DEFAULT_REPLAY_SPEED);
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(format!("replay speed must be positive, got [{}]", speed).into());
+    }
+    Ok(cfg.replay().unwrap_or(false).then_some(speed))
+}
+
+// the longest uninterrupted wait of the pacing; a cancellation is noticed within it.
+const PACING_SLEEP_STEP: Duration = Duration::from_millis(100);
+
+/// The datapoints paced over time (in order); the exporter loop.
+///
+/// With a `replay_speed`, each datapoint is yielded when its offset from the 1st datapoint has
+/// elapsed on the `clock` (divided by the speed, e.g. speed 10 = 10x faster than real time);
+/// otherwise all datapoints are yielded at once.
+///
+/// With a `max_export_rate` (an exporter's rows/sec cap), each datapoint additionally waits for
+/// its rows on a `TokenBucket`; a slot larger than the cap is spread over the following seconds.
+///
+/// Stops before the next datapoint once `cancellation` trips (a wait included).
+///
+/// PS. the speed and the rate are expected positive; see `replay_speed` and `max_export_rate_of`.
+pub fn paced_datapoints<'a, I, C>(
+    datapoints: I,
+    replay_speed: Option<f64>,
+    max_export_rate: Option<f64>,
+    clock: C,
+    cancellation: &'a CancellationToken,
+) -> impl Iterator<Item = &'a DataPoint> + 'a
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+    I::IntoIter: 'a,
+    C: ReplayClock + 'a,
+{
+    let mut bucket = max_export_rate.map(|rate| TokenBucket::new(rate, &clock));
+    let mut first_timestamp: Option<DateTime<Utc>> = None;
+    let wait = move |clock: &C, duration: Duration| {
+        let mut remaining = duration;
+        while !remaining.is_zero() && !cancellation.is_cancelled() {
+            let step = remaining.min(PACING_SLEEP_STEP);
+            clock.sleep(step);
+            remaining -= step;
+        }
+    };
+    datapoints
+        .into_iter()
+        .inspect(move |datapoint| {
+            if let Some(speed) = replay_speed {
+                // a datapoint earlier than the 1st one is yielded right away
+                let first_timestamp = *first_timestamp.get_or_insert(*datapoint.timestamp());
+                let offset = (*datapoint.timestamp() - first_timestamp)
+                    .to_std()
+                    .unwrap_or(Duration::ZERO)
+                    .div_f64(speed);
+                wait(&clock, offset.saturating_sub(clock.elapsed()));
+            }
+            if let Some(bucket) = bucket.as_mut() {
+                bucket.acquire((*datapoint.rows_to_add()).max(0) as u64, &clock);
+            }
+        })
+        .take_while(move |_| !cancellation.is_cancelled())
+}
+
+/// Run every enabled exporter concurrently (1 thread per exporter) against the same datapoints.
+///
+/// The datapoints are shared through an `Arc` so no exporter gets its own copy of the dataset.
+/// `export_fn` is called once per enabled exporter with that exporter's config.
+///
+/// # Returns
+/// The number of exporters run.
+///
+/// # Errors
+///
+/// If any exporter fails (or panics), the remaining exporters still run to completion and
+/// an aggregated error naming every failed exporter is returned.
+///
+pub fn dispatch_exporters<F>(
+    exporters: &[ConfigExporter],
+    datapoints: Arc<Vec<DataPoint>>,
+    export_fn: F,
+) -> Result<usize, Box<dyn std::error::Error>>
+where
+    F: Fn(&ConfigExporter, &[DataPoint]) -> Result<(), String> + Sync,
+{
+    let enabled_exporters: Vec<&ConfigExporter> = exporters
+        .iter()
+        .filter(|e| e.enabled().unwrap_or(false))
+        .collect();
+
+    // weighted exporters share the dataset; the unweighted ones each get the full dataset
+    let mut weighted_exporters: Vec<usize> = vec![];
+    let mut weights: Vec<u32> = vec![];
+    for (idx, exporter) in enabled_exporters.iter().enumerate() {
+        if let Some(weight) = exporter.weight() {
+            if *weight == 0 {
+                return Err(format!(
+                    "weight of exporter [{}] must be positive",
+                    exporter.name().clone().unwrap_or_default()
+                )
+                .into());
+            }
+            weighted_exporters.push(idx);
+            weights.push(*weight);
+        }
+    }
+    let mut routed_datapoints: Vec<Arc<Vec<DataPoint>>> =
+        vec![Arc::clone(&datapoints); enabled_exporters.len()];
+    for (idx, partition) in weighted_exporters
+        .into_iter()
+        .zip(partition_by_weights(&datapoints, &weights))
+    {
+        routed_datapoints[idx] = Arc::new(partition);
+    }
+
+    let export_fn = &export_fn;
+    let results: Vec<(String, Result<(), String>)> = thread::scope(|scope| {
+        let handles: Vec<_> = enabled_exporters
+            .iter()
+            .zip(routed_datapoints)
+            .map(|(exporter, datapoints)| {
+                let handle = scope.spawn(move || export_fn(exporter, &datapoints));
+                (exporter.name().clone().unwrap_or_default(), handle)
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|(name, handle)| {
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err("exporter panicked".to_string()));
+                (name, result)
+            })
+            .collect()
+    });
+
+    let mut errors: Vec<String> = vec![];
+    for (name, result) in results.iter() {
+        match result {
+            Ok(_) => tracing::debug!(
+                message = format!("exporter [{}] completed", name),
+                module = "exporters"
+            ),
+            Err(e) => errors.push(format!("[{}] {}", name, e)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(format!(
+            "{} of {} exporter(s) failed: {}",
+            errors.len(),
+            results.len(),
+            errors.join("; ")
+        )
+        .into());
+    }
+    Ok(results.len())
+}
+
+/// Partition each slot's rows across the `weights` (1 partition per weight, same slots).
+///
+/// Largest-remainder rounding; the rounded-off remainders are carried across slots (the leftover
+/// rows of a slot go to the partitions furthest behind their share so far), hence no rows are lost
+/// and the split stays within rounding of the weights over the whole dataset.
+pub fn partition_by_weights(datapoints: &[DataPoint], weights: &[u32]) -> Vec<Vec<DataPoint>> {
+    let total_weight: u64 = weights.iter().map(|weight| *weight as u64).sum();
+    let mut partitions: Vec<Vec<DataPoint>> = weights
+        .iter()
+        .map(|_| Vec::with_capacity(datapoints.len()))
+        .collect();
+    if total_weight == 0 {
+        return partitions;
+    }
+
+    let mut cumulative_rows: u64 = 0;
+    let mut assigned_rows: Vec<u64> = vec![0; weights.len()];
+    for datapoint in datapoints {
+        let rows = (*datapoint.rows_to_add()).max(0) as u64;
+        cumulative_rows += rows;
+
+        let mut shares: Vec<u64> = weights
+            .iter()
+            .map(|weight| rows * *weight as u64 / total_weight)
+            .collect();
+        let leftover = rows - shares.iter().sum::<u64>();
+        if leftover > 0 {
+            // furthest behind the ideal cumulative share first
+            let mut deficits: Vec<(usize, f64)> = weights
+                .iter()
+                .enumerate()
+                .map(|(idx, weight)| {
+                    let ideal = cumulative_rows as f64 * *weight as f64 / total_weight as f64;
+                    (idx, ideal - (assigned_rows[idx] + shares[idx]) as f64)
+                })
+                .collect();
+            deficits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            for (idx, _) in deficits.iter().take(leftover as usize) {
+                shares[*idx] += 1;
+            }
+        }
+
+        for (idx, share) in shares.into_iter().enumerate() {
+            assigned_rows[idx] += share;
+            partitions[idx].push(DataPoint::new(*datapoint.timestamp(), share as i16));
+        }
+    }
+    partitions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::app_init;
+    use crate::augmentation::generate_datapoints;
+    use crate::config::{Config, ConfigAnomaly, ConfigRepeat};
+
+    fn test_config() -> Config {
+        toml::from_str(
+            r#"
+            number_of_entries = 1000
+            timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+            use_now_as_timestamp = false
+            generation_duration = "1m"
+            start_timestamp = "2022-01-01T00:00:00.000+00:00"
+            distribution_by = "even"
+
+            [[exporter]]
+            name = "memory_a"
+            enabled = true
+
+            [[exporter]]
+            name = "memory_b"
+            enabled = true
+
+            [[exporter]]
+            name = "memory_disabled"
+            enabled = false
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dispatch_exporters() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let cfg = test_config();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        // in-memory exporters; keep (datapoints count, rows count) per exporter name
+        let received: Mutex<HashMap<String, (usize, u64)>> = Mutex::new(HashMap::new());
+        let result = dispatch_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            Arc::clone(&datapoints),
+            |exporter, datapoints| {
+                let rows = total_rows(datapoints).unwrap();
+                received.lock().unwrap().insert(
+                    exporter.name().clone().unwrap(),
+                    (datapoints.len(), rows),
+                );
+                Ok(())
+            },
+        );
+        assert_eq!(result.unwrap(), 2);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received.get("memory_disabled").is_none());
+        for name in ["memory_a", "memory_b"] {
+            let (num_datapoints, rows) = received.get(name).unwrap();
+            assert_eq!(*num_datapoints, datapoints.len());
+            assert_eq!(*rows, 1000);
+        }
+    }
+
+    #[test]
+    fn test_dispatch_exporters_aggregated_error() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let cfg = test_config();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        let result = dispatch_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            datapoints,
+            |exporter, _| {
+                if exporter.name().as_deref() == Some("memory_b") {
+                    return Err("sink unavailable".to_string());
+                }
+                Ok(())
+            },
+        );
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "1 of 2 exporter(s) failed: [memory_b] sink unavailable"
+        );
+    }
+
+    #[test]
+    fn test_dispatch_exporters_weighted() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let cfg: Config = toml::from_str(
+            r#"
+            number_of_entries = 1000
+            timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+            use_now_as_timestamp = false
+            generation_duration = "1m"
+            start_timestamp = "2022-01-01T00:00:00.000+00:00"
+            distribution_by = "even"
+
+            [[exporter]]
+            name = "shard_a"
+            enabled = true
+            weight = 1
+
+            [[exporter]]
+            name = "shard_b"
+            enabled = true
+            weight = 3
+
+            [[exporter]]
+            name = "unweighted"
+            enabled = true
+            "#,
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        // keep (datapoints count, rows count) per exporter name
+        let received: Mutex<HashMap<String, (usize, u64)>> = Mutex::new(HashMap::new());
+        let result = dispatch_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            Arc::clone(&datapoints),
+            |exporter, datapoints| {
+                received.lock().unwrap().insert(
+                    exporter.name().clone().unwrap(),
+                    (datapoints.len(), total_rows(datapoints).unwrap()),
+                );
+                Ok(())
+            },
+        );
+        assert_eq!(result.unwrap(), 3);
+
+        let received = received.lock().unwrap();
+        let (a_datapoints, a_rows) = received.get("shard_a").unwrap();
+        let (b_datapoints, b_rows) = received.get("shard_b").unwrap();
+        // same slots, rows split 1:3 with nothing lost
+        assert_eq!(*a_datapoints, datapoints.len());
+        assert_eq!(*b_datapoints, datapoints.len());
+        assert_eq!(a_rows + b_rows, 1000);
+        assert!((249..=251).contains(a_rows));
+        // unweighted; the full dataset
+        assert_eq!(
+            *received.get("unweighted").unwrap(),
+            (datapoints.len(), 1000)
+        );
+    }
+
+    #[test]
+    fn test_partition_by_weights() {
+        let start_time: chrono::DateTime<chrono::Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        // 1 row per slot; the remainders must be carried across slots
+        let datapoints: Vec<DataPoint> = (0..90)
+            .map(|i| DataPoint::new(start_time + chrono::Duration::seconds(i), 1))
+            .collect();
+        let partitions = partition_by_weights(&datapoints, &[1, 2]);
+        assert_eq!(total_rows(&partitions[0]).unwrap(), 30);
+        assert_eq!(total_rows(&partitions[1]).unwrap(), 60);
+        for (i, datapoint) in datapoints.iter().enumerate() {
+            assert_eq!(
+                partitions[0][i].rows_to_add() + partitions[1][i].rows_to_add(),
+                *datapoint.rows_to_add()
+            );
+        }
+    }
+
+    /// records the names of the exporter configs it is run for.
+    struct StubExporter {
+        name: String,
+        exported: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Exporter for StubExporter {
+        fn name(&self) -> &str {
+            self.name.as_str()
+        }
+
+        fn export(
+            &self,
+            datapoints: &[DataPoint],
+            cfg: &ConfigExporter,
+            _: &ExportContext,
+        ) -> Result<ExportSummary, String> {
+            self.exported.lock().unwrap().push(format!(
+                "{}:{}",
+                self.name,
+                cfg.name().clone().unwrap()
+            ));
+            Ok(ExportSummary {
+                rows_exported: total_rows(datapoints).unwrap(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_exporter_for() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        for name in [
+            "stdout",
+            "file",
+            "clickhouse",
+            "memory",
+            "aggregate",
+            "otlp_http_json",
+        ] {
+            assert_eq!(exporter_for(name).unwrap().name(), name);
+        }
+        assert!(exporter_for("unknown").is_none());
+
+        // the exporters are built by name for the enabled exporter configs only
+        let cfg = test_config();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+        let exported = Arc::new(Mutex::new(vec![]));
+        let result = run_exporters_with(
+            cfg.exporters().as_ref().unwrap(),
+            datapoints,
+            &ExportContext::default(),
+            &CancellationToken::new(),
+            |name| {
+                if name != "memory_a" {
+                    return None;
+                }
+                Some(Box::new(StubExporter {
+                    name: "stub".to_string(),
+                    exported: Arc::clone(&exported),
+                }))
+            },
+        );
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "1 of 2 exporter(s) failed: [memory_b] unknown exporter [memory_b]"
+        );
+        assert_eq!(*exported.lock().unwrap(), vec!["stub:memory_a".to_string()]);
+    }
+
+    #[test]
+    fn test_run_exporters_memory() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let cfg = test_config();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+        // every enabled exporter shares the same in-memory rows
+        let memory = memory::MemoryExporter::new();
+        let result = run_exporters_with(
+            cfg.exporters().as_ref().unwrap(),
+            datapoints,
+            &ExportContext::default(),
+            &CancellationToken::new(),
+            |_| Some(Box::new(memory.clone())),
+        );
+        let runs = result.unwrap();
+        assert_eq!(
+            runs.iter().map(|run| run.name.as_str()).collect::<Vec<_>>(),
+            vec!["memory_a", "memory_b"]
+        );
+        assert!(runs.iter().all(|run| run.rows_exported == 1000));
+        assert_eq!(memory.len(), 2 * 1000);
+
+        // batched; the rows arrive unchanged, batch by batch
+        let expected = memory::MemoryExporter::new();
+        let cfg_exporter: ConfigExporter = toml::from_str("name = \"memory\"").unwrap();
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let ctx = ExportContext::default();
+        expected.export(&datapoints, &cfg_exporter, &ctx).unwrap();
+
+        let memory = memory::MemoryExporter::new();
+        let summary = batching::BatchingSink::new(memory.clone(), 64)
+            .export_iter(datapoints, &cfg_exporter, &ctx)
+            .unwrap();
+        assert_eq!(summary.rows_exported, 1000);
+        assert_eq!(summary.batches, 1000_usize.div_ceil(64));
+        assert_eq!(memory.rows(), expected.rows());
+    }
+
+    #[test]
+    fn test_signal_exporters() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let mut cfg: Config = toml::from_str(
+            r#"
+            number_of_entries = 1000
+            timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+            use_now_as_timestamp = false
+            generation_duration = "1m"
+            start_timestamp = "2022-01-01T00:00:00.000+00:00"
+            distribution_by = "even"
+            signals = ["logs", "traces", "metrics"]
+
+            [[exporter]]
+            name = "memory_logs"
+            enabled = true
+            fields = { signal = "logs" }
+
+            [[exporter]]
+            name = "memory_traces"
+            enabled = true
+            fields = { signal = "traces" }
+
+            [[exporter]]
+            name = "memory_metrics"
+            enabled = true
+            fields = { signal = "metrics" }
+            "#,
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+        let received: HashMap<String, memory::MemoryExporter> =
+            ["memory_logs", "memory_traces", "memory_metrics"]
+                .into_iter()
+                .map(|name| (name.to_string(), memory::MemoryExporter::new()))
+                .collect();
+        let runs = run_exporters_with(
+            &signal_exporters(&cfg).unwrap(),
+            Arc::clone(&datapoints),
+            &ExportContext::default(),
+            &CancellationToken::new(),
+            |name| Some(Box::new(received[name].clone())),
+        )
+        .unwrap();
+        assert_eq!(runs.len(), 3);
+        // the same generation fanned out; every signal got all the entries
+        for (name, memory) in received.iter() {
+            assert_eq!(memory.len(), 1000, "{}", name);
+        }
+
+        // a signal not listed; its exporter is disabled, then nothing emits the metrics
+        cfg.set_signals(Some(vec!["logs".to_string(), "traces".to_string()]));
+        let exporters = signal_exporters(&cfg).unwrap();
+        assert_eq!(*exporters[2].enabled(), Some(false));
+        cfg.set_signals(Some(vec!["metrics".to_string()]));
+        let mut exporters = cfg.exporters().clone().unwrap();
+        exporters[2].set_enabled(Some(false));
+        cfg.set_exporters(Some(exporters));
+        assert_eq!(
+            signal_exporters(&cfg).err().unwrap().to_string(),
+            "no enabled exporter for the [metrics] signal; tag one with the `signal` field"
+        );
+        cfg.set_signals(Some(vec!["profiles".to_string()]));
+        assert_eq!(
+            signal_exporters(&cfg).err().unwrap().to_string(),
+            "unknown signal [profiles]; expecting \"logs\", \"traces\" or \"metrics\""
+        );
+    }
+
+    #[test]
+    fn test_static_attributes_of() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let cfg: Config = toml::from_str(
+            format!(
+                r#"
+                number_of_entries = 100
+                timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+                use_now_as_timestamp = false
+                generation_duration = "10s"
+                start_timestamp = "2022-01-01T00:00:00.000+00:00"
+                distribution_by = "even"
+
+                [static_attributes]
+                dataset = "loadtest-2024"
+                "team \"a\"" = "perf"
+
+                [[exporter]]
+                name = "file"
+                enabled = true
+                [exporter.fields]
+                path = "{}"
+                filename = "rows.jsonl"
+                "#,
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        let ctx = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        run_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            datapoints,
+            &ctx,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(folder.path().join("rows.jsonl")).unwrap();
+        let records: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 100);
+        for record in records.iter() {
+            assert!(record["timestamp"].is_string(), "{}", record);
+            assert_eq!(record["dataset"], "loadtest-2024");
+            assert_eq!(record["team \"a\""], "perf");
+        }
+
+        // collisions with the generated fields
+        let mut cfg = cfg;
+        let static_attributes = cfg.static_attributes().clone().unwrap();
+        for (section, key) in [
+            ("static_attributes", "timestamp"),
+            ("attributes", "host"),
+            ("templates", "message"),
+        ] {
+            cfg.set_attributes(None);
+            cfg.set_templates(None);
+            match section {
+                "attributes" => cfg.set_attributes(Some(HashMap::from([(
+                    key.to_string(),
+                    vec!["host-1".to_string()],
+                )]))),
+                "templates" => {
+                    cfg.set_templates(Some(HashMap::from([(key.to_string(), "GET".to_string())])))
+                }
+                _ => &mut cfg,
+            };
+            let mut colliding = static_attributes.clone();
+            colliding.insert(key.to_string(), "x".to_string());
+            cfg.set_static_attributes(Some(colliding));
+            assert_eq!(
+                static_attributes_of(&cfg).err().unwrap().to_string(),
+                format!("static attribute [{}] collides with a generated field", key)
+            );
+        }
+    }
+
+    #[test]
+    fn test_timezone_of_rows() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let mut cfg: Config = toml::from_str(
+            format!(
+                r#"
+                number_of_entries = 2
+                timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+                use_now_as_timestamp = false
+                generation_duration = "2s"
+                start_timestamp = "2022-01-01T00:00:00.000+00:00"
+                distribution_by = "even"
+                timezone = "Asia/Hong_Kong"
+
+                [[exporter]]
+                name = "file"
+                enabled = true
+                [exporter.fields]
+                path = "{}"
+                filename = "rows.jsonl"
+                timestamp_format = "%Y-%m-%d %H:%M:%S%:z"
+                "#,
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        let ctx = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        run_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            datapoints,
+            &ctx,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        // the same instants; only the rendering shifts
+        assert_eq!(
+            std::fs::read_to_string(folder.path().join("rows.jsonl")).unwrap(),
+            "{\"timestamp\":\"2022-01-01 08:00:00+08:00\"}\n\
+             {\"timestamp\":\"2022-01-01 08:00:01+08:00\"}\n"
+        );
+
+        cfg.set_timezone(Some("Mars/Olympus_Mons".to_string()));
+        assert_eq!(
+            ExportContext::from_config(&cfg, 42, &[])
+                .err()
+                .unwrap()
+                .to_string(),
+            "unknown timezone [Mars/Olympus_Mons]; expecting an IANA name like \"Asia/Hong_Kong\""
+        );
+    }
+
+    #[test]
+    fn test_attributes_of_rows() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let mut cfg: Config = toml::from_str(
+            format!(
+                r#"
+                number_of_entries = 300
+                timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+                use_now_as_timestamp = false
+                generation_duration = "10s"
+                start_timestamp = "2022-01-01T00:00:00.000+00:00"
+                distribution_by = "even"
+
+                [attributes]
+                "service.name" = ["cart", "checkout", "payment"]
+                region = ["us-east-1"]
+                unused = []
+
+                [[exporter]]
+                name = "file"
+                enabled = true
+                [exporter.fields]
+                path = "{0}"
+                filename = "a.jsonl"
+
+                [[exporter]]
+                name = "file"
+                enabled = true
+                [exporter.fields]
+                path = "{0}"
+                filename = "b.jsonl"
+                "#,
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        let ctx = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        run_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            datapoints,
+            &ctx,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(folder.path().join("a.jsonl")).unwrap();
+        let mut seen_services = std::collections::HashSet::new();
+        for line in content.lines() {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            let service = record["service.name"].as_str().unwrap();
+            assert!(
+                ["cart", "checkout", "payment"].contains(&service),
+                "{}",
+                line
+            );
+            assert_eq!(record["region"], "us-east-1");
+            assert!(record.get("unused").is_none(), "{}", line);
+            seen_services.insert(service.to_string());
+        }
+        assert_eq!(content.lines().count(), 300);
+        assert_eq!(seen_services.len(), 3);
+        // the same seed; the same picks whatever the exporter
+        assert_eq!(
+            std::fs::read_to_string(folder.path().join("b.jsonl")).unwrap(),
+            content
+        );
+
+        // collisions with the generated fields
+        cfg.set_attributes(Some(HashMap::from([(
+            "value".to_string(),
+            vec!["1".to_string()],
+        )])));
+        assert_eq!(
+            attributes_of(&cfg).err().unwrap().to_string(),
+            "attribute [value] collides with a generated field"
+        );
+        cfg.set_attributes(Some(HashMap::from([(
+            "host".to_string(),
+            vec!["host-1".to_string()],
+        )])));
+        for field in ["index", "host"] {
+            cfg.set_templates(Some(HashMap::from([(
+                field.to_string(),
+                "GET".to_string(),
+            )])));
+            assert_eq!(
+                templates_of(&cfg).err().unwrap().to_string(),
+                format!("template [{}] collides with a generated field", field)
+            );
+        }
+    }
+
+    #[test]
+    fn test_export_stream_cancellation() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 3),
+            DataPoint::new(start_time + chrono::Duration::seconds(1), 2),
+        ];
+        let cfg: ConfigExporter = toml::from_str("name = \"file\"").unwrap();
+        let ctx = ExportContext::default();
+
+        // tripped while consuming the 1st datapoint; its rows are still exported
+        let mut records = row_records(&datapoints, &cfg, &ctx, None);
+        assert!(records.next().is_some());
+        ctx.cancellation.cancel();
+        assert_eq!(records.count(), 2);
+        assert_eq!(export_stream(&datapoints, &ctx).count(), 0);
+
+        // the row loop of an exporter; nothing consumed once tripped
+        let folder = tempfile::tempdir().unwrap();
+        let file_cfg: ConfigExporter = toml::from_str(
+            format!(
+                "name = \"file\"\n[fields]\npath = \"{}\"\nfilename = \"rows.jsonl\"",
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let summary = file::FileExporter
+            .export(&datapoints, &file_cfg, &ctx)
+            .unwrap();
+        assert_eq!(summary.rows_exported, 0);
+        assert_eq!(
+            std::fs::read_to_string(folder.path().join("rows.jsonl")).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_run_exporters_replay() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let cfg: Config = toml::from_str(
+            format!(
+                r#"
+                number_of_entries = 4
+                timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+                use_now_as_timestamp = false
+                generation_duration = "4s"
+                start_timestamp = "2022-01-01T00:00:00.000+00:00"
+                distribution_by = "even"
+                replay = true
+                speed = 10.0
+
+                [[exporter]]
+                name = "file"
+                enabled = true
+                [exporter.fields]
+                path = "{}"
+                filename = "rows.jsonl"
+                "#,
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        // the last datapoint is 3s after the 1st one; 0.3s at 10x
+        let ctx = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        let runs = run_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            datapoints,
+            &ctx,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert_eq!(runs[0].rows_exported, 4);
+        assert!(
+            runs[0].elapsed >= Duration::from_millis(300),
+            "{:?}",
+            runs[0]
+        );
+        assert!(runs[0].elapsed < Duration::from_secs(3), "{:?}", runs[0]);
+    }
+
+    #[test]
+    fn test_run_exporters_max_export_rate() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let mut cfg: Config = toml::from_str(
+            format!(
+                r#"
+                number_of_entries = 300
+                timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+                use_now_as_timestamp = false
+                generation_duration = "3s"
+                start_timestamp = "2022-01-01T00:00:00.000+00:00"
+                distribution_by = "even"
+
+                [[exporter]]
+                name = "file"
+                enabled = true
+                max_export_rate = 1000.0
+                [exporter.fields]
+                path = "{}"
+                filename = "rows.jsonl"
+                "#,
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        // 300 rows at 1000 rows/sec; the bucket starts empty
+        let ctx = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        let runs = run_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            Arc::clone(&datapoints),
+            &ctx,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert_eq!(runs[0].rows_exported, 300);
+        assert!(
+            runs[0].elapsed >= Duration::from_millis(290),
+            "{:?}",
+            runs[0]
+        );
+
+        // an invalid rate fails the exporter
+        for rate in [0.0, f64::NAN] {
+            let mut exporters = cfg.exporters().clone().unwrap();
+            exporters[0].set_max_export_rate(Some(rate));
+            cfg.set_exporters(Some(exporters));
+            assert_eq!(
+                run_exporters(
+                    cfg.exporters().as_ref().unwrap(),
+                    Arc::clone(&datapoints),
+                    &ctx,
+                    &CancellationToken::new(),
+                )
+                .err()
+                .unwrap()
+                .to_string(),
+                format!(
+                    "1 of 1 exporter(s) failed: [file] max_export_rate of exporter [file] must be positive, got [{}]",
+                    rate
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_cumulative_records() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let mut cfg: Config = toml::from_str(
+            format!(
+                r#"
+                number_of_entries = 1000
+                timestamp_format = "%Y-%m-%dT%H:%M:%S%.f%:z"
+                use_now_as_timestamp = false
+                generation_duration = "1m"
+                start_timestamp = "2022-01-01T00:00:00.000+00:00"
+                distribution_by = "even"
+                cumulative = true
+
+                [[exporter]]
+                name = "file"
+                enabled = true
+                [exporter.fields]
+                path = "{}"
+                filename = "counter.jsonl"
+                "#,
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let datapoints = Arc::new(generate_datapoints(&cfg).unwrap());
+
+        let runs = run_exporters(
+            cfg.exporters().as_ref().unwrap(),
+            Arc::clone(&datapoints),
+            &ExportContext::from_config(&cfg, 42, &datapoints).unwrap(),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        // 1 record per slot
+        assert_eq!(runs[0].rows_exported, 60);
+
+        let content = std::fs::read_to_string(folder.path().join("counter.jsonl")).unwrap();
+        let values: Vec<u64> = content
+            .lines()
+            .map(|line| {
+                let record: serde_json::Value = serde_json::from_str(line).unwrap();
+                record["value"].as_u64().unwrap()
+            })
+            .collect();
+        assert_eq!(values.len(), datapoints.len());
+        for pair in values.windows(2) {
+            assert!(pair[0] <= pair[1], "{:?}", values);
+        }
+        assert_eq!(*values.last().unwrap(), 1000);
+        assert_eq!(values[0], *datapoints[0].rows_to_add() as u64);
+
+        // the deltas as-is by default
+        cfg.set_cumulative(None);
+        let ctx = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        assert!(!ctx.cumulative);
+        assert_eq!(row_records_count(&datapoints, &ctx).unwrap(), 1000);
+    }
+
+    #[test]
+    fn test_row_records_flag_anomalies() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let mut cfg = test_config();
+        cfg.set_generation_duration(Some("3s".to_string()));
+        cfg.set_number_of_entries(Some(6));
+        // flat slots of 2 rows
+        cfg.set_distribution_by(Some("weights".to_string()));
+        cfg.set_weights(Some(vec![1.0]));
+        cfg.set_anomalies(Some(vec![ConfigAnomaly::new(0.5, "spike", Some(2.0))]));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let mut exporter = cfg.exporters().as_ref().unwrap()[0].clone();
+        let mut ctx = ExportContext::default();
+
+        // not flagged by default
+        let records: Vec<String> = row_records(&datapoints, &exporter, &ctx, None)
+            .map(|(_, payload)| payload)
+            .collect();
+        assert_eq!(records.len(), 6);
+        assert!(records.iter().all(|record| !record.contains("anomaly")));
+
+        exporter.set_flag_anomalies(Some(true));
+        let flagged: Vec<DateTime<Utc>> = row_records(&datapoints, &exporter, &ctx, None)
+            .filter(|(_, payload)| payload.ends_with(",\"anomaly\":\"spike\"}"))
+            .map(|(timestamp, _)| timestamp)
+            .collect();
+        assert_eq!(flagged, vec![start_time + chrono::Duration::seconds(1); 4]);
+
+        // 1 flagged slot in the cumulative mode
+        ctx.cumulative = true;
+        let records: Vec<String> = row_records(&datapoints, &exporter, &ctx, None)
+            .map(|(_, payload)| payload)
+            .collect();
+        assert_eq!(
+            records[1],
+            format!(
+                "{{\"timestamp\":\"{}\",\"value\":5,\"anomaly\":\"spike\"}}",
+                format_timestamp(&(start_time + chrono::Duration::seconds(1)), None)
+            )
+        );
+    }
+
+    #[test]
+    fn test_batch_idempotency_key() {
+        let cfg = test_config();
+        let exporters = cfg.exporters().clone().unwrap();
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let ctx = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        assert_eq!(ctx.run_key, run_key(&cfg, 42, &datapoints).unwrap());
+
+        // 2 attempts of the same batch; the same key
+        let key = batch_idempotency_key(&exporters[0], &ctx, 3);
+        assert_eq!(key.len(), 16);
+        assert_eq!(key, batch_idempotency_key(&exporters[0], &ctx, 3));
+        // reloaded the same (e.g. a re-run); the same key
+        let reloaded = ExportContext::from_config(&test_config(), 42, &datapoints).unwrap();
+        assert_eq!(key, batch_idempotency_key(&exporters[0], &reloaded, 3));
+
+        // another batch, exporter, seed or config; another key
+        assert_ne!(key, batch_idempotency_key(&exporters[0], &ctx, 4));
+        assert_ne!(key, batch_idempotency_key(&exporters[1], &ctx, 3));
+        let reseeded = ExportContext::from_config(&cfg, 43, &datapoints).unwrap();
+        assert_ne!(key, batch_idempotency_key(&exporters[0], &reseeded, 3));
+        let mut cfg = test_config();
+        cfg.set_number_of_entries(Some(1001));
+        let reconfigured = ExportContext::from_config(&cfg, 42, &datapoints).unwrap();
+        assert_ne!(key, batch_idempotency_key(&exporters[0], &reconfigured, 3));
+    }
+
+    #[test]
+    fn test_run_key_time_range() {
+        // the same seed and config (e.g. use_now_as_timestamp) run an hour later
+        let cfg = test_config();
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let later: Vec<DataPoint> = datapoints
+            .iter()
+            .map(|datapoint| {
+                DataPoint::new(
+                    *datapoint.timestamp() + chrono::Duration::hours(1),
+                    *datapoint.rows_to_add(),
+                )
+            })
+            .collect();
+        assert_ne!(
+            run_key(&cfg, 42, &datapoints).unwrap(),
+            run_key(&cfg, 42, &later).unwrap()
+        );
+        assert_eq!(
+            run_key(&cfg, 42, &datapoints).unwrap(),
+            run_key(&cfg, 42, &datapoints.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_filter_export_range() {
+        let mut cfg = test_config();
+        cfg.set_seed(Some(42));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+
+        // the 10th to the 20th second inclusive
+        cfg.set_export_from(Some("2022-01-01T00:00:10.000+00:00".to_string()));
+        cfg.set_export_to(Some("2022-01-01T00:00:20.000+00:00".to_string()));
+        let (export_from, export_to) = export_range(&cfg).unwrap();
+        let filtered = filter_export_range(&datapoints, export_from, export_to);
+        assert_eq!(filtered.len(), 11);
+        assert_eq!(
+            *filtered[0].timestamp(),
+            start_time + chrono::Duration::seconds(10)
+        );
+        assert_eq!(
+            *filtered[10].timestamp(),
+            start_time + chrono::Duration::seconds(20)
+        );
+        assert_eq!(
+            total_rows(&filtered).unwrap(),
+            total_rows(&datapoints[10..=20]).unwrap()
+        );
+
+        // open ended
+        cfg.set_export_from(None);
+        let (export_from, export_to) = export_range(&cfg).unwrap();
+        assert_eq!(
+            filter_export_range(&datapoints, export_from, export_to).len(),
+            21
+        );
+
+        // entirely outside the window; nothing exported
+        cfg.set_export_from(Some("2023-01-01T00:00:00.000+00:00".to_string()));
+        cfg.set_export_to(None);
+        let (export_from, export_to) = export_range(&cfg).unwrap();
+        assert!(filter_export_range(&datapoints, export_from, export_to).is_empty());
+
+        cfg.set_export_to(Some("2022-01-01T00:00:20.000+00:00".to_string()));
+        assert_eq!(
+            export_range(&cfg).err().unwrap().to_string(),
+            "export_from [2023-01-01T00:00:00.000+00:00] must be <= export_to [2022-01-01T00:00:20.000+00:00]"
+        );
+        cfg.set_export_to(Some("2022/01/01".to_string()));
+        assert_eq!(
+            export_range(&cfg).err().unwrap().to_string(),
+            "failed to parse export_to [2022/01/01] with format [%Y-%m-%dT%H:%M:%S%.f%:z]: input contains invalid characters"
+        );
+    }
+
+    #[test]
+    fn test_slot_grid() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let mut cfg = test_config();
+        assert_eq!(
+            SlotGrid::from_config(&cfg).unwrap(),
+            SlotGrid {
+                windows: vec![(start_time, start_time + TimeDelta::minutes(1))],
+                interval: TimeDelta::seconds(1),
+            }
+        );
+
+        // 1 window per repeat; within the export range, on the slot boundaries
+        cfg.set_repeat(Some(ConfigRepeat::new(3, "1h")));
+        cfg.set_export_from(Some("2022-01-01T00:00:10.500+00:00".to_string()));
+        cfg.set_export_to(Some("2022-01-01T01:00:20.000+00:00".to_string()));
+        let grid = SlotGrid::from_config(&cfg).unwrap();
+        assert_eq!(
+            grid.windows,
+            vec![
+                (
+                    start_time + TimeDelta::seconds(11),
+                    start_time + TimeDelta::minutes(1)
+                ),
+                (
+                    start_time + TimeDelta::hours(1),
+                    start_time
+                        + TimeDelta::hours(1)
+                        + TimeDelta::seconds(20)
+                        + TimeDelta::nanoseconds(1)
+                ),
+            ]
+        );
+
+        // the whole minutes of the truncated timestamps
+        cfg.set_repeat(None);
+        cfg.set_export_from(None);
+        cfg.set_export_to(None);
+        cfg.set_start_timestamp(Some("2022-01-01T00:00:30.000+00:00".to_string()));
+        cfg.set_timestamp_truncate(Some("minute".to_string()));
+        assert_eq!(
+            SlotGrid::from_config(&cfg).unwrap(),
+            SlotGrid {
+                windows: vec![(start_time, start_time + TimeDelta::minutes(2))],
+                interval: TimeDelta::minutes(1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_truncate_datapoints() {
+        // 2 minutes of per-second slots (10000 rows over 10m; 16 or 17 rows per slot)
+        let mut cfg = test_config();
+        cfg.set_number_of_entries(Some(10000));
+        cfg.set_generation_duration(Some("10m".to_string()));
+        cfg.set_seed(Some(42));
+        let datapoints: Vec<DataPoint> = generate_datapoints(&cfg).unwrap()[..120].to_vec();
+
+        let truncated = truncate_datapoints(&datapoints, TimestampTruncate::Minute);
+        // 60 per-second slots collapse into 1 per minute
+        assert_eq!(truncated.len(), 2);
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        assert_eq!(*truncated[0].timestamp(), start_time);
+        assert_eq!(
+            *truncated[1].timestamp(),
+            start_time + TimeDelta::minutes(1)
+        );
+        assert_eq!(
+            *truncated[0].rows_to_add() as u64,
+            total_rows(&datapoints[..60]).unwrap()
+        );
+        assert_eq!(
+            total_rows(&truncated).unwrap(),
+            total_rows(&datapoints).unwrap()
+        );
+
+        // an hour bucket beyond the i16 range is split; no rows lost
+        let datapoints: Vec<DataPoint> = (0..3600)
+            .map(|second| DataPoint::new(start_time + TimeDelta::seconds(second), 100))
+            .collect();
+        let truncated = truncate_datapoints(&datapoints, TimestampTruncate::Hour);
+        assert_eq!(total_rows(&truncated).unwrap(), 360000);
+        assert!(truncated.iter().all(|d| *d.timestamp() == start_time));
+
+        assert_eq!(
+            truncate_datapoints(&datapoints, TimestampTruncate::None).len(),
+            3600
+        );
+
+        cfg.set_timestamp_truncate(Some("Minute".to_string()));
+        assert_eq!(
+            TimestampTruncate::from_config(&cfg).unwrap(),
+            TimestampTruncate::Minute
+        );
+        cfg.set_timestamp_truncate(Some("day".to_string()));
+        assert_eq!(
+            TimestampTruncate::from_config(&cfg)
+                .err()
+                .unwrap()
+                .to_string(),
+            "unknown timestamp_truncate [day]; expecting \"none\", \"minute\" or \"hour\""
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        let mut cfg = test_config();
+        cfg.set_seed(Some(42));
+        cfg.set_timestamp_jitter(Some("800ms".to_string()));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let jittered = datapoints
+            .iter()
+            .map(|datapoint| *datapoint.timestamp())
+            .find(|timestamp| timestamp.timestamp_subsec_nanos() % 1_000_000 != 0)
+            .unwrap();
+        let nanos = format!("{:09}", jittered.timestamp_subsec_nanos());
+
+        // no format; rfc3339 with the full nanoseconds
+        let formatted = format_timestamp(&jittered, None);
+        assert!(formatted.ends_with(format!(".{}Z", nanos).as_str()));
+        assert_eq!(formatted.parse::<DateTime<Utc>>().unwrap(), jittered);
+
+        // the configured format is honoured
+        let formatted = format_timestamp(&jittered, Some("%Y-%m-%dT%H:%M:%S%.9f%:z"));
+        assert!(formatted.ends_with(format!(".{}+00:00", nanos).as_str()));
+        let formatted = format_timestamp(&jittered, Some("%Y-%m-%d %H:%M:%S%.3f"));
+        assert_eq!(formatted.len(), "2022-01-01 00:00:00.000".len());
+
+        assert_eq!(
+            row_payload(&jittered, None, &Tz::UTC, &[]),
+            format!(
+                "{{\"timestamp\":\"{}\"}}",
+                format_timestamp(&jittered, None)
+            )
+        );
+    }
+
+    /// a mocked clock; sleeping simply moves the time forward.
+    struct MockClock {
+        now: Cell<Duration>,
+    }
+
+    impl ReplayClock for MockClock {
+        fn elapsed(&self) -> Duration {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.now.set(self.now.get() + duration);
+        }
+    }
+
+    #[test]
+    fn test_paced_datapoints_replay() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let mut cfg = test_config();
+        cfg.set_generation_duration(Some("5s".to_string()));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), 5);
+
+        // [case][01] replay in real time; datapoint N is emitted at N seconds
+        cfg.set_replay(Some(true));
+        let clock = MockClock {
+            now: Cell::new(Duration::ZERO),
+        };
+        let cancellation = CancellationToken::new();
+        let speed = replay_speed(&cfg).unwrap();
+        assert_eq!(speed, Some(1.0));
+        let emitted: Vec<(Duration, DateTime<Utc>)> =
+            paced_datapoints(&datapoints, speed, None, &clock, &cancellation)
+                .map(|datapoint| (clock.elapsed(), *datapoint.timestamp()))
+                .collect();
+        assert_eq!(emitted.len(), 5);
+        for (i, (emitted_at, timestamp)) in emitted.iter().enumerate() {
+            assert_eq!(*timestamp, *datapoints[i].timestamp());
+            assert_eq!(*emitted_at, Duration::from_secs(i as u64));
+        }
+
+        // [case][02] replay 10x faster
+        cfg.set_speed(Some(10.0));
+        let clock = MockClock {
+            now: Cell::new(Duration::ZERO),
+        };
+        let emitted_at: Vec<Duration> = paced_datapoints(
+            &datapoints,
+            replay_speed(&cfg).unwrap(),
+            None,
+            &clock,
+            &cancellation,
+        )
+        .map(|_| clock.elapsed())
+        .collect();
+        for (i, elapsed) in emitted_at.iter().enumerate() {
+            assert_eq!(*elapsed, Duration::from_millis(100 * i as u64));
+        }
+
+        // [case][03] no replay; everything is emitted at once
+        cfg.set_replay(Some(false));
+        let clock = MockClock {
+            now: Cell::new(Duration::ZERO),
+        };
+        assert_eq!(replay_speed(&cfg).unwrap(), None);
+        let count = paced_datapoints(&datapoints, None, None, &clock, &cancellation).count();
+        assert_eq!(count, 5);
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+
+        // [case][04] interrupted after the 2nd datapoint; the wait for the 3rd one is skipped
+        let clock = MockClock {
+            now: Cell::new(Duration::ZERO),
+        };
+        let mut paced = paced_datapoints(&datapoints, Some(1.0), None, &clock, &cancellation);
+        assert_eq!(paced.by_ref().take(2).count(), 2);
+        cancellation.cancel();
+        assert_eq!(paced.count(), 0);
+        assert!(clock.elapsed() < Duration::from_secs(2));
+
+        // [case][05] invalid speeds; rejected whether replaying or not
+        for speed in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            cfg.set_speed(Some(speed));
+            assert_eq!(
+                replay_speed(&cfg).err().unwrap().to_string(),
+                format!("replay speed must be positive, got [{}]", speed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_paced_datapoints_max_export_rate() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let cfg = test_config();
+        let start_time = *generate_datapoints(&cfg).unwrap()[0].timestamp();
+        // bursty slots; 1 second apart
+        let datapoints: Vec<DataPoint> = [3000, 0, 500, 10, 2500, 1]
+            .iter()
+            .enumerate()
+            .map(|(i, rows)| DataPoint::new(start_time + TimeDelta::seconds(i as i64), *rows))
+            .collect();
+        let total: u64 = total_rows(&datapoints).unwrap();
+        let rate = 1000.0;
+
+        // every emit is within the cap: rows emitted so far <= rate * elapsed
+        let assert_capped = |emitted: &[(Duration, u64)]| {
+            for (elapsed, rows) in emitted {
+                assert!(
+                    *rows as f64 <= rate * elapsed.as_secs_f64() + 1e-6,
+                    "{} rows emitted by {:?}",
+                    rows,
+                    elapsed
+                );
+            }
+        };
+
+        // [case][01] immediate mode; paced by the cap only
+        let clock = MockClock {
+            now: Cell::new(Duration::ZERO),
+        };
+        let cancellation = CancellationToken::new();
+        let mut rows = 0;
+        let emitted: Vec<(Duration, u64)> =
+            paced_datapoints(&datapoints, None, Some(rate), &clock, &cancellation)
+                .map(|datapoint| {
+                    rows += *datapoint.rows_to_add() as u64;
+                    (clock.elapsed(), rows)
+                })
+                .collect();
+        assert_eq!(emitted.len(), datapoints.len());
+        assert_eq!(rows, total);
+        assert_capped(&emitted);
+        // never idle, hence exactly at the cap on average
+        assert!((clock.elapsed().as_secs_f64() - total as f64 / rate).abs() < 1e-3);
+
+        // [case][02] replay; the slots larger than the cap are spread over the next seconds
+        let mut cfg = test_config();
+        cfg.set_replay(Some(true));
+        let clock = MockClock {
+            now: Cell::new(Duration::ZERO),
+        };
+        let mut rows = 0;
+        let emitted: Vec<(Duration, u64)> = paced_datapoints(
+            &datapoints,
+            replay_speed(&cfg).unwrap(),
+            Some(rate),
+            &clock,
+            &cancellation,
+        )
+        .map(|datapoint| {
+            rows += *datapoint.rows_to_add() as u64;
+            (clock.elapsed(), rows)
+        })
+        .collect();
+        assert_eq!(rows, total);
+        assert_capped(&emitted);
+        // never emitted before the replay offset
+        for (i, (elapsed, _)) in emitted.iter().enumerate() {
+            assert!(*elapsed >= Duration::from_secs(i as u64));
+        }
+    }
+}
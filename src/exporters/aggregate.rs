@@ -0,0 +1,468 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use chrono::{DateTime, Utc};
+
+use super::file::output_file_path;
+use super::{
+    format_timestamp, timestamp_format_of, ExportContext, ExportSummary, Exporter, SlotGrid,
+};
+use crate::augmentation::{total_rows, DataPoint};
+use crate::config::ConfigExporter;
+
+pub const AGGREGATE_EXPORTER: &str = "aggregate";
+
+/// The field picking the output format; `"csv"` (the default) or `"jsonl"`.
+pub const FORMAT_FIELD: &str = "format";
+
+/// The line format of the aggregate exporter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AggregateFormat {
+    /// `2022-01-01T00:00:00.000000000Z,15`
+    Csv,
+    /// `{"timestamp":"2022-01-01T00:00:00.000000000Z","count":15}`
+    Jsonl,
+}
+
+impl AggregateFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.to_lowercase().as_str() {
+            "csv" => Ok(AggregateFormat::Csv),
+            "jsonl" => Ok(AggregateFormat::Jsonl),
+            _ => Err(format!(
+                "unknown {} [{}]; expecting \"csv\" or \"jsonl\"",
+                FORMAT_FIELD, format
+            )),
+        }
+    }
+}
+
+/// The field deciding how the gap slots (no datapoint in between 2 datapoints) are written;
+/// `"omit"` (the default), `"zero"` or `"null"`.
+pub const GAP_MARKER_FIELD: &str = "gap_marker";
+
+/// How a gap slot is written by the aggregate exporter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GapMarker {
+    /// no line; the gap is simply missing.
+    Omit,
+    /// a line with a 0 count.
+    Zero,
+    /// a line with a null count (an empty CSV count); plotting tools (e.g. Grafana) break the line.
+    Null,
+}
+
+impl GapMarker {
+    pub fn parse(gap_marker: &str) -> Result<Self, String> {
+        match gap_marker.to_lowercase().as_str() {
+            "omit" => Ok(GapMarker::Omit),
+            "zero" => Ok(GapMarker::Zero),
+            "null" => Ok(GapMarker::Null),
+            _ => Err(format!(
+                "unknown {} [{}]; expecting \"omit\", \"zero\" or \"null\"",
+                GAP_MARKER_FIELD, gap_marker
+            )),
+        }
+    }
+}
+
+/// Writes the per-slot aggregates (1 line per datapoint; its timestamp and row count) into the
+/// `filename` under the `path` folder. The rows are never expanded, hence much cheaper than
+/// exporting the rows to re-aggregate them (e.g. for plotting the generated series).
+///
+/// With the `gap_marker` field, the gap slots of the generation (e.g. of the sparse_fill model)
+/// get a line too; see `aggregate_lines`.
+///
+/// The static attributes follow the count on every line; as trailing CSV columns (in key order)
+/// or as JSONL fields.
+pub struct AggregateExporter;
+
+impl Exporter for AggregateExporter {
+    fn name(&self) -> &str {
+        AGGREGATE_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let fields = cfg.fields().clone().unwrap_or_default();
+        let (path, filename) = match (fields.get("path"), fields.get("filename")) {
+            (Some(path), Some(filename)) => (path, filename),
+            _ => {
+                return Err(format!(
+                    "missing `path` / `filename` fields for the {} exporter",
+                    AGGREGATE_EXPORTER
+                ))
+            }
+        };
+        let format = match fields.get(FORMAT_FIELD) {
+            Some(format) => AggregateFormat::parse(format)?,
+            None => AggregateFormat::Csv,
+        };
+        let gap_marker = match fields.get(GAP_MARKER_FIELD) {
+            Some(gap_marker) => GapMarker::parse(gap_marker)?,
+            None => GapMarker::Omit,
+        };
+        let file_path = output_file_path(path, filename);
+        let write_error =
+            |e: std::io::Error| format!("failed to write to [{}]: {}", file_path.display(), e);
+
+        std::fs::create_dir_all(path).map_err(write_error)?;
+        let mut out = BufWriter::new(File::create(&file_path).map_err(write_error)?);
+        let timestamp_format = timestamp_format_of(cfg);
+        // the same on every line
+        let attributes: String = ctx
+            .static_attributes
+            .iter()
+            .map(|(key, value)| match format {
+                AggregateFormat::Csv => format!(",{}", value),
+                AggregateFormat::Jsonl => format!(
+                    ",{}:{}",
+                    serde_json::Value::from(key.as_str()),
+                    serde_json::Value::from(value.as_str())
+                ),
+            })
+            .collect();
+        for (timestamp, count) in aggregate_lines(datapoints, gap_marker, &ctx.slot_grid) {
+            let timestamp =
+                format_timestamp(&timestamp.with_timezone(&ctx.timezone), timestamp_format);
+            match format {
+                AggregateFormat::Csv => writeln!(
+                    out,
+                    "{},{}{}",
+                    timestamp,
+                    count.map(|count| count.to_string()).unwrap_or_default(),
+                    attributes
+                ),
+                AggregateFormat::Jsonl => writeln!(
+                    out,
+                    "{{\"timestamp\":{},\"count\":{}{}}}",
+                    serde_json::Value::from(timestamp),
+                    serde_json::Value::from(count),
+                    attributes
+                ),
+            }
+            .map_err(write_error)?;
+        }
+        out.flush().map_err(write_error)?;
+        Ok(ExportSummary {
+            rows_exported: total_rows(datapoints)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// The (timestamp, count) lines of the datapoints in order; a None count is a null gap marker.
+///
+/// Unless `GapMarker::Omit`, every slot of the `grid` without a datapoint gets a line too (at
+/// the start of the slot); before the 1st datapoint and after the last one as well. A datapoint
+/// fills the slot it falls within, hence also a jittered one.
+pub fn aggregate_lines(
+    datapoints: &[DataPoint],
+    gap_marker: GapMarker,
+    grid: &SlotGrid,
+) -> Vec<(DateTime<Utc>, Option<i64>)> {
+    let line = |datapoint: &DataPoint| {
+        (
+            *datapoint.timestamp(),
+            Some((*datapoint.rows_to_add()).max(0) as i64),
+        )
+    };
+    let gap_count = match gap_marker {
+        GapMarker::Omit => return datapoints.iter().map(line).collect(),
+        GapMarker::Zero => Some(0),
+        GapMarker::Null => None,
+    };
+    // the datapoints could be in the descending order; merged oldest first then flipped back
+    let descending = datapoints
+        .first()
+        .zip(datapoints.last())
+        .is_some_and(|(first, last)| first.timestamp() > last.timestamp());
+    let mut datapoints = datapoints.to_vec();
+    if descending {
+        datapoints.reverse();
+    }
+    let interval = grid.interval.num_milliseconds().max(1);
+    let mut lines: Vec<(DateTime<Utc>, Option<i64>)> = vec![];
+    for (start, end) in grid.windows.iter() {
+        let slots = ((*end - *start).num_milliseconds() + interval - 1) / interval;
+        let mut filled = vec![false; slots as usize];
+        for datapoint in datapoints.iter() {
+            if datapoint.timestamp() >= start && datapoint.timestamp() < end {
+                filled
+                    [((*datapoint.timestamp() - *start).num_milliseconds() / interval) as usize] =
+                    true;
+            }
+        }
+        lines.extend(
+            filled
+                .iter()
+                .enumerate()
+                .filter(|(_, filled)| !**filled)
+                .map(|(slot, _)| (*start + grid.interval * slot as i32, gap_count)),
+        );
+    }
+    // a gap line comes before a datapoint of the same timestamp (none in practice)
+    lines.extend(datapoints.iter().map(line));
+    lines.sort_by_key(|(timestamp, _)| *timestamp);
+    if descending {
+        lines.reverse();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_export_aggregate() {
+        let folder = tempfile::tempdir().unwrap();
+        let path = folder.path().join("generated");
+        let cfg_with_format = |format: &str| -> ConfigExporter {
+            toml::from_str(
+                format!(
+                    r#"
+                    name = "aggregate"
+                    enabled = true
+                    [fields]
+                    path = "{}"
+                    filename = "series.out"
+                    format = "{}"
+                    "#,
+                    path.display(),
+                    format
+                )
+                .as_str(),
+            )
+            .unwrap()
+        };
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 15),
+            DataPoint::new(start_time + Duration::seconds(1), 0),
+            DataPoint::new(start_time + Duration::seconds(2), 7),
+        ];
+
+        let summary = AggregateExporter
+            .export(
+                &datapoints,
+                &cfg_with_format("csv"),
+                &ExportContext::default(),
+            )
+            .unwrap();
+        assert_eq!(summary.rows_exported, 22);
+        let content = std::fs::read_to_string(path.join("series.out")).unwrap();
+        assert_eq!(content.lines().count(), datapoints.len());
+        assert_eq!(
+            content.lines().next().unwrap(),
+            "2022-01-01T00:00:00.000000000Z,15"
+        );
+
+        AggregateExporter
+            .export(
+                &datapoints,
+                &cfg_with_format("JSONL"),
+                &ExportContext::default(),
+            )
+            .unwrap();
+        let content = std::fs::read_to_string(path.join("series.out")).unwrap();
+        assert_eq!(content.lines().count(), datapoints.len());
+        assert_eq!(
+            content.lines().last().unwrap(),
+            "{\"timestamp\":\"2022-01-01T00:00:02.000000000Z\",\"count\":7}"
+        );
+
+        // the static attributes on every line
+        let ctx = ExportContext {
+            static_attributes: vec![("dataset".to_string(), "loadtest".to_string())],
+            ..Default::default()
+        };
+        AggregateExporter
+            .export(&datapoints, &cfg_with_format("csv"), &ctx)
+            .unwrap();
+        let content = std::fs::read_to_string(path.join("series.out")).unwrap();
+        assert_eq!(
+            content.lines().next().unwrap(),
+            "2022-01-01T00:00:00.000000000Z,15,loadtest"
+        );
+        AggregateExporter
+            .export(&datapoints, &cfg_with_format("jsonl"), &ctx)
+            .unwrap();
+        let content = std::fs::read_to_string(path.join("series.out")).unwrap();
+        assert!(content
+            .lines()
+            .all(|line| line.ends_with(",\"dataset\":\"loadtest\"}")));
+
+        // rendered in the configured timezone
+        let ctx = ExportContext {
+            timezone: chrono_tz::Tz::Asia__Hong_Kong,
+            ..Default::default()
+        };
+        AggregateExporter
+            .export(&datapoints, &cfg_with_format("csv"), &ctx)
+            .unwrap();
+        let content = std::fs::read_to_string(path.join("series.out")).unwrap();
+        assert_eq!(
+            content.lines().next().unwrap(),
+            "2022-01-01T08:00:00.000000000+08:00,15"
+        );
+
+        assert_eq!(
+            AggregateExporter
+                .export(
+                    &datapoints,
+                    &cfg_with_format("xml"),
+                    &ExportContext::default()
+                )
+                .err()
+                .unwrap(),
+            "unknown format [xml]; expecting \"csv\" or \"jsonl\""
+        );
+    }
+
+    #[test]
+    fn test_export_aggregate_gap_marker() {
+        let folder = tempfile::tempdir().unwrap();
+        let path = folder.path().join("generated");
+        let cfg_with_gap_marker = |format: &str, gap_marker: &str| -> ConfigExporter {
+            toml::from_str(
+                format!(
+                    r#"
+                    name = "aggregate"
+                    enabled = true
+                    [fields]
+                    path = "{}"
+                    filename = "series.out"
+                    format = "{}"
+                    gap_marker = "{}"
+                    "#,
+                    path.display(),
+                    format,
+                    gap_marker
+                )
+                .as_str(),
+            )
+            .unwrap()
+        };
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        // the slots of a 6 seconds generation
+        let mut generation_cfg = Config::new();
+        generation_cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+        generation_cfg.set_use_now_as_timestamp(Some(false));
+        generation_cfg.set_generation_duration(Some("6s".to_string()));
+        generation_cfg.set_start_timestamp(Some("2022-01-01T00:00:00.000+00:00".to_string()));
+        let ctx = ExportContext {
+            slot_grid: SlotGrid::from_config(&generation_cfg).unwrap(),
+            ..Default::default()
+        };
+        // a leading gap slot (the 1st second), a gap slot (the 4th second) in between and a
+        // trailing gap slot (the 6th second)
+        let datapoints = vec![
+            DataPoint::new(start_time + Duration::seconds(1), 3),
+            DataPoint::new(start_time + Duration::seconds(2), 2),
+            DataPoint::new(start_time + Duration::seconds(4), 5),
+        ];
+
+        AggregateExporter
+            .export(&datapoints, &cfg_with_gap_marker("jsonl", "null"), &ctx)
+            .unwrap();
+        let content = std::fs::read_to_string(path.join("series.out")).unwrap();
+        assert_eq!(
+            content.lines().collect::<Vec<&str>>(),
+            vec![
+                "{\"timestamp\":\"2022-01-01T00:00:00.000000000Z\",\"count\":null}",
+                "{\"timestamp\":\"2022-01-01T00:00:01.000000000Z\",\"count\":3}",
+                "{\"timestamp\":\"2022-01-01T00:00:02.000000000Z\",\"count\":2}",
+                "{\"timestamp\":\"2022-01-01T00:00:03.000000000Z\",\"count\":null}",
+                "{\"timestamp\":\"2022-01-01T00:00:04.000000000Z\",\"count\":5}",
+                "{\"timestamp\":\"2022-01-01T00:00:05.000000000Z\",\"count\":null}",
+            ]
+        );
+
+        AggregateExporter
+            .export(&datapoints, &cfg_with_gap_marker("csv", "null"), &ctx)
+            .unwrap();
+        let content = std::fs::read_to_string(path.join("series.out")).unwrap();
+        assert_eq!(
+            content.lines().nth(3).unwrap(),
+            "2022-01-01T00:00:03.000000000Z,"
+        );
+
+        // zero; in the descending order too
+        let mut descending = datapoints.clone();
+        descending.reverse();
+        let counts: Vec<Option<i64>> =
+            aggregate_lines(&descending, GapMarker::Zero, &ctx.slot_grid)
+                .into_iter()
+                .map(|(_, count)| count)
+                .collect();
+        assert_eq!(
+            counts,
+            vec![Some(0), Some(5), Some(0), Some(2), Some(3), Some(0)]
+        );
+        // omit (the default); as is
+        assert_eq!(
+            aggregate_lines(&datapoints, GapMarker::Omit, &ctx.slot_grid).len(),
+            3
+        );
+
+        // jittered timestamps fill the slot they fall within; no spacing to infer the slots from
+        let jittered = vec![
+            DataPoint::new(start_time + Duration::milliseconds(1300), 1),
+            DataPoint::new(start_time + Duration::milliseconds(2700), 1),
+            DataPoint::new(start_time + Duration::milliseconds(4100), 1),
+        ];
+        let gaps: Vec<DateTime<Utc>> = aggregate_lines(&jittered, GapMarker::Null, &ctx.slot_grid)
+            .into_iter()
+            .filter(|(_, count)| count.is_none())
+            .map(|(timestamp, _)| timestamp)
+            .collect();
+        assert_eq!(
+            gaps,
+            vec![
+                start_time,
+                start_time + Duration::seconds(3),
+                start_time + Duration::seconds(5)
+            ]
+        );
+
+        // the slots 1 minute wide; as configured, not the smallest spacing of the datapoints
+        generation_cfg.set_generation_duration(None);
+        generation_cfg.set_slot_count(Some(5));
+        generation_cfg.set_slot_width(Some("1m".to_string()));
+        let wide_grid = SlotGrid::from_config(&generation_cfg).unwrap();
+        let wide = vec![
+            DataPoint::new(start_time + Duration::minutes(1), 1),
+            DataPoint::new(start_time + Duration::minutes(3), 1),
+        ];
+        assert_eq!(
+            aggregate_lines(&wide, GapMarker::Null, &wide_grid),
+            vec![
+                (start_time, None),
+                (start_time + Duration::minutes(1), Some(1)),
+                (start_time + Duration::minutes(2), None),
+                (start_time + Duration::minutes(3), Some(1)),
+                (start_time + Duration::minutes(4), None),
+            ]
+        );
+
+        assert_eq!(
+            AggregateExporter
+                .export(
+                    &datapoints,
+                    &cfg_with_gap_marker("csv", "nan"),
+                    &ExportContext::default()
+                )
+                .err()
+                .unwrap(),
+            "unknown gap_marker [nan]; expecting \"omit\", \"zero\" or \"null\""
+        );
+    }
+}
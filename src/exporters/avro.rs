@@ -0,0 +1,167 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+use apache_avro::types::{Record, Value};
+use apache_avro::{Schema, Writer};
+
+use super::file::output_file_path;
+use super::{export_stream, ExportContext, ExportSummary, Exporter};
+use crate::augmentation::DataPoint;
+use crate::config::ConfigExporter;
+
+pub const AVRO_EXPORTER: &str = "avro";
+
+/// The field pointing to an Avro schema (JSON) file replacing the built-in `DEFAULT_SCHEMA`.
+pub const SCHEMA_PATH_FIELD: &str = "schema_path";
+
+/// The built-in schema; 1 record per row with the row's `timestamp` (epoch millis) and its
+/// 0-based `index` in the stream. A custom schema needs both fields (of type `long`) and may
+/// declare `string` fields for the `static_attributes`.
+pub const DEFAULT_SCHEMA: &str = r#"{
+    "type": "record",
+    "name": "BroccoliRow",
+    "namespace": "otel_broccoli",
+    "fields": [
+        {"name": "timestamp", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+        {"name": "index", "type": "long"}
+    ]
+}"#;
+
+/// Writes the generated rows as Avro records into the `filename` under the `path` folder (an
+/// Avro object container file, e.g. for Hadoop / Spark ingestion).
+///
+/// The records are streamed; the writer flushes a block whenever its buffer fills up, hence
+/// large datasets are never held in memory as records.
+pub struct AvroExporter;
+
+impl Exporter for AvroExporter {
+    fn name(&self) -> &str {
+        AVRO_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let fields = cfg.fields().clone().unwrap_or_default();
+        let (path, filename) = match (fields.get("path"), fields.get("filename")) {
+            (Some(path), Some(filename)) => (path, filename),
+            _ => {
+                return Err(format!(
+                    "missing `path` / `filename` fields for the {} exporter",
+                    AVRO_EXPORTER
+                ))
+            }
+        };
+        let schema = match fields.get(SCHEMA_PATH_FIELD) {
+            Some(schema_path) => {
+                let content = std::fs::read_to_string(schema_path).map_err(|e| {
+                    format!("failed to read the avro schema [{}]: {}", schema_path, e)
+                })?;
+                Schema::parse_str(&content)
+                    .map_err(|e| format!("invalid avro schema [{}]: {}", schema_path, e))?
+            }
+            None => Schema::parse_str(DEFAULT_SCHEMA)
+                .map_err(|e| format!("invalid built-in avro schema: {}", e))?,
+        };
+
+        let file_path = output_file_path(path, filename);
+        let write_error =
+            |e: String| format!("failed to write to [{}]: {}", file_path.display(), e);
+        std::fs::create_dir_all(path).map_err(|e| write_error(e.to_string()))?;
+        let file = File::create(&file_path).map_err(|e| write_error(e.to_string()))?;
+        let mut writer = Writer::new(&schema, BufWriter::new(file));
+
+        let mut rows_exported: u64 = 0;
+        let timestamps = export_stream(datapoints, ctx).flat_map(|datapoint| {
+            std::iter::repeat_n(
+                *datapoint.timestamp(),
+                (*datapoint.rows_to_add()).max(0) as usize,
+            )
+        });
+        for timestamp in timestamps {
+            let mut record = Record::new(&schema).ok_or(format!(
+                "the avro schema of the {} exporter is not a record",
+                AVRO_EXPORTER
+            ))?;
+            record.put("timestamp", Value::Long(timestamp.timestamp_millis()));
+            record.put("index", Value::Long(rows_exported as i64));
+            // only kept if the (custom) schema declares the field
+            for (key, value) in ctx.static_attributes.iter() {
+                record.put(key, Value::String(value.clone()));
+            }
+            writer
+                .append(record)
+                .map_err(|e| write_error(e.to_string()))?;
+            rows_exported += 1;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| write_error(e.to_string()))?
+            .into_inner()
+            .map_err(|e| write_error(e.to_string()))?;
+        Ok(ExportSummary {
+            rows_exported,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::Reader;
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+
+    #[test]
+    fn test_export_avro() {
+        let folder = tempfile::tempdir().unwrap();
+        let path = folder.path().join("generated");
+        let cfg: ConfigExporter = toml::from_str(
+            format!(
+                r#"
+                name = "avro"
+                enabled = true
+                [fields]
+                path = "{}"
+                filename = "rows.avro"
+                "#,
+                path.display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 2),
+            DataPoint::new(start_time + Duration::seconds(1), 0),
+            DataPoint::new(start_time + Duration::seconds(2), 1),
+        ];
+        let summary = AvroExporter
+            .export(&datapoints, &cfg, &ExportContext::default())
+            .unwrap();
+        assert_eq!(summary.rows_exported, 3);
+
+        let reader = Reader::new(File::open(path.join("rows.avro")).unwrap()).unwrap();
+        let records: Vec<(i64, i64)> = reader
+            .map(|value| match value.unwrap() {
+                Value::Record(fields) => {
+                    let long = |name: &str| -> i64 {
+                        match fields.iter().find(|(field, _)| field == name) {
+                            Some((_, Value::Long(v))) | Some((_, Value::TimestampMillis(v))) => *v,
+                            other => panic!("unexpected [{}] field {:?}", name, other),
+                        }
+                    };
+                    (long("timestamp"), long("index"))
+                }
+                other => panic!("unexpected record {:?}", other),
+            })
+            .collect();
+        let millis = start_time.timestamp_millis();
+        assert_eq!(records, vec![(millis, 0), (millis, 1), (millis + 2000, 2)]);
+    }
+}
@@ -0,0 +1,322 @@
+use std::sync::Arc;
+
+use super::progress::ProgressReporter;
+use super::{CancellationToken, ExportContext, ExportSummary, Exporter};
+use crate::augmentation::{total_rows, DataPoint};
+use crate::config::ConfigExporter;
+
+/// An adapter flushing the datapoints to the wrapped `Exporter` in batches of (at most)
+/// `batch_size` rows, with a final flush at the end; bounds the memory held for an exporter to
+/// a single batch while the datapoints stream through (see `generate_datapoints_iter`).
+///
+/// A datapoint with more rows than the room left in the batch is split across batches
+/// (same timestamp), hence no rows are dropped and every batch but the last one is full. In the
+/// `cumulative` mode (1 record per datapoint) the batches hold `batch_size` datapoints instead.
+///
+/// Every flush hands the batch to the wrapped exporter with its `batch_index` and `rows_before`
+/// in the `ExportContext`; without the pacing, the progress and the cancellation of the run (the
+/// datapoints are paced, counted and cut off while streaming in, see `export_stream`).
+pub struct BatchingSink<E: Exporter> {
+    inner: E,
+    batch_size: u64,
+    cancellation: Option<CancellationToken>,
+    progress: Option<Arc<ProgressReporter>>,
+}
+
+impl<E: Exporter> BatchingSink<E> {
+    pub fn new(inner: E, batch_size: u64) -> Self {
+        BatchingSink {
+            inner,
+            batch_size: batch_size.max(1),
+            cancellation: None,
+            progress: None,
+        }
+    }
+
+    /// Stop consuming the datapoints once `token` trips; the partial batch is still flushed.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Report the rows of every flush to `progress`.
+    pub fn with_progress(mut self, progress: Arc<ProgressReporter>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Stream the datapoints through the wrapped exporter batch by batch.
+    ///
+    /// On cancellation, the rows consumed so far are flushed and the summary is marked `cancelled`.
+    ///
+    /// # Errors
+    ///
+    /// The 1st failed flush; the rest of the datapoints are not consumed.
+    ///
+    pub fn export_iter<I>(
+        &self,
+        datapoints: I,
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String>
+    where
+        I: IntoIterator<Item = DataPoint>,
+    {
+        let mut summary = ExportSummary::default();
+        let mut batch: Vec<DataPoint> = vec![];
+        let mut batch_rows: u64 = 0;
+        let mut rows_before: u64 = 0;
+        for datapoint in datapoints {
+            if self
+                .cancellation
+                .as_ref()
+                .is_some_and(|token| token.is_cancelled())
+            {
+                summary.cancelled = true;
+                break;
+            }
+            if ctx.cumulative {
+                batch.push(datapoint);
+                batch_rows += 1;
+            } else {
+                let mut rows = (*datapoint.rows_to_add()).max(0) as u64;
+                while rows > 0 {
+                    let rows_taken = rows.min(self.batch_size - batch_rows);
+                    batch.push(datapoint.with_rows_to_add(rows_taken as i16));
+                    batch_rows += rows_taken;
+                    rows -= rows_taken;
+                    if batch_rows == self.batch_size {
+                        self.flush(&mut batch, &mut rows_before, cfg, ctx, &mut summary)?;
+                        batch_rows = 0;
+                    }
+                }
+            }
+            if batch_rows == self.batch_size {
+                self.flush(&mut batch, &mut rows_before, cfg, ctx, &mut summary)?;
+                batch_rows = 0;
+            }
+        }
+        if batch_rows > 0 {
+            self.flush(&mut batch, &mut rows_before, cfg, ctx, &mut summary)?;
+        }
+        Ok(summary)
+    }
+
+    fn flush(
+        &self,
+        batch: &mut Vec<DataPoint>,
+        rows_before: &mut u64,
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+        summary: &mut ExportSummary,
+    ) -> Result<(), String> {
+        let batch_ctx = ExportContext {
+            cancellation: CancellationToken::new(),
+            replay_speed: None,
+            max_export_rate: None,
+            progress: None,
+            batch_index: summary.batches,
+            rows_before: *rows_before,
+            ..ctx.clone()
+        };
+        let flushed = self.inner.export(batch, cfg, &batch_ctx).map_err(|e| {
+            format!(
+                "{} batch [{}] failed: {}",
+                self.inner.name(),
+                summary.batches,
+                e
+            )
+        })?;
+        *rows_before += total_rows(batch)?;
+        summary.rows_exported += flushed.rows_exported;
+        summary.batches += 1;
+        if let Some(progress) = self.progress.as_ref() {
+            progress.advance(flushed.rows_exported);
+        }
+        batch.clear();
+        Ok(())
+    }
+}
+
+impl<E: Exporter> Exporter for BatchingSink<E> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        self.export_iter(datapoints.iter().cloned(), cfg, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::augmentation::{generate_datapoints_iter, total_rows};
+    use crate::config::generation_test_config;
+    use crate::exporters::memory::MemoryExporter;
+    use crate::exporters::row_records;
+
+    /// records the rows of every flushed batch.
+    struct RecordingExporter {
+        batches: Mutex<Vec<u64>>,
+    }
+
+    impl Exporter for RecordingExporter {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn export(
+            &self,
+            datapoints: &[DataPoint],
+            _: &ConfigExporter,
+            _: &ExportContext,
+        ) -> Result<ExportSummary, String> {
+            let rows = total_rows(datapoints).unwrap();
+            self.batches.lock().unwrap().push(rows);
+            Ok(ExportSummary {
+                rows_exported: rows,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_batching_sink() {
+        let cfg = generation_test_config("even", 10000, "10m");
+        let exporter_cfg: ConfigExporter = toml::from_str("name = \"recording\"").unwrap();
+
+        for batch_size in [1000, 3000, 20000] {
+            let sink = BatchingSink::new(
+                RecordingExporter {
+                    batches: Mutex::new(vec![]),
+                },
+                batch_size,
+            );
+            let summary = sink
+                .export_iter(
+                    generate_datapoints_iter(&cfg).unwrap(),
+                    &exporter_cfg,
+                    &ExportContext::default(),
+                )
+                .unwrap();
+
+            let expected_flushes = 10000_u64.div_ceil(batch_size) as usize;
+            assert_eq!(summary.batches, expected_flushes);
+            assert_eq!(summary.rows_exported, 10000);
+            let batches = sink.inner.batches.lock().unwrap();
+            assert_eq!(batches.len(), expected_flushes);
+            assert_eq!(batches.iter().sum::<u64>(), 10000);
+            // full batches except the last one
+            assert!(batches[..batches.len() - 1]
+                .iter()
+                .all(|rows| *rows == batch_size));
+        }
+    }
+
+    /// records the batch (index and rows before) and the cumulative totals of every flush.
+    struct CumulativeExporter {
+        batches: Mutex<Vec<(usize, u64)>>,
+        totals: Mutex<Vec<u64>>,
+    }
+
+    impl Exporter for CumulativeExporter {
+        fn name(&self) -> &str {
+            "cumulative"
+        }
+
+        fn export(
+            &self,
+            datapoints: &[DataPoint],
+            cfg: &ConfigExporter,
+            ctx: &ExportContext,
+        ) -> Result<ExportSummary, String> {
+            self.batches
+                .lock()
+                .unwrap()
+                .push((ctx.batch_index, ctx.rows_before));
+            let mut totals = self.totals.lock().unwrap();
+            for (_, payload) in row_records(datapoints, cfg, ctx, None) {
+                let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+                totals.push(payload["value"].as_u64().unwrap());
+            }
+            Ok(ExportSummary {
+                rows_exported: datapoints.len() as u64,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_batching_sink_cumulative() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let exporter_cfg: ConfigExporter = toml::from_str("name = \"cumulative\"").unwrap();
+        let sink = BatchingSink::new(
+            CumulativeExporter {
+                batches: Mutex::new(vec![]),
+                totals: Mutex::new(vec![]),
+            },
+            2,
+        );
+        let ctx = ExportContext {
+            cumulative: true,
+            ..Default::default()
+        };
+
+        // 1 record per datapoint; batches of 2 datapoints whatever their rows
+        let datapoints = (0..5).map(|idx| DataPoint::new(start_time + Duration::seconds(idx), 10));
+        let summary = sink.export_iter(datapoints, &exporter_cfg, &ctx).unwrap();
+
+        assert_eq!(summary.batches, 3);
+        assert_eq!(summary.rows_exported, 5);
+        assert_eq!(
+            *sink.inner.batches.lock().unwrap(),
+            vec![(0, 0), (1, 20), (2, 40)]
+        );
+        // the running totals carry on across the batches
+        assert_eq!(*sink.inner.totals.lock().unwrap(), vec![10, 20, 30, 40, 50]);
+    }
+
+    #[test]
+    fn test_batching_sink_cancelled() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let exporter_cfg: ConfigExporter = toml::from_str("name = \"memory\"").unwrap();
+        let memory = MemoryExporter::new();
+        let token = CancellationToken::new();
+        let sink = BatchingSink::new(memory.clone(), 25).with_cancellation(token.clone());
+
+        // 10 rows per datapoint; the token trips (e.g. SIGINT) after the 7th datapoint
+        let consumed = Mutex::new(0_u64);
+        let datapoints = (0..100).map(|idx| {
+            if idx == 7 {
+                token.cancel();
+            }
+            *consumed.lock().unwrap() += 10;
+            DataPoint::new(start_time + Duration::seconds(idx), 10)
+        });
+        let summary = sink
+            .export_iter(datapoints, &exporter_cfg, &ExportContext::default())
+            .unwrap();
+
+        assert!(summary.cancelled);
+        // the 8th datapoint was pulled but not taken
+        assert_eq!(*consumed.lock().unwrap(), 80);
+        // 2 full batches of 25 + the partial batch of 20 flushed
+        assert_eq!(summary.rows_exported, 70);
+        assert_eq!(summary.batches, 3);
+        assert_eq!(memory.len(), 70);
+        assert_eq!(
+            memory.rows().last(),
+            Some(&(start_time + Duration::seconds(6)))
+        );
+    }
+}
@@ -0,0 +1,690 @@
+use std::thread;
+use std::time::Duration;
+
+use super::batching::BatchingSink;
+use super::{
+    batch_idempotency_key, export_stream, http, row_records, timestamp_format_of,
+    CancellationToken, ExportContext, ExportSummary, Exporter, ANOMALY_FIELD,
+};
+use crate::augmentation::{parse_time_duration, DataPoint};
+use crate::config::ConfigExporter;
+
+pub const CLICKHOUSE_EXPORTER: &str = "clickhouse";
+
+const DEFAULT_TABLE: &str = "otel_broccoli";
+const DEFAULT_BATCH_SIZE: usize = 10000;
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_RETRY_BACKOFF: &str = "500ms";
+// the doubling of the `retry_backoff` stops here.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+// the backoff sleeps in steps of this; the cancellation of the run is checked in between.
+const RETRY_SLEEP_STEP: Duration = Duration::from_millis(100);
+// the DateTime64 text format ClickHouse parses by default; overridable via `timestamp_format`.
+const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+/// The field enabling the CREATE TABLE IF NOT EXISTS (see `create_table_ddl`) before the inserts;
+/// off by default so a managed (production) schema is never touched.
+pub const AUTO_CREATE_TABLE_FIELD: &str = "auto_create_table";
+
+/// The header carrying the `batch_idempotency_key` of every insert; the same across the retries of
+/// a batch, so a deduplicating proxy (or sink) in front of ClickHouse can drop the duplicates.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Inserts the generated events (1 row per event) into ClickHouse through its HTTP interface.
+///
+/// The events stream through a `BatchingSink` and are inserted in batches of `batch_size` rows
+/// (1 batch held at a time); a failed batch insert is retried up to
+/// `max_retries` times with exponential backoff (`retry_backoff`, doubled on every retry up to
+/// 1 minute), so a briefly unavailable server does not lose the batch. Only the transient failures
+/// are retried (see `is_retryable_status`) and never past the cancellation of the run.
+///
+/// Every insert carries the batch's idempotency key (`IDEMPOTENCY_KEY_HEADER`).
+///
+/// With `auto_create_table = "true"` the table is created first if it does not exist yet.
+pub struct ClickhouseExporter;
+
+impl Exporter for ClickhouseExporter {
+    fn name(&self) -> &str {
+        CLICKHOUSE_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let url = field(cfg, "url").ok_or(format!(
+            "missing `url` field for the {} exporter",
+            CLICKHOUSE_EXPORTER
+        ))?;
+        let table = field(cfg, "table").unwrap_or(DEFAULT_TABLE);
+        let batch_size = parse_field(cfg, "batch_size", DEFAULT_BATCH_SIZE)?;
+        if batch_size == 0 {
+            return Err("batch_size must be positive, got [0]".to_string());
+        }
+        let max_retries = parse_field(cfg, "max_retries", DEFAULT_MAX_RETRIES)?;
+        let retry_backoff = retry_backoff(cfg)?;
+        let timestamp_format = timestamp_format_of(cfg).unwrap_or(DEFAULT_TIMESTAMP_FORMAT);
+        let auto_create_table = parse_field(cfg, AUTO_CREATE_TABLE_FIELD, false)?;
+
+        let insert_url = insert_url(url, table);
+        let mut headers = vec![("Content-Type", "application/x-ndjson")];
+        if let Some(user) = field(cfg, "user") {
+            headers.push(("X-ClickHouse-User", user));
+        }
+        if let Some(password) = field(cfg, "password") {
+            headers.push(("X-ClickHouse-Key", password));
+        }
+
+        if auto_create_table {
+            create_table(url, &headers, table, cfg, ctx)?;
+        }
+
+        let inserts = ClickhouseInserts {
+            url: insert_url,
+            headers,
+            timestamp_format,
+            max_retries,
+            retry_backoff,
+            // the batches get a fresh token of their own (see `BatchingSink`)
+            cancellation: ctx.cancellation.clone(),
+        };
+        BatchingSink::new(inserts, batch_size as u64)
+            .with_cancellation(ctx.cancellation.clone())
+            .export_iter(export_stream(datapoints, ctx).cloned(), cfg, ctx)
+    }
+}
+
+/// The inserts of the `ClickhouseExporter`; 1 insert per batch of its `BatchingSink`.
+struct ClickhouseInserts<'a> {
+    url: String,
+    headers: Vec<(&'a str, &'a str)>,
+    timestamp_format: &'a str,
+    max_retries: u32,
+    retry_backoff: Duration,
+    cancellation: CancellationToken,
+}
+
+impl Exporter for ClickhouseInserts<'_> {
+    fn name(&self) -> &str {
+        CLICKHOUSE_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let mut batch = String::new();
+        let mut rows_exported: u64 = 0;
+        for (_, payload) in row_records(datapoints, cfg, ctx, Some(self.timestamp_format)) {
+            batch.push_str(payload.as_str());
+            batch.push('\n');
+            rows_exported += 1;
+        }
+        insert_batch(
+            &self.url,
+            &self.headers,
+            &batch,
+            (
+                ctx.batch_index,
+                &batch_idempotency_key(cfg, ctx, ctx.batch_index),
+            ),
+            self.max_retries,
+            self.retry_backoff,
+            &self.cancellation,
+        )?;
+        Ok(ExportSummary {
+            rows_exported,
+            batches: 1,
+            ..Default::default()
+        })
+    }
+}
+
+/// The CREATE TABLE IF NOT EXISTS of the `table`; 1 column per exported field of the rows (see
+/// `row_records`): the `timestamp`, the `value` in the cumulative mode, the `row_fields`, then the
+/// static attributes.
+pub fn create_table_ddl(table: &str, cfg: &ConfigExporter, ctx: &ExportContext) -> String {
+    let mut columns = vec!["`timestamp` DateTime64(3)".to_string()];
+    if ctx.cumulative {
+        columns.push("`value` UInt64".to_string());
+    }
+    if cfg.flag_anomalies().unwrap_or(false) {
+        // empty for the rows outside of the anomalies
+        columns.push(format!("`{}` String", ANOMALY_FIELD));
+    }
+    let static_keys = ctx.static_attributes.iter().map(|(key, _)| key.as_str());
+    for key in ctx.row_fields().into_iter().chain(static_keys) {
+        columns.push(format!("{} String", quote_identifier(key)));
+    }
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} ({}) ENGINE = MergeTree ORDER BY timestamp",
+        quote_table(table),
+        columns.join(", ")
+    )
+}
+
+/// POST the `create_table_ddl`; not retried (a failure here is most likely a config issue).
+fn create_table(
+    url: &str,
+    headers: &[(&str, &str)],
+    table: &str,
+    cfg: &ConfigExporter,
+    ctx: &ExportContext,
+) -> Result<(), String> {
+    let ddl = create_table_ddl(table, cfg, ctx);
+    let failure = match http::post(
+        &format!("{}/", url.trim_end_matches('/')),
+        headers,
+        ddl.as_bytes(),
+    ) {
+        Ok(response) if response.is_success() => return Ok(()),
+        Ok(response) => format!("http status {}: {}", response.status, response.body.trim()),
+        Err(e) => e,
+    };
+    Err(format!(
+        "failed to create the {} table [{}] with {}",
+        CLICKHOUSE_EXPORTER, table, failure
+    ))
+}
+
+/// The url of the inserts into the `table`; the (quoted) table name is percent-encoded into the
+/// query.
+fn insert_url(url: &str, table: &str) -> String {
+    format!(
+        "{}/?query=INSERT%20INTO%20{}%20FORMAT%20JSONEachRow",
+        url.trim_end_matches('/'),
+        http::percent_encode(quote_table(table).as_str())
+    )
+}
+
+/// The `name` as a quoted identifier; the backticks within are doubled.
+fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// The (optionally database qualified, e.g. `db.events`) `table` as quoted identifiers.
+fn quote_table(table: &str) -> String {
+    table
+        .split('.')
+        .map(quote_identifier)
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// The backoff of the next retry; doubled, at most `MAX_RETRY_BACKOFF`.
+fn next_backoff(backoff: Duration) -> Duration {
+    backoff
+        .checked_mul(2)
+        .unwrap_or(MAX_RETRY_BACKOFF)
+        .min(MAX_RETRY_BACKOFF)
+}
+
+/// Whether a failed insert with the response `status` is worth retrying; a server side error (5xx)
+/// or a throttling (429). Any other status (e.g. a 400 of a bad schema) fails the same way again.
+fn is_retryable_status(status: u16) -> bool {
+    status >= 500 || status == 429
+}
+
+/// POST the batch (with its index and idempotency key); retried with exponential backoff on
+/// connection errors and retryable responses (see `is_retryable_status`), every attempt carrying
+/// the same key. The backoff is cut short once the `cancellation` trips.
+fn insert_batch(
+    url: &str,
+    headers: &[(&str, &str)],
+    batch: &str,
+    (batch_index, idempotency_key): (usize, &str),
+    max_retries: u32,
+    retry_backoff: Duration,
+    cancellation: &CancellationToken,
+) -> Result<(), String> {
+    // a bad url fails the same way on every attempt
+    http::validate_url(url)?;
+    let mut headers = headers.to_vec();
+    headers.push((IDEMPOTENCY_KEY_HEADER, idempotency_key));
+    let mut backoff = retry_backoff;
+    let mut attempt: u32 = 0;
+    loop {
+        let (failure, retryable) = match http::post(url, &headers, batch.as_bytes()) {
+            Ok(response) if response.is_success() => return Ok(()),
+            Ok(response) => (
+                format!("http status {}: {}", response.status, response.body.trim()),
+                is_retryable_status(response.status),
+            ),
+            // i.e. a connection (or exchange) error; the url is valid
+            Err(e) => (e, true),
+        };
+        if !retryable || attempt >= max_retries {
+            return Err(format!(
+                "gave up after {} attempt(s) with {}",
+                attempt + 1,
+                failure
+            ));
+        }
+        if cancellation.is_cancelled() {
+            return Err(format!(
+                "cancelled after {} attempt(s) with {}",
+                attempt + 1,
+                failure
+            ));
+        }
+        tracing::warn!(
+            "{} batch [{}] attempt {} failed with {}; retrying in {:?}",
+            CLICKHOUSE_EXPORTER,
+            batch_index,
+            attempt + 1,
+            failure,
+            backoff
+        );
+        let mut remaining = backoff;
+        while !remaining.is_zero() && !cancellation.is_cancelled() {
+            let step = remaining.min(RETRY_SLEEP_STEP);
+            thread::sleep(step);
+            remaining -= step;
+        }
+        if cancellation.is_cancelled() {
+            return Err(format!(
+                "cancelled after {} attempt(s) with {}",
+                attempt + 1,
+                failure
+            ));
+        }
+        backoff = next_backoff(backoff);
+        attempt += 1;
+    }
+}
+
+fn field<'a>(cfg: &'a ConfigExporter, name: &str) -> Option<&'a str> {
+    cfg.fields()
+        .as_ref()
+        .and_then(|fields| fields.get(name))
+        .map(|value| value.as_str())
+}
+
+fn parse_field<T: std::str::FromStr>(
+    cfg: &ConfigExporter,
+    name: &str,
+    default: T,
+) -> Result<T, String> {
+    match field(cfg, name) {
+        Some(value) => value
+            .trim()
+            .parse::<T>()
+            .map_err(|_| format!("invalid `{}` field [{}]", name, value)),
+        None => Ok(default),
+    }
+}
+
+fn retry_backoff(cfg: &ConfigExporter) -> Result<Duration, String> {
+    let value = field(cfg, "retry_backoff").unwrap_or(DEFAULT_RETRY_BACKOFF);
+    parse_time_duration(value.to_string())
+        .ok()
+        .and_then(|duration| duration.to_std().ok())
+        .filter(|duration| !duration.is_zero())
+        .ok_or(format!("invalid `retry_backoff` field [{}]", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::templates::RowTemplates;
+
+    /// read a full request (headers + Content-Length body); returns the body.
+    fn read_request_body(reader: &mut BufReader<std::net::TcpStream>) -> String {
+        read_request(reader).1
+    }
+
+    /// read a full request; returns the idempotency key (if any) and the body.
+    fn read_request(reader: &mut BufReader<std::net::TcpStream>) -> (Option<String>, String) {
+        let mut content_length = 0;
+        let mut idempotency_key = None;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Content-Length: ") {
+                content_length = value.trim().parse::<usize>().unwrap();
+            }
+            if let Some(value) = line.strip_prefix(format!("{}: ", IDEMPOTENCY_KEY_HEADER).as_str())
+            {
+                idempotency_key = Some(value.trim().to_string());
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        (idempotency_key, String::from_utf8(body).unwrap())
+    }
+
+    fn clickhouse_config(url: String) -> ConfigExporter {
+        let toml_str = format!(
+            r#"
+            name = "clickhouse"
+            enabled = true
+            [fields]
+            url = "{}"
+            user = "root"
+            password = "password"
+            batch_size = "3"
+            max_retries = "2"
+            retry_backoff = "10ms"
+            "#,
+            url
+        );
+        toml::from_str(toml_str.as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_export_with_retries() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        // fails twice then succeeds; returns the keys and the bodies of every request
+        let server = thread::spawn(move || {
+            let mut keys = vec![];
+            let mut bodies = vec![];
+            for attempt in 0..3 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let (key, body) = read_request(&mut reader);
+                keys.push(key.unwrap());
+                bodies.push(body);
+                let response: &[u8] = if attempt < 2 {
+                    b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 4\r\n\r\nbusy"
+                } else {
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"
+                };
+                reader.get_mut().write_all(response).unwrap();
+            }
+            (keys, bodies)
+        });
+
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 2),
+            DataPoint::new(start_time + Duration::seconds(1), 1),
+        ];
+        let cfg = clickhouse_config(url);
+        let ctx = ExportContext {
+            run_key: 42,
+            ..Default::default()
+        };
+        let summary = ClickhouseExporter.export(&datapoints, &cfg, &ctx).unwrap();
+        assert_eq!(summary.rows_exported, 3);
+
+        let (keys, bodies) = server.join().unwrap();
+        // the same batch (and key) was retried till it landed
+        assert_eq!(bodies.len(), 3);
+        assert!(bodies.iter().all(|body| *body == bodies[0]));
+        assert!(keys
+            .iter()
+            .all(|key| *key == batch_idempotency_key(&cfg, &ctx, 0)));
+        assert_eq!(
+            bodies[2],
+            "{\"timestamp\":\"2022-01-01 00:00:00.000\"}\n\
+             {\"timestamp\":\"2022-01-01 00:00:00.000\"}\n\
+             {\"timestamp\":\"2022-01-01 00:00:01.000\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_url() {
+        assert_eq!(
+            insert_url("http://localhost:8123/", "db.otel_broccoli"),
+            "http://localhost:8123/?query=INSERT%20INTO%20%60db%60.%60otel_broccoli%60%20FORMAT%20JSONEachRow"
+        );
+        // never breaks out of the query
+        assert_eq!(
+            insert_url("http://localhost:8123", "t FORMAT CSV&x=1"),
+            "http://localhost:8123/?query=INSERT%20INTO%20%60t%20FORMAT%20CSV%26x%3D1%60%20FORMAT%20JSONEachRow"
+        );
+    }
+
+    #[test]
+    fn test_next_backoff() {
+        assert_eq!(
+            next_backoff(std::time::Duration::from_millis(500)),
+            std::time::Duration::from_secs(1)
+        );
+        assert_eq!(
+            next_backoff(std::time::Duration::from_secs(40)),
+            MAX_RETRY_BACKOFF
+        );
+        // no overflow
+        assert_eq!(next_backoff(std::time::Duration::MAX), MAX_RETRY_BACKOFF);
+    }
+
+    #[test]
+    fn test_create_table_ddl() {
+        let cfg = clickhouse_config("http://localhost:8123".to_string());
+        let mut ctx = ExportContext::default();
+        assert_eq!(
+            create_table_ddl("otel_broccoli", &cfg, &ctx),
+            "CREATE TABLE IF NOT EXISTS `otel_broccoli` (`timestamp` DateTime64(3)) \
+            ENGINE = MergeTree ORDER BY timestamp"
+        );
+        // the backticks within are doubled; a database qualified table is quoted per part
+        ctx.static_attributes = vec![("odd`key".to_string(), "x".to_string())];
+        assert_eq!(
+            create_table_ddl("db.odd`table", &cfg, &ctx),
+            "CREATE TABLE IF NOT EXISTS `db`.`odd``table` (`timestamp` DateTime64(3), \
+            `odd``key` String) ENGINE = MergeTree ORDER BY timestamp"
+        );
+
+        // the configured columns; in the order of the row payloads
+        ctx.cumulative = true;
+        ctx.static_attributes = vec![
+            ("dataset".to_string(), "loadtest-2024".to_string()),
+            ("service.name".to_string(), "cart".to_string()),
+        ];
+        assert_eq!(
+            create_table_ddl("loadtest", &cfg, &ctx),
+            "CREATE TABLE IF NOT EXISTS `loadtest` (`timestamp` DateTime64(3), `value` UInt64, \
+            `dataset` String, `service.name` String) ENGINE = MergeTree ORDER BY timestamp"
+        );
+
+        // the attributes ahead of the static attributes; none for the slots
+        ctx.attributes = HashMap::from([
+            ("region".to_string(), vec!["us-east-1".to_string()]),
+            ("host".to_string(), vec!["host-1".to_string()]),
+            ("unused".to_string(), vec![]),
+        ]);
+        ctx.static_attributes.truncate(1);
+        assert_eq!(
+            create_table_ddl("loadtest", &cfg, &ctx),
+            "CREATE TABLE IF NOT EXISTS `loadtest` (`timestamp` DateTime64(3), `value` UInt64, \
+            `dataset` String) ENGINE = MergeTree ORDER BY timestamp"
+        );
+        ctx.templates = RowTemplates::new(
+            &HashMap::from([("message".to_string(), "GET".to_string())]),
+            &HashMap::new(),
+        );
+        ctx.cumulative = false;
+        assert_eq!(
+            create_table_ddl("loadtest", &cfg, &ctx),
+            "CREATE TABLE IF NOT EXISTS `loadtest` (`timestamp` DateTime64(3), `host` String, \
+            `region` String, `message` String, `dataset` String) ENGINE = MergeTree ORDER BY timestamp"
+        );
+    }
+
+    #[test]
+    fn test_export_auto_create_table() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        // the DDL then the insert; returns the bodies of every request
+        let server = thread::spawn(move || {
+            let mut bodies = vec![];
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                bodies.push(read_request_body(&mut reader));
+                reader
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                    .unwrap();
+            }
+            bodies
+        });
+
+        let mut cfg = clickhouse_config(url);
+        let mut fields = cfg.fields().clone().unwrap();
+        fields.insert(AUTO_CREATE_TABLE_FIELD.to_string(), "true".to_string());
+        cfg.set_fields(Some(fields));
+        let ctx = ExportContext {
+            static_attributes: vec![("dataset".to_string(), "loadtest".to_string())],
+            ..Default::default()
+        };
+
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![DataPoint::new(start_time, 1)];
+        ClickhouseExporter.export(&datapoints, &cfg, &ctx).unwrap();
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies[0], create_table_ddl(DEFAULT_TABLE, &cfg, &ctx));
+        assert_eq!(
+            bodies[1],
+            "{\"timestamp\":\"2022-01-01 00:00:00.000\",\"dataset\":\"loadtest\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_export_retries_exhausted() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        // 1st batch lands, the 2nd keeps failing
+        let server = thread::spawn(move || {
+            for attempt in 0..4 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                read_request_body(&mut reader);
+                let response: &[u8] = if attempt == 0 {
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"
+                } else {
+                    b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 4\r\n\r\ndown"
+                };
+                reader.get_mut().write_all(response).unwrap();
+            }
+        });
+
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![DataPoint::new(start_time, 5)];
+        let result = ClickhouseExporter.export(
+            &datapoints,
+            &clickhouse_config(url),
+            &ExportContext::default(),
+        );
+        server.join().unwrap();
+        assert_eq!(
+            result.err().unwrap(),
+            "clickhouse batch [1] failed: gave up after 3 attempt(s) with http status 500: down"
+        );
+    }
+
+    #[test]
+    fn test_export_retries_only_transient_failures() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        // throttled once, then a bad request; the 400 is never retried
+        let server = thread::spawn(move || {
+            for attempt in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                read_request_body(&mut reader);
+                let response: &[u8] = if attempt == 0 {
+                    b"HTTP/1.1 429 Too Many Requests\r\nContent-Length: 4\r\n\r\nslow"
+                } else {
+                    b"HTTP/1.1 400 Bad Request\r\nContent-Length: 3\r\n\r\nbad"
+                };
+                reader.get_mut().write_all(response).unwrap();
+            }
+            // no 3rd attempt
+            listener.set_nonblocking(true).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            listener.accept().is_err()
+        });
+
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![DataPoint::new(start_time, 1)];
+        let result = ClickhouseExporter.export(
+            &datapoints,
+            &clickhouse_config(url),
+            &ExportContext::default(),
+        );
+        assert!(server.join().unwrap());
+        assert_eq!(
+            result.err().unwrap(),
+            "clickhouse batch [0] failed: gave up after 2 attempt(s) with http status 400: bad"
+        );
+
+        assert!(is_retryable_status(503));
+        assert!(is_retryable_status(429));
+        assert!(!is_retryable_status(404));
+        // a bad url is not retried either
+        assert_eq!(
+            insert_batch(
+                "https://localhost:8123",
+                &[],
+                "",
+                (0, "key"),
+                2,
+                std::time::Duration::from_secs(60),
+                &CancellationToken::new(),
+            )
+            .err()
+            .unwrap(),
+            "only http:// urls are supported, got [https://localhost:8123]"
+        );
+    }
+
+    #[test]
+    fn test_export_retry_cancelled() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let cancellation = CancellationToken::new();
+
+        // unavailable; Ctrl-C while backing off
+        let token = cancellation.clone();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            read_request_body(&mut reader);
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 4\r\n\r\nbusy")
+                .unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            token.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let result = insert_batch(
+            &url,
+            &[],
+            "{}\n",
+            (0, "key"),
+            3,
+            std::time::Duration::from_secs(30),
+            &cancellation,
+        );
+        server.join().unwrap();
+        // never waited out the 30s backoff
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        assert_eq!(
+            result.err().unwrap(),
+            "cancelled after 1 attempt(s) with http status 503: busy"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::{row_records, timestamp_format_of, ExportContext, ExportSummary, Exporter};
+use crate::augmentation::DataPoint;
+use crate::config::ConfigExporter;
+
+pub const FILE_EXPORTER: &str = "file";
+
+/// The field deciding whether an existing output file is appended to (`"true"`) or truncated
+/// (`"false"`, the default).
+pub const APPEND_FIELD: &str = "append";
+
+/// The output file `filename` under the `path` folder; joined by `Path::join` (with or without a
+/// trailing slash on `path`) and normalized (no `.` components or repeated separators).
+pub fn output_file_path(path: &str, filename: &str) -> PathBuf {
+    Path::new(path).join(filename).components().collect()
+}
+
+/// Writes the generated rows (1 line per row) into the `filename` under the `path` folder.
+pub struct FileExporter;
+
+impl Exporter for FileExporter {
+    fn name(&self) -> &str {
+        FILE_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let fields = cfg.fields().clone().unwrap_or_default();
+        let (path, filename) = match (fields.get("path"), fields.get("filename")) {
+            (Some(path), Some(filename)) => (path, filename),
+            _ => {
+                return Err(format!(
+                    "missing `path` / `filename` fields for the {} exporter",
+                    FILE_EXPORTER
+                ))
+            }
+        };
+        let file_path = output_file_path(path, filename);
+        let write_error =
+            |e: std::io::Error| format!("failed to write to [{}]: {}", file_path.display(), e);
+
+        let append = match fields.get(APPEND_FIELD).map(|value| value.trim()) {
+            Some(value) => value
+                .parse::<bool>()
+                .map_err(|_| format!("invalid `{}` field [{}]", APPEND_FIELD, value))?,
+            None => false,
+        };
+
+        std::fs::create_dir_all(path).map_err(write_error)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&file_path)
+            .map_err(write_error)?;
+        let mut out = BufWriter::new(file);
+        let timestamp_format = timestamp_format_of(cfg);
+        let mut rows_exported: u64 = 0;
+        for (_, payload) in row_records(datapoints, cfg, ctx, timestamp_format) {
+            writeln!(out, "{}", payload).map_err(write_error)?;
+            rows_exported += 1;
+        }
+        out.flush().map_err(write_error)?;
+        Ok(ExportSummary {
+            rows_exported,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+
+    #[test]
+    fn test_export_file() {
+        let folder = tempfile::tempdir().unwrap();
+        let path = folder.path().join("generated");
+        let cfg: ConfigExporter = toml::from_str(
+            format!(
+                r#"
+                name = "file"
+                enabled = true
+                [fields]
+                path = "{}"
+                filename = "log.log"
+                "#,
+                path.display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let summary = FileExporter
+            .export(
+                &[DataPoint::new(start_time, 2)],
+                &cfg,
+                &ExportContext::default(),
+            )
+            .unwrap();
+        assert_eq!(summary.rows_exported, 2);
+        assert_eq!(
+            std::fs::read_to_string(path.join("log.log")).unwrap(),
+            "{\"timestamp\":\"2022-01-01T00:00:00.000000000Z\"}\n\
+             {\"timestamp\":\"2022-01-01T00:00:00.000000000Z\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_export_file_append() {
+        let folder = tempfile::tempdir().unwrap();
+        let path = folder.path().join("generated");
+        let cfg_with_append = |append: &str| -> ConfigExporter {
+            toml::from_str(
+                format!(
+                    r#"
+                    name = "file"
+                    enabled = true
+                    [fields]
+                    path = "{}"
+                    filename = "log.log"
+                    append = "{}"
+                    "#,
+                    path.display(),
+                    append
+                )
+                .as_str(),
+            )
+            .unwrap()
+        };
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let second_time: DateTime<Utc> = "2022-01-01T00:00:01.000Z".parse().unwrap();
+
+        // both runs' rows are kept
+        let cfg = cfg_with_append("true");
+        FileExporter
+            .export(
+                &[DataPoint::new(start_time, 1)],
+                &cfg,
+                &ExportContext::default(),
+            )
+            .unwrap();
+        FileExporter
+            .export(
+                &[DataPoint::new(second_time, 1)],
+                &cfg,
+                &ExportContext::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path.join("log.log")).unwrap(),
+            "{\"timestamp\":\"2022-01-01T00:00:00.000000000Z\"}\n\
+             {\"timestamp\":\"2022-01-01T00:00:01.000000000Z\"}\n"
+        );
+
+        // truncated
+        FileExporter
+            .export(
+                &[DataPoint::new(second_time, 1)],
+                &cfg_with_append("false"),
+                &ExportContext::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path.join("log.log")).unwrap(),
+            "{\"timestamp\":\"2022-01-01T00:00:01.000000000Z\"}\n"
+        );
+
+        assert_eq!(
+            FileExporter
+                .export(
+                    &[DataPoint::new(second_time, 1)],
+                    &cfg_with_append("yes"),
+                    &ExportContext::default()
+                )
+                .err()
+                .unwrap(),
+            "invalid `append` field [yes]"
+        );
+    }
+
+    #[test]
+    fn test_output_file_path() {
+        assert_eq!(
+            output_file_path("./generated/", "log.log"),
+            PathBuf::from("./generated/log.log")
+        );
+        assert_eq!(
+            output_file_path("./generated", "log.log"),
+            PathBuf::from("./generated/log.log")
+        );
+        assert_eq!(
+            output_file_path("generated//nested/./", "log.log"),
+            PathBuf::from("generated/nested/log.log")
+        );
+
+        // with or without the trailing slash; the rows land in the same file
+        let folder = tempfile::tempdir().unwrap();
+        let path = folder.path().join("generated");
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        for path in [format!("{}/", path.display()), path.display().to_string()] {
+            let cfg: ConfigExporter = toml::from_str(
+                format!(
+                    r#"
+                    name = "file"
+                    enabled = true
+                    [fields]
+                    path = "{}"
+                    filename = "log.log"
+                    append = "true"
+                    "#,
+                    path
+                )
+                .as_str(),
+            )
+            .unwrap();
+            FileExporter
+                .export(
+                    &[DataPoint::new(start_time, 1)],
+                    &cfg,
+                    &ExportContext::default(),
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            std::fs::read_to_string(path.join("log.log"))
+                .unwrap()
+                .lines()
+                .count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const DEFAULT_HTTP_TIMEOUT_IN_SECONDS: u64 = 30;
+
+/// The status and body of an HTTP response.
+#[derive(Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// A minimal HTTP/1.1 POST for the exporters pushing to an endpoint.
+///
+/// Only plain `http://` urls are supported; enough for local collectors and test servers.
+/// The connection is closed after the response, hence the response body is simply read till EOF.
+///
+/// # Errors
+///
+/// If the url is not a valid `http://` url, or the connection / exchange fails.
+///
+pub fn post(url: &str, headers: &[(&str, &str)], body: &[u8]) -> Result<HttpResponse, String> {
+    let (host, path) = split_http_url(url)?;
+    let mut stream = TcpStream::connect(host.as_str())
+        .map_err(|e| format!("failed to connect to [{}]: {}", url, e))?;
+    let timeout = Some(Duration::from_secs(DEFAULT_HTTP_TIMEOUT_IN_SECONDS));
+    stream
+        .set_read_timeout(timeout)
+        .and_then(|_| stream.set_write_timeout(timeout))
+        .map_err(|e| format!("failed to set timeout for [{}]: {}", url, e))?;
+
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        path,
+        host,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(format!("{}: {}\r\n", name, value).as_str());
+    }
+    request.push_str("\r\n");
+
+    stream
+        .write_all(request.as_bytes())
+        .and_then(|_| stream.write_all(body))
+        .map_err(|e| format!("failed to send request to [{}]: {}", url, e))?;
+
+    let mut raw_response = vec![];
+    stream
+        .read_to_end(&mut raw_response)
+        .map_err(|e| format!("failed to read response from [{}]: {}", url, e))?;
+    parse_http_response(&raw_response)
+}
+
+/// Check the `url` the same way `post` does; i.e. whether it is a valid `http://` url.
+///
+/// # Errors
+///
+/// If the url is not a valid `http://` url.
+///
+pub fn validate_url(url: &str) -> Result<(), String> {
+    split_http_url(url).map(|_| ())
+}
+
+/// Percent-encode the `value` for a url query (every byte but the unreserved `A-Z a-z 0-9 - . _ ~`).
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(format!("%{:02X}", byte).as_str());
+        }
+    }
+    encoded
+}
+
+/// split the url into (host:port, path).
+fn split_http_url(url: &str) -> Result<(String, String), String> {
+    let without_scheme = url
+        .strip_prefix("http://")
+        .ok_or(format!("only http:// urls are supported, got [{}]", url))?;
+    let (authority, path) = match without_scheme.find('/') {
+        Some(idx) => without_scheme.split_at(idx),
+        None => (without_scheme, "/"),
+    };
+    if authority.is_empty() {
+        return Err(format!("missing host in url [{}]", url));
+    }
+    let host = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    Ok((host, path.to_string()))
+}
+
+fn parse_http_response(raw_response: &[u8]) -> Result<HttpResponse, String> {
+    let response = String::from_utf8_lossy(raw_response);
+    let status_line = response.lines().next().unwrap_or_default();
+    // e.g. HTTP/1.1 200 OK
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or(format!("invalid http status line [{}]", status_line))?;
+    let body = match response.find("\r\n\r\n") {
+        Some(idx) => response[idx + 4..].to_string(),
+        None => String::new(),
+    };
+    Ok(HttpResponse { status, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_post() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/v1/write", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // the head and the body could arrive in separate reads
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"hello") {
+                let size = stream.read(&mut buffer).unwrap();
+                if size == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..size]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 2\r\n\r\nok")
+                .unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        let response = post(url.as_str(), &[("Content-Type", "text/plain")], b"hello").unwrap();
+        assert_eq!(response.status, 202);
+        assert!(response.is_success());
+        assert_eq!(response.body, "ok");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /api/v1/write HTTP/1.1\r\n"));
+        assert!(request.contains("Content-Type: text/plain\r\n"));
+        assert!(request.ends_with("\r\n\r\nhello"));
+
+        assert_eq!(
+            post("https://localhost/", &[], b"").err().unwrap(),
+            "only http:// urls are supported, got [https://localhost/]"
+        );
+    }
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(percent_encode("db.otel_broccoli-1~"), "db.otel_broccoli-1~");
+        assert_eq!(
+            percent_encode("t FORMAT CSV&x=1#"),
+            "t%20FORMAT%20CSV%26x%3D1%23"
+        );
+        assert_eq!(percent_encode("événement"), "%C3%A9v%C3%A9nement");
+    }
+}
@@ -0,0 +1,377 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rdkafka::config::ClientConfig;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::DeliveryResult;
+use rdkafka::producer::{BaseProducer, BaseRecord, Producer, ProducerContext};
+use rdkafka::ClientContext;
+
+use super::{
+    batch_idempotency_key, format_timestamp, row_records, timestamp_format_of, ExportContext,
+    ExportSummary, Exporter,
+};
+use crate::augmentation::DataPoint;
+use crate::config::ConfigExporter;
+
+pub const KAFKA_EXPORTER: &str = "kafka";
+
+const FLUSH_TIMEOUT_IN_SECONDS: u64 = 30;
+const QUEUE_FULL_BACKOFF_IN_MILLIS: u64 = 100;
+
+/// The `key_field` keying every message by its idempotency key; each message is a batch of 1 row
+/// (see `batch_idempotency_key`), hence a re-sent message keeps its key.
+pub const IDEMPOTENCY_KEY_FIELD: &str = "idempotency_key";
+
+/// The producing side of the kafka exporter; abstracted so the exporter is testable without a broker.
+pub trait KafkaProducer {
+    /// Enqueue a message; an error means the message could not even be enqueued.
+    fn send(
+        &self,
+        topic: &str,
+        key: Option<&str>,
+        payload: &str,
+        timestamp_millis: i64,
+    ) -> Result<(), String>;
+
+    /// Wait for the outstanding messages; returns the number of messages failed to be delivered.
+    fn flush(&self) -> Result<usize, String>;
+}
+
+/// Publishes the generated rows to kafka; 1 message per row with the row's timestamp as the
+/// message timestamp.
+///
+/// Reads `brokers`, `topic` and optional `key_field` (the payload field used as the message key,
+/// only `timestamp` is available; or `idempotency_key`) from the exporter `fields`.
+pub struct KafkaExporter;
+
+impl Exporter for KafkaExporter {
+    fn name(&self) -> &str {
+        KAFKA_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let brokers = field(cfg, "brokers")?;
+        let producer = RdKafkaProducer::new(brokers)?;
+        self.export_with(&producer, datapoints, cfg, ctx)
+    }
+}
+
+impl KafkaExporter {
+    /// # Errors
+    ///
+    /// If the fields are invalid, or any message fails to be sent / delivered; the count of
+    /// failed messages is reported.
+    ///
+    pub fn export_with(
+        &self,
+        producer: &dyn KafkaProducer,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let topic = field(cfg, "topic")?;
+        let key_field = cfg
+            .fields()
+            .as_ref()
+            .and_then(|fields| fields.get("key_field"));
+        if let Some(key_field) = key_field {
+            if key_field != "timestamp" && key_field != IDEMPOTENCY_KEY_FIELD {
+                return Err(format!(
+                    "unsupported key_field [{}] for the {} exporter",
+                    key_field, KAFKA_EXPORTER
+                ));
+            }
+        }
+
+        let timestamp_format = timestamp_format_of(cfg);
+        let mut sent: usize = 0;
+        let mut failed: usize = 0;
+        let mut first_error: Option<String> = None;
+        for (index, (timestamp, payload)) in
+            row_records(datapoints, cfg, ctx, timestamp_format).enumerate()
+        {
+            let key = key_field.map(|key_field| match key_field.as_str() {
+                IDEMPOTENCY_KEY_FIELD => batch_idempotency_key(cfg, ctx, index),
+                _ => format_timestamp(&timestamp.with_timezone(&ctx.timezone), timestamp_format),
+            });
+            let key = key.as_deref();
+            sent += 1;
+            if let Err(e) = producer.send(topic, key, &payload, timestamp.timestamp_millis()) {
+                failed += 1;
+                first_error.get_or_insert(e);
+            }
+        }
+        match producer.flush() {
+            Ok(failed_deliveries) => failed += failed_deliveries,
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        if failed > 0 || first_error.is_some() {
+            return Err(format!(
+                "{} of {} message(s) failed to be sent to the kafka topic [{}]: {}",
+                failed,
+                sent,
+                topic,
+                first_error.unwrap_or("delivery failed".to_string())
+            ));
+        }
+        Ok(ExportSummary {
+            rows_exported: sent as u64,
+            ..Default::default()
+        })
+    }
+}
+
+fn field<'a>(cfg: &'a ConfigExporter, name: &str) -> Result<&'a str, String> {
+    cfg.fields()
+        .as_ref()
+        .and_then(|fields| fields.get(name))
+        .map(|value| value.as_str())
+        .ok_or(format!(
+            "missing `{}` field for the {} exporter",
+            name, KAFKA_EXPORTER
+        ))
+}
+
+/// counts the failed deliveries (reported asynchronously by librdkafka).
+#[derive(Default)]
+struct DeliveryContext {
+    failed: AtomicUsize,
+    first_error: Mutex<Option<String>>,
+}
+
+impl ClientContext for DeliveryContext {}
+
+impl ProducerContext for DeliveryContext {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, delivery_result: &DeliveryResult<'_>, _: Self::DeliveryOpaque) {
+        if let Err((e, _)) = delivery_result {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+            self.first_error
+                .lock()
+                .unwrap()
+                .get_or_insert(e.to_string());
+        }
+    }
+}
+
+/// The rdkafka backed `KafkaProducer`.
+pub struct RdKafkaProducer {
+    producer: BaseProducer<DeliveryContext>,
+}
+
+impl RdKafkaProducer {
+    pub fn new(brokers: &str) -> Result<Self, String> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create_with_context(DeliveryContext::default())
+            .map_err(|e| format!("failed to create the kafka producer: {}", e))?;
+        Ok(RdKafkaProducer { producer })
+    }
+}
+
+impl KafkaProducer for RdKafkaProducer {
+    fn send(
+        &self,
+        topic: &str,
+        key: Option<&str>,
+        payload: &str,
+        timestamp_millis: i64,
+    ) -> Result<(), String> {
+        let mut record = BaseRecord::to(topic)
+            .payload(payload)
+            .timestamp(timestamp_millis);
+        if let Some(key) = key {
+            record = record.key(key);
+        }
+        loop {
+            match self.producer.send(record) {
+                Ok(_) => break,
+                // wait for the queue to drain a bit then retry
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), returned)) => {
+                    self.producer
+                        .poll(Duration::from_millis(QUEUE_FULL_BACKOFF_IN_MILLIS));
+                    record = returned;
+                }
+                Err((e, _)) => return Err(e.to_string()),
+            }
+        }
+        // serve the delivery callbacks
+        self.producer.poll(Duration::ZERO);
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<usize, String> {
+        self.producer
+            .flush(Duration::from_secs(FLUSH_TIMEOUT_IN_SECONDS))
+            .map_err(|e| format!("failed to flush the kafka producer: {}", e))?;
+        let context = self.producer.context();
+        if let Some(e) = context.first_error.lock().unwrap().as_ref() {
+            tracing::warn!("kafka delivery failed: {}", e);
+        }
+        Ok(context.failed.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+
+    /// (topic, key, payload, timestamp in milliseconds)
+    type Message = (String, Option<String>, String, i64);
+
+    /// records the messages; the `fail_every`-th message fails to be delivered.
+    struct MockProducer {
+        messages: RefCell<Vec<Message>>,
+        fail_every: usize,
+    }
+
+    impl KafkaProducer for MockProducer {
+        fn send(
+            &self,
+            topic: &str,
+            key: Option<&str>,
+            payload: &str,
+            timestamp_millis: i64,
+        ) -> Result<(), String> {
+            self.messages.borrow_mut().push((
+                topic.to_string(),
+                key.map(|key| key.to_string()),
+                payload.to_string(),
+                timestamp_millis,
+            ));
+            Ok(())
+        }
+
+        fn flush(&self) -> Result<usize, String> {
+            if self.fail_every == 0 {
+                return Ok(0);
+            }
+            Ok(self.messages.borrow().len() / self.fail_every)
+        }
+    }
+
+    fn kafka_config(key_field: Option<&str>) -> ConfigExporter {
+        let toml_str = format!(
+            r#"
+            name = "kafka"
+            enabled = true
+            [fields]
+            brokers = "localhost:9092"
+            topic = "broccoli"
+            {}
+            "#,
+            key_field
+                .map(|key_field| format!("key_field = \"{}\"", key_field))
+                .unwrap_or_default()
+        );
+        toml::from_str(toml_str.as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_export_with_mock_producer() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 2),
+            DataPoint::new(start_time + Duration::seconds(1), 1),
+        ];
+
+        let producer = MockProducer {
+            messages: RefCell::new(vec![]),
+            fail_every: 0,
+        };
+        KafkaExporter
+            .export_with(
+                &producer,
+                &datapoints,
+                &kafka_config(Some("timestamp")),
+                &ExportContext::default(),
+            )
+            .unwrap();
+        let messages = producer.messages.borrow();
+        // 1 message per row
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[2],
+            (
+                "broccoli".to_string(),
+                Some("2022-01-01T00:00:01.000000000Z".to_string()),
+                "{\"timestamp\":\"2022-01-01T00:00:01.000000000Z\"}".to_string(),
+                (start_time + Duration::seconds(1)).timestamp_millis()
+            )
+        );
+
+        // failed deliveries are counted
+        let producer = MockProducer {
+            messages: RefCell::new(vec![]),
+            fail_every: 3,
+        };
+        assert_eq!(
+            KafkaExporter
+                .export_with(
+                    &producer,
+                    &datapoints,
+                    &kafka_config(None),
+                    &ExportContext::default(),
+                )
+                .err()
+                .unwrap(),
+            "1 of 3 message(s) failed to be sent to the kafka topic [broccoli]: delivery failed"
+        );
+        assert!(producer.messages.borrow().iter().all(|m| m.1.is_none()));
+
+        // keyed by the idempotency key; the same across attempts
+        let cfg = kafka_config(Some(IDEMPOTENCY_KEY_FIELD));
+        let ctx = ExportContext {
+            run_key: 42,
+            ..Default::default()
+        };
+        let keys = || {
+            let producer = MockProducer {
+                messages: RefCell::new(vec![]),
+                fail_every: 0,
+            };
+            KafkaExporter
+                .export_with(&producer, &datapoints, &cfg, &ctx)
+                .unwrap();
+            let keys: Vec<String> = producer
+                .messages
+                .borrow()
+                .iter()
+                .map(|m| m.1.clone().unwrap())
+                .collect();
+            keys
+        };
+        let first_attempt = keys();
+        assert_eq!(first_attempt, keys());
+        assert_eq!(first_attempt[2], batch_idempotency_key(&cfg, &ctx, 2));
+        assert_ne!(first_attempt[0], first_attempt[1]);
+
+        assert_eq!(
+            KafkaExporter
+                .export_with(
+                    &producer,
+                    &datapoints,
+                    &kafka_config(Some("id")),
+                    &ExportContext::default(),
+                )
+                .err()
+                .unwrap(),
+            "unsupported key_field [id] for the kafka exporter"
+        );
+    }
+}
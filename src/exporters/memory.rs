@@ -0,0 +1,97 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+use super::{ExportContext, ExportSummary, Exporter};
+use crate::augmentation::{expand_datapoints_iter, DataPoint};
+use crate::config::ConfigExporter;
+
+pub const MEMORY_EXPORTER: &str = "memory";
+
+/// Keeps every received row (its timestamp) in memory; handy for asserting on the export
+/// behaviour (routing, batching, concurrency) without touching the disk or the network.
+///
+/// Clones share the same rows, hence a clone handed to the exporters can be inspected through
+/// the original after the export.
+#[derive(Clone, Default)]
+pub struct MemoryExporter {
+    rows: Arc<Mutex<Vec<DateTime<Utc>>>>,
+}
+
+impl MemoryExporter {
+    pub fn new() -> Self {
+        MemoryExporter::default()
+    }
+
+    /// A snapshot of the rows received so far, in the order received.
+    pub fn rows(&self) -> Vec<DateTime<Utc>> {
+        self.rows.lock().unwrap().clone()
+    }
+
+    /// The number of rows received so far.
+    pub fn len(&self) -> usize {
+        self.rows.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Exporter for MemoryExporter {
+    fn name(&self) -> &str {
+        MEMORY_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        _cfg: &ConfigExporter,
+        _ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let mut rows = self
+            .rows
+            .lock()
+            .map_err(|e| format!("the {} exporter is poisoned: {}", MEMORY_EXPORTER, e))?;
+        let rows_before = rows.len();
+        rows.extend(expand_datapoints_iter(datapoints));
+        Ok(ExportSummary {
+            rows_exported: (rows.len() - rows_before) as u64,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_export_memory() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 2),
+            DataPoint::new(start_time + Duration::seconds(1), 1),
+        ];
+        let cfg: ConfigExporter = toml::from_str("name = \"memory\"").unwrap();
+        let ctx = ExportContext::default();
+
+        let exporter = MemoryExporter::new();
+        assert!(exporter.is_empty());
+        // a clone shares the same rows
+        let summary = exporter.clone().export(&datapoints, &cfg, &ctx).unwrap();
+        assert_eq!(summary.rows_exported, 3);
+        exporter.export(&datapoints[1..], &cfg, &ctx).unwrap();
+        assert_eq!(
+            exporter.rows(),
+            vec![
+                start_time,
+                start_time,
+                start_time + Duration::seconds(1),
+                start_time + Duration::seconds(1)
+            ]
+        );
+    }
+}
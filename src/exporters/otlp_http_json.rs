@@ -0,0 +1,374 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use super::batching::BatchingSink;
+use super::trace_ids::{spans_per_trace_of, TraceIdGenerator};
+use super::{
+    export_stream, http, row_records, timestamp_format_of, ExportContext, ExportSummary, Exporter,
+    Signal, SIGNAL_FIELD,
+};
+use crate::augmentation::DataPoint;
+use crate::config::ConfigExporter;
+
+pub const OTLP_HTTP_JSON_EXPORTER: &str = "otlp_http_json";
+
+/// The field of the extra request headers; comma separated `name=value` pairs,
+/// e.g. `"Authorization=Bearer abc,X-Scope-OrgID=tenant1"`.
+pub const HEADERS_FIELD: &str = "headers";
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+// the service / instrumentation scope name of the exported records.
+const SCOPE_NAME: &str = "otel_broccoli";
+// SPAN_KIND_INTERNAL
+const SPAN_KIND: u8 = 1;
+
+/// POSTs the generated rows as OTLP/HTTP JSON requests to the `endpoint` field (e.g.
+/// `http://localhost:4318/v1/logs`); an `ExportLogsServiceRequest` with 1 log record per row, or
+/// with the `signal = "traces"` field an `ExportTraceServiceRequest` with 1 span per row (see
+/// `TraceIdGenerator` for the IDs).
+///
+/// The rows stream through a `BatchingSink`; every request carries up to `batch_size` records; readable as is, hence handy for debugging
+/// against a collector.
+pub struct OtlpHttpJsonExporter;
+
+impl Exporter for OtlpHttpJsonExporter {
+    fn name(&self) -> &str {
+        OTLP_HTTP_JSON_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let fields = cfg.fields().clone().unwrap_or_default();
+        let endpoint = fields.get("endpoint").ok_or(format!(
+            "missing `endpoint` field for the {} exporter",
+            OTLP_HTTP_JSON_EXPORTER
+        ))?;
+        let signal = match fields.get(SIGNAL_FIELD) {
+            Some(signal) => Signal::parse(signal).map_err(|e| e.to_string())?,
+            None => Signal::Logs,
+        };
+        if signal == Signal::Metrics {
+            return Err(format!(
+                "the {} exporter emits the \"logs\" or \"traces\" signal, got [metrics]",
+                OTLP_HTTP_JSON_EXPORTER
+            ));
+        }
+        let batch_size = match fields.get("batch_size") {
+            Some(value) => match value.trim().parse::<usize>() {
+                Ok(batch_size) if batch_size > 0 => batch_size,
+                _ => return Err(format!("invalid `batch_size` field [{}]", value)),
+            },
+            None => DEFAULT_BATCH_SIZE,
+        };
+        let extra_headers = parse_headers(fields.get(HEADERS_FIELD).map(String::as_str))?;
+        let mut headers = vec![("Content-Type", "application/json")];
+        headers.extend(
+            extra_headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+
+        let requests = OtlpRequests {
+            endpoint,
+            headers,
+            signal,
+            attributes: key_values(&ctx.static_attributes),
+            trace_ids: Mutex::new(TraceIdGenerator::new(ctx.run_key, spans_per_trace_of(cfg)?)),
+        };
+        BatchingSink::new(requests, batch_size as u64)
+            .with_cancellation(ctx.cancellation.clone())
+            .export_iter(export_stream(datapoints, ctx).cloned(), cfg, ctx)
+    }
+}
+
+/// The requests of the `OtlpHttpJsonExporter`; 1 request per batch of its `BatchingSink`.
+struct OtlpRequests<'a> {
+    endpoint: &'a str,
+    headers: Vec<(&'a str, &'a str)>,
+    signal: Signal,
+    attributes: Value,
+    // the spans of a trace may span batches
+    trace_ids: Mutex<TraceIdGenerator>,
+}
+
+impl Exporter for OtlpRequests<'_> {
+    fn name(&self) -> &str {
+        OTLP_HTTP_JSON_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let mut trace_ids = self.trace_ids.lock().unwrap();
+        let records: Vec<Value> = row_records(datapoints, cfg, ctx, timestamp_format_of(cfg))
+            .map(|(timestamp, payload)| match self.signal {
+                Signal::Traces => span(&timestamp, &mut trace_ids, &self.attributes),
+                _ => log_record(&timestamp, payload, &self.attributes),
+            })
+            .collect();
+        let rows_exported = records.len() as u64;
+        send_request(self.endpoint, &self.headers, self.signal, records)?;
+        Ok(ExportSummary {
+            rows_exported,
+            batches: 1,
+            ..Default::default()
+        })
+    }
+}
+
+/// The OTLP JSON export request of the signal wrapping the records (log records or spans) under
+/// 1 resource and scope.
+pub fn export_request_json(signal: Signal, records: Vec<Value>) -> Value {
+    let resource = json!({
+        "attributes": [
+            {"key": "service.name", "value": {"stringValue": SCOPE_NAME}}
+        ]
+    });
+    let scope = json!({ "name": SCOPE_NAME });
+    match signal {
+        Signal::Traces => json!({
+            "resourceSpans": [{
+                "resource": resource,
+                "scopeSpans": [{ "scope": scope, "spans": records }]
+            }]
+        }),
+        _ => json!({
+            "resourceLogs": [{
+                "resource": resource,
+                "scopeLogs": [{ "scope": scope, "logRecords": records }]
+            }]
+        }),
+    }
+}
+
+fn send_request(
+    endpoint: &str,
+    headers: &[(&str, &str)],
+    signal: Signal,
+    records: Vec<Value>,
+) -> Result<(), String> {
+    let body = export_request_json(signal, records).to_string();
+    let response = http::post(endpoint, headers, body.as_bytes())?;
+    if !response.is_success() {
+        return Err(format!(
+            "{} request to [{}] failed with http status {}: {}",
+            OTLP_HTTP_JSON_EXPORTER,
+            endpoint,
+            response.status,
+            response.body.trim()
+        ));
+    }
+    Ok(())
+}
+
+/// 1 log record; the row payload is its body.
+fn log_record(timestamp: &DateTime<Utc>, payload: String, attributes: &Value) -> Value {
+    json!({
+        "timeUnixNano": unix_nanos(timestamp),
+        "body": { "stringValue": payload },
+        "attributes": attributes
+    })
+}
+
+/// 1 (instant) span starting and ending at the row timestamp.
+fn span(timestamp: &DateTime<Utc>, trace_ids: &mut TraceIdGenerator, attributes: &Value) -> Value {
+    let (trace_id, span_id) = trace_ids.next_ids();
+    json!({
+        "traceId": trace_id.to_string(),
+        "spanId": span_id.to_string(),
+        "name": SCOPE_NAME,
+        "kind": SPAN_KIND,
+        "startTimeUnixNano": unix_nanos(timestamp),
+        "endTimeUnixNano": unix_nanos(timestamp),
+        "attributes": attributes
+    })
+}
+
+// the 64 bit nanoseconds are strings in the OTLP JSON encoding.
+fn unix_nanos(timestamp: &DateTime<Utc>) -> String {
+    timestamp
+        .timestamp_nanos_opt()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The static attributes as OTLP `KeyValue`s.
+fn key_values(attributes: &[(String, String)]) -> Value {
+    Value::Array(
+        attributes
+            .iter()
+            .map(|(key, value)| json!({"key": key, "value": {"stringValue": value}}))
+            .collect(),
+    )
+}
+
+/// The `headers` field as (name, value) pairs; none if unset.
+fn parse_headers(headers: Option<&str>) -> Result<Vec<(String, String)>, String> {
+    let headers = match headers {
+        Some(headers) if !headers.trim().is_empty() => headers,
+        _ => return Ok(vec![]),
+    };
+    headers
+        .split(',')
+        .map(|header| match header.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(format!(
+                "invalid `{}` field [{}]; expecting comma separated name=value pairs",
+                HEADERS_FIELD, headers
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use chrono::Duration;
+
+    use super::*;
+
+    /// serve 1 request; returns its head and body.
+    fn serve_request(listener: TcpListener) -> (String, String) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Content-Length: ") {
+                content_length = value.trim().parse().unwrap();
+            }
+            head.push_str(line.as_str());
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}")
+            .unwrap();
+        (head, String::from_utf8(body).unwrap())
+    }
+
+    fn exporter_cfg(endpoint: &str, signal: &str) -> ConfigExporter {
+        toml::from_str(
+            format!(
+                r#"
+                name = "otlp_http_json"
+                enabled = true
+                [fields]
+                endpoint = "{}"
+                signal = "{}"
+                headers = "Authorization=Bearer abc, X-Scope-OrgID=tenant1"
+                "#,
+                endpoint, signal
+            )
+            .as_str(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_export_otlp_http_json() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 3),
+            DataPoint::new(start_time + Duration::seconds(1), 2),
+        ];
+
+        // logs
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1/logs", listener.local_addr().unwrap());
+        let server = thread::spawn(move || serve_request(listener));
+        let summary = OtlpHttpJsonExporter
+            .export(
+                &datapoints,
+                &exporter_cfg(endpoint.as_str(), "logs"),
+                &ExportContext::default(),
+            )
+            .unwrap();
+        assert_eq!(summary.rows_exported, 5);
+        assert_eq!(summary.batches, 1);
+        let (head, body) = server.join().unwrap();
+        assert!(head.starts_with("POST /v1/logs HTTP/1.1\r\n"));
+        assert!(head.contains("Content-Type: application/json\r\n"));
+        assert!(head.contains("Authorization: Bearer abc\r\n"));
+        assert!(head.contains("X-Scope-OrgID: tenant1\r\n"));
+        let request: Value = serde_json::from_str(body.as_str()).unwrap();
+        let records = request["resourceLogs"][0]["scopeLogs"][0]["logRecords"]
+            .as_array()
+            .unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0]["timeUnixNano"], "1640995200000000000");
+        assert_eq!(
+            records[0]["body"]["stringValue"],
+            "{\"timestamp\":\"2022-01-01T00:00:00.000000000Z\"}"
+        );
+
+        // traces
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+        let server = thread::spawn(move || serve_request(listener));
+        OtlpHttpJsonExporter
+            .export(
+                &datapoints,
+                &exporter_cfg(endpoint.as_str(), "traces"),
+                &ExportContext::default(),
+            )
+            .unwrap();
+        let (_, body) = server.join().unwrap();
+        let request: Value = serde_json::from_str(body.as_str()).unwrap();
+        let spans = request["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 5);
+        assert_eq!(spans[0]["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(spans[0]["spanId"].as_str().unwrap().len(), 16);
+        assert_ne!(spans[0]["spanId"], spans[1]["spanId"]);
+
+        assert_eq!(
+            OtlpHttpJsonExporter
+                .export(
+                    &datapoints,
+                    &exporter_cfg("http://localhost:1/", "metrics"),
+                    &ExportContext::default(),
+                )
+                .err()
+                .unwrap(),
+            "the otlp_http_json exporter emits the \"logs\" or \"traces\" signal, got [metrics]"
+        );
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        assert!(parse_headers(None).unwrap().is_empty());
+        assert_eq!(
+            parse_headers(Some("a=1,b=x=y")).unwrap(),
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "x=y".to_string())
+            ]
+        );
+        assert_eq!(
+            parse_headers(Some("a")).err().unwrap(),
+            "invalid `headers` field [a]; expecting comma separated name=value pairs"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::augmentation::DataPoint;
+
+/// The default minimum interval between 2 progress updates.
+pub const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Reports the percentage of the total rows exported so far; throttled to at most 1 update per
+/// `interval` (the final 100% is always reported).
+///
+/// Feed it either from the batching layer (`BatchingSink::with_progress`, counting the flushed
+/// rows) or from the streaming iterator (`track` or the `export_stream` of a run, counting the
+/// rows consumed); not both, as the rows would be counted twice.
+pub struct ProgressReporter {
+    total_rows: u64,
+    interval: Duration,
+    state: Mutex<ProgressState>,
+    on_progress: Box<dyn Fn(f64) + Send + Sync>,
+}
+
+impl std::fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressReporter")
+            .field("total_rows", &self.total_rows)
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+struct ProgressState {
+    rows: u64,
+    last_update: Option<Instant>,
+    last_percentage: f64,
+}
+
+impl ProgressReporter {
+    /// `on_progress` receives the percentage (0..=100) of the `total_rows` exported.
+    pub fn new<F>(total_rows: u64, interval: Duration, on_progress: F) -> Self
+    where
+        F: Fn(f64) + Send + Sync + 'static,
+    {
+        ProgressReporter {
+            total_rows,
+            interval,
+            state: Mutex::new(ProgressState {
+                rows: 0,
+                last_update: None,
+                last_percentage: 0.0,
+            }),
+            on_progress: Box::new(on_progress),
+        }
+    }
+
+    /// A reporter logging the progress of the `exporter` (e.g. `exporter [file] exported 42.0%
+    /// (4200 / 10000 rows)`); the total is typically the rows of its datapoints.
+    pub fn logging(exporter: &str, total_rows: u64) -> Self {
+        let exporter = exporter.to_string();
+        ProgressReporter::new(total_rows, DEFAULT_PROGRESS_INTERVAL, move |percentage| {
+            tracing::info!(
+                "exporter [{}] exported {:.1}% ({} / {} rows)",
+                exporter,
+                percentage,
+                (percentage / 100.0 * total_rows as f64).round() as u64,
+                total_rows
+            )
+        })
+    }
+
+    /// Count `rows` more rows exported; reports if the interval has passed since the last
+    /// update or the total is reached.
+    pub fn advance(&self, rows: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.rows += rows;
+        let percentage = match self.total_rows {
+            0 => 100.0,
+            total_rows => (state.rows as f64 * 100.0 / total_rows as f64).min(100.0),
+        };
+        let due = match state.last_update {
+            Some(last_update) => last_update.elapsed() >= self.interval,
+            None => true,
+        };
+        // never repeat (or go back on) a percentage; 100% is reported exactly once
+        if percentage > state.last_percentage && (due || percentage >= 100.0) {
+            state.last_update = Some(Instant::now());
+            state.last_percentage = percentage;
+            (self.on_progress)(percentage);
+        }
+    }
+
+    /// Count the rows of the datapoints as they stream through.
+    pub fn track<'a, I>(&'a self, datapoints: I) -> impl Iterator<Item = DataPoint> + 'a
+    where
+        I: IntoIterator<Item = DataPoint>,
+        I::IntoIter: 'a,
+    {
+        datapoints
+            .into_iter()
+            .inspect(move |datapoint| self.advance((*datapoint.rows_to_add()).max(0) as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::augmentation::generate_datapoints_iter;
+    use crate::config::{generation_test_config, Config, ConfigExporter};
+    use crate::exporters::batching::BatchingSink;
+    use crate::exporters::file::FileExporter;
+    use crate::exporters::memory::MemoryExporter;
+    use crate::exporters::{ExportContext, Exporter};
+
+    fn cfg() -> Config {
+        generation_test_config("even", 10000, "10m")
+    }
+
+    fn assert_progress(percentages: &[f64]) {
+        assert!(!percentages.is_empty());
+        for pair in percentages.windows(2) {
+            assert!(pair[0] < pair[1], "{:?}", percentages);
+        }
+        assert_eq!(*percentages.last().unwrap(), 100.0);
+    }
+
+    #[test]
+    fn test_progress_batching() {
+        let cfg = cfg();
+        let exporter_cfg: ConfigExporter = toml::from_str("name = \"memory\"").unwrap();
+        let percentages = Arc::new(Mutex::new(vec![]));
+        let recorded = percentages.clone();
+        let progress = Arc::new(ProgressReporter::new(
+            cfg.number_of_entries().unwrap() as u64,
+            Duration::ZERO,
+            move |percentage| recorded.lock().unwrap().push(percentage),
+        ));
+
+        let sink = BatchingSink::new(MemoryExporter::new(), 1500).with_progress(progress);
+        sink.export_iter(
+            generate_datapoints_iter(&cfg).unwrap(),
+            &exporter_cfg,
+            &ExportContext::default(),
+        )
+        .unwrap();
+        let percentages = percentages.lock().unwrap();
+        // 1 update per flush
+        assert_eq!(percentages.len(), 7);
+        assert_eq!(percentages[0], 15.0);
+        assert_progress(&percentages);
+    }
+
+    #[test]
+    fn test_progress_track() {
+        let cfg = cfg();
+        let percentages = Arc::new(Mutex::new(vec![]));
+        let recorded = percentages.clone();
+        // throttled; only the 1st update and the final 100% get through
+        let progress = ProgressReporter::new(10000, Duration::from_secs(3600), move |percentage| {
+            recorded.lock().unwrap().push(percentage)
+        });
+        let rows: u64 = progress
+            .track(generate_datapoints_iter(&cfg).unwrap())
+            .map(|datapoint| *datapoint.rows_to_add() as u64)
+            .sum();
+        assert_eq!(rows, 10000);
+        let percentages = percentages.lock().unwrap();
+        assert_eq!(percentages.len(), 2);
+        assert_progress(&percentages);
+    }
+
+    #[test]
+    fn test_progress_export_stream() {
+        let cfg = cfg();
+        let datapoints = generate_datapoints_iter(&cfg).unwrap().collect::<Vec<_>>();
+        let folder = tempfile::tempdir().unwrap();
+        let exporter_cfg: ConfigExporter = toml::from_str(
+            format!(
+                "name = \"file\"\n[fields]\npath = \"{}\"\nfilename = \"rows.jsonl\"",
+                folder.path().display()
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let percentages = Arc::new(Mutex::new(vec![]));
+        let recorded = percentages.clone();
+        let ctx = ExportContext {
+            progress: Some(Arc::new(ProgressReporter::new(
+                10000,
+                Duration::ZERO,
+                move |percentage| recorded.lock().unwrap().push(percentage),
+            ))),
+            ..Default::default()
+        };
+
+        // the rows consumed by the row loop of the exporter
+        FileExporter
+            .export(&datapoints, &exporter_cfg, &ctx)
+            .unwrap();
+        let percentages = percentages.lock().unwrap();
+        // 1 update per datapoint
+        assert_eq!(percentages.len(), datapoints.len());
+        assert_progress(&percentages);
+    }
+}
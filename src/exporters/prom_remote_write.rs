@@ -0,0 +1,328 @@
+use prost::Message;
+
+use super::{http, ExportContext, ExportSummary, Exporter};
+use crate::augmentation::{total_rows, DataPoint, Unit, ValueMode};
+use crate::config::ConfigExporter;
+
+pub const PROM_REMOTE_WRITE_EXPORTER: &str = "prom_remote_write";
+
+const METRIC_NAME: &str = "broccoli_generated_total";
+const GAUGE_METRIC_NAME: &str = "broccoli_generated_value";
+const BYTES_METRIC_NAME: &str = "broccoli_generated_bytes";
+const JOB_NAME: &str = "otel_broccoli";
+
+/// prometheus remote-write (v1) protobuf messages; only the parts required by the exporter.
+#[derive(Clone, PartialEq, Message)]
+pub struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub timeseries: Vec<TimeSeries>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct TimeSeries {
+    #[prost(message, repeated, tag = "1")]
+    pub labels: Vec<Label>,
+
+    #[prost(message, repeated, tag = "2")]
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Label {
+    #[prost(string, tag = "1")]
+    pub name: String,
+
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Sample {
+    #[prost(double, tag = "1")]
+    pub value: f64,
+
+    /// timestamp in milliseconds.
+    #[prost(int64, tag = "2")]
+    pub timestamp: i64,
+}
+
+/// Exports the per-slot counts as the `broccoli_generated_total` counter via prometheus remote-write.
+///
+/// Every datapoint becomes a sample of the running cumulative sum of rows, POSTed (protobuf +
+/// snappy) to the exporter's `endpoint` field. With the `value_mode = "gauge"` field, the
+/// per-slot values are exported as-is as the `broccoli_generated_value` gauge instead; with the
+/// top level `unit = "bytes"` (or the exporter's `unit` field overriding it), as the
+/// `broccoli_generated_bytes` gauge.
+pub struct PromRemoteWriteExporter;
+
+impl Exporter for PromRemoteWriteExporter {
+    fn name(&self) -> &str {
+        PROM_REMOTE_WRITE_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let endpoint = cfg
+            .fields()
+            .as_ref()
+            .and_then(|fields| fields.get("endpoint"))
+            .ok_or(format!(
+                "missing `endpoint` field for the {} exporter",
+                PROM_REMOTE_WRITE_EXPORTER
+            ))?;
+        let value_mode = match cfg.fields().as_ref().and_then(|f| f.get("value_mode")) {
+            Some(value_mode) => ValueMode::parse(value_mode).map_err(|e| e.to_string())?,
+            None => ValueMode::Count,
+        };
+        let unit = match cfg.fields().as_ref().and_then(|f| f.get("unit")) {
+            Some(unit) => Unit::parse(unit).map_err(|e| e.to_string())?,
+            None => ctx.unit,
+        };
+        let payload = encode_write_request(&build_write_request(
+            datapoints,
+            value_mode,
+            unit,
+            ctx.cumulative,
+        ))?;
+
+        let response = http::post(
+            endpoint,
+            &[
+                ("Content-Type", "application/x-protobuf"),
+                ("Content-Encoding", "snappy"),
+                ("X-Prometheus-Remote-Write-Version", "0.1.0"),
+            ],
+            &payload,
+        )?;
+        if !response.is_success() {
+            return Err(format!(
+                "remote-write to [{}] failed with http status {}: {}",
+                endpoint, response.status, response.body
+            ));
+        }
+        Ok(ExportSummary {
+            rows_exported: total_rows(datapoints)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Build the remote-write request; 1 counter sample (running cumulative sum) per datapoint, or
+/// 1 gauge sample (the datapoint's value) per datapoint in the gauge mode.
+///
+/// In bytes (`unit`), every datapoint is a `broccoli_generated_bytes` gauge sample of its bytes
+/// (the throughput per slot) whatever the `value_mode`.
+///
+/// With `cumulative`, the gauge samples are running totals too (the counter always is).
+pub fn build_write_request(
+    datapoints: &[DataPoint],
+    value_mode: ValueMode,
+    unit: Unit,
+    cumulative: bool,
+) -> WriteRequest {
+    let value_mode = match unit {
+        Unit::Events => value_mode,
+        Unit::Bytes => ValueMode::Gauge,
+    };
+    // samples must be in time order (jitter could have shuffled the datapoints slightly)
+    let mut ordered: Vec<&DataPoint> = datapoints.iter().collect();
+    ordered.sort_by_key(|datapoint| *datapoint.timestamp());
+
+    let mut cumulative_sum: u64 = 0;
+    let samples = ordered
+        .iter()
+        .map(|datapoint| {
+            let value = match value_mode {
+                ValueMode::Gauge if !cumulative => *datapoint.rows_to_add() as f64,
+                _ => {
+                    cumulative_sum += (*datapoint.rows_to_add()).max(0) as u64;
+                    cumulative_sum as f64
+                }
+            };
+            Sample {
+                value,
+                timestamp: datapoint.timestamp().timestamp_millis(),
+            }
+        })
+        .collect();
+
+    WriteRequest {
+        timeseries: vec![TimeSeries {
+            // labels must be sorted by name
+            labels: vec![
+                Label {
+                    name: "__name__".to_string(),
+                    value: match (unit, value_mode) {
+                        (Unit::Bytes, _) => BYTES_METRIC_NAME,
+                        (Unit::Events, ValueMode::Count) => METRIC_NAME,
+                        (Unit::Events, ValueMode::Gauge) => GAUGE_METRIC_NAME,
+                    }
+                    .to_string(),
+                },
+                Label {
+                    name: "job".to_string(),
+                    value: JOB_NAME.to_string(),
+                },
+            ],
+            samples,
+        }],
+    }
+}
+
+/// protobuf encode then snappy (block format) compress the request.
+pub fn encode_write_request(request: &WriteRequest) -> Result<Vec<u8>, String> {
+    snap::raw::Encoder::new()
+        .compress_vec(&request.encode_to_vec())
+        .map_err(|e| format!("failed to snappy compress the remote-write payload: {}", e))
+}
+
+/// the reverse of `encode_write_request`.
+pub fn decode_write_request(payload: &[u8]) -> Result<WriteRequest, String> {
+    let decompressed = snap::raw::Decoder::new()
+        .decompress_vec(payload)
+        .map_err(|e| {
+            format!(
+                "failed to snappy decompress the remote-write payload: {}",
+                e
+            )
+        })?;
+    WriteRequest::decode(decompressed.as_slice())
+        .map_err(|e| format!("failed to decode the remote-write payload: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+
+    #[test]
+    fn test_encode_and_decode_write_request() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 5),
+            DataPoint::new(start_time + Duration::seconds(1), 0),
+            DataPoint::new(start_time + Duration::seconds(2), 12),
+            DataPoint::new(start_time + Duration::seconds(3), 3),
+        ];
+        let payload = encode_write_request(&build_write_request(
+            &datapoints,
+            ValueMode::Count,
+            Unit::Events,
+            false,
+        ))
+        .unwrap();
+        let request = decode_write_request(&payload).unwrap();
+
+        assert_eq!(request.timeseries.len(), 1);
+        let series = &request.timeseries[0];
+        assert_eq!(series.labels[0].name, "__name__");
+        assert_eq!(series.labels[0].value, METRIC_NAME);
+
+        let values: Vec<f64> = series.samples.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![5.0, 5.0, 17.0, 20.0]);
+        // a counter never goes down
+        for pair in series.samples.windows(2) {
+            assert!(pair[0].value <= pair[1].value);
+            assert!(pair[0].timestamp < pair[1].timestamp);
+        }
+        assert_eq!(series.samples[0].timestamp, start_time.timestamp_millis());
+
+        // gauge; the values as-is
+        let request = build_write_request(&datapoints, ValueMode::Gauge, Unit::Events, false);
+        let series = &request.timeseries[0];
+        assert_eq!(series.labels[0].value, GAUGE_METRIC_NAME);
+        let values: Vec<f64> = series.samples.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![5.0, 0.0, 12.0, 3.0]);
+
+        // bytes; a gauge of the per-slot bytes whatever the value_mode
+        let request = build_write_request(&datapoints, ValueMode::Count, Unit::Bytes, false);
+        let series = &request.timeseries[0];
+        assert_eq!(series.labels[0].value, "broccoli_generated_bytes");
+        let values: Vec<f64> = series.samples.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![5.0, 0.0, 12.0, 3.0]);
+
+        // cumulative; the gauge becomes a running total
+        let request = build_write_request(&datapoints, ValueMode::Count, Unit::Bytes, true);
+        let values: Vec<f64> = request.timeseries[0]
+            .samples
+            .iter()
+            .map(|s| s.value)
+            .collect();
+        assert_eq!(values, vec![5.0, 5.0, 17.0, 20.0]);
+    }
+
+    #[test]
+    fn test_export_unit() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/api/v1/write", listener.local_addr().unwrap());
+
+        // the metric name of every request
+        let server = thread::spawn(move || {
+            let mut names = vec![];
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        content_length = value.trim().parse::<usize>().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request = decode_write_request(&body).unwrap();
+                names.push(request.timeseries[0].labels[0].value.clone());
+                reader
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                    .unwrap();
+            }
+            names
+        });
+
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![DataPoint::new(start_time, 5)];
+        let cfg_with_unit = |unit: Option<&str>| -> ConfigExporter {
+            let unit = unit
+                .map(|unit| format!("unit = \"{}\"", unit))
+                .unwrap_or_default();
+            toml::from_str(
+                format!(
+                    "name = \"prom_remote_write\"\n[fields]\nendpoint = \"{}\"\n{}",
+                    endpoint, unit
+                )
+                .as_str(),
+            )
+            .unwrap()
+        };
+        // the top level unit; overridden by the exporter's field
+        let ctx = ExportContext {
+            unit: Unit::Bytes,
+            ..Default::default()
+        };
+        PromRemoteWriteExporter
+            .export(&datapoints, &cfg_with_unit(None), &ctx)
+            .unwrap();
+        PromRemoteWriteExporter
+            .export(&datapoints, &cfg_with_unit(Some("events")), &ctx)
+            .unwrap();
+        assert_eq!(
+            server.join().unwrap(),
+            vec![BYTES_METRIC_NAME.to_string(), METRIC_NAME.to_string()]
+        );
+    }
+}
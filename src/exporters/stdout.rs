@@ -0,0 +1,195 @@
+use std::io::Write;
+
+use super::{
+    row_records, row_records_count, timestamp_format_of, ExportContext, ExportSummary, Exporter,
+};
+use crate::augmentation::DataPoint;
+use crate::config::ConfigExporter;
+
+pub const STDOUT_EXPORTER: &str = "stdout";
+
+// non verbose mode; the row count is updated every N rows.
+const PROGRESS_INTERVAL: u64 = 100000;
+
+/// Prints the generated rows to the stdout.
+///
+/// With `verbose` every row is printed; otherwise only the 1st and the last rows are printed and
+/// in between only the row count is updated (printing is an `expensive` operation).
+pub struct StdoutExporter;
+
+impl Exporter for StdoutExporter {
+    fn name(&self) -> &str {
+        STDOUT_EXPORTER
+    }
+
+    fn export(
+        &self,
+        datapoints: &[DataPoint],
+        cfg: &ConfigExporter,
+        ctx: &ExportContext,
+    ) -> Result<ExportSummary, String> {
+        let verbose = cfg.verbose().unwrap_or(false);
+        write_rows(
+            row_records(datapoints, cfg, ctx, timestamp_format_of(cfg)).map(|(_, payload)| payload),
+            row_records_count(datapoints, ctx)?,
+            verbose,
+            &mut std::io::stdout().lock(),
+        )
+        .map_err(|e| format!("failed to write to the stdout: {}", e))
+    }
+}
+
+fn write_rows<W: Write>(
+    payloads: impl Iterator<Item = String>,
+    total: u64,
+    verbose: bool,
+    out: &mut W,
+) -> std::io::Result<ExportSummary> {
+    let mut rows_exported: u64 = 0;
+    for payload in payloads {
+        rows_exported += 1;
+        if verbose || rows_exported == 1 || rows_exported == total {
+            writeln!(out, "{}", payload)?;
+        } else if rows_exported.is_multiple_of(PROGRESS_INTERVAL) {
+            writeln!(out, "... {} rows", rows_exported)?;
+        }
+    }
+    out.flush()?;
+    Ok(ExportSummary {
+        rows_exported,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::templates::RowTemplates;
+
+    #[test]
+    fn test_write_rows() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![
+            DataPoint::new(start_time, 2),
+            DataPoint::new(start_time + Duration::seconds(1), 1),
+        ];
+
+        let cfg: ConfigExporter = toml::from_str("name = \"stdout\"").unwrap();
+        let ctx = ExportContext::default();
+        let mut out: Vec<u8> = vec![];
+        let summary = write_rows(
+            row_records(&datapoints, &cfg, &ctx, None).map(|(_, payload)| payload),
+            row_records_count(&datapoints, &ctx).unwrap(),
+            true,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(summary.rows_exported, 3);
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 3);
+
+        // non verbose; only the 1st and the last rows
+        let mut out: Vec<u8> = vec![];
+        write_rows(
+            row_records(&datapoints, &cfg, &ctx, Some("%Y-%m-%dT%H:%M:%S%.3fZ"))
+                .map(|(_, payload)| payload),
+            row_records_count(&datapoints, &ctx).unwrap(),
+            false,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"timestamp\":\"2022-01-01T00:00:00.000Z\"}\n\
+             {\"timestamp\":\"2022-01-01T00:00:01.000Z\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_rows_attributes() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![DataPoint::new(start_time, 50)];
+
+        let cfg: ConfigExporter = toml::from_str("name = \"stdout\"").unwrap();
+        let ctx = ExportContext {
+            attributes: HashMap::from([
+                (
+                    "host".to_string(),
+                    vec!["host-1".to_string(), "host-2".to_string()],
+                ),
+                ("region".to_string(), vec!["us-east-1".to_string()]),
+            ]),
+            static_attributes: vec![("dataset".to_string(), "loadtest".to_string())],
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = vec![];
+        write_rows(
+            row_records(&datapoints, &cfg, &ctx, None).map(|(_, payload)| payload),
+            row_records_count(&datapoints, &ctx).unwrap(),
+            true,
+            &mut out,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 50);
+        let mut seen_hosts = HashSet::new();
+        for line in out.lines() {
+            // the picks in key order, then the static attributes
+            let host = line
+                .strip_prefix("{\"timestamp\":\"2022-01-01T00:00:00.000000000Z\",\"host\":\"")
+                .and_then(|rest| {
+                    rest.strip_suffix("\",\"region\":\"us-east-1\",\"dataset\":\"loadtest\"}")
+                })
+                .unwrap();
+            assert!(["host-1", "host-2"].contains(&host), "{}", line);
+            seen_hosts.insert(host.to_string());
+        }
+        assert_eq!(seen_hosts.len(), 2);
+    }
+
+    #[test]
+    fn test_write_rows_templates() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let datapoints = vec![DataPoint::new(start_time, 50)];
+
+        let cfg: ConfigExporter = toml::from_str("name = \"stdout\"").unwrap();
+        let ctx = ExportContext {
+            attributes: HashMap::from([("region".to_string(), vec!["us-east-1".to_string()])]),
+            templates: RowTemplates::new(
+                &HashMap::from([("message".to_string(), "GET /api/{path}".to_string())]),
+                &HashMap::from([(
+                    "path".to_string(),
+                    vec!["users".to_string(), "orders".to_string()],
+                )]),
+            ),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = vec![];
+        write_rows(
+            row_records(&datapoints, &cfg, &ctx, None).map(|(_, payload)| payload),
+            row_records_count(&datapoints, &ctx).unwrap(),
+            true,
+            &mut out,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 50);
+        let mut seen_paths = HashSet::new();
+        for line in out.lines() {
+            // the attributes, then the rendered templates
+            let path = line
+                .strip_prefix(
+                    "{\"timestamp\":\"2022-01-01T00:00:00.000000000Z\",\"region\":\"us-east-1\",\
+                     \"message\":\"GET /api/",
+                )
+                .and_then(|rest| rest.strip_suffix("\"}"))
+                .unwrap();
+            assert!(["users", "orders"].contains(&path), "{}", line);
+            seen_paths.insert(path.to_string());
+        }
+        assert_eq!(seen_paths.len(), 2);
+    }
+}
@@ -0,0 +1,91 @@
+use std::io::Write;
+
+use chrono_tz::Tz;
+
+use crate::augmentation::{expand_datapoints_iter, DataPoint};
+
+pub const TIMESTAMPS_OUTPUT_FORMAT: &str = "timestamps";
+
+/// Writes the expanded event stream; 1 line per individual row carrying only its timestamp.
+///
+/// The timestamps are rendered in the given timezone. The lines are sorted by timestamp. Only the datapoints are sorted (not the expanded events),
+/// hence the memory footprint stays the same as the datapoints even for huge datasets.
+pub struct TimestampsExporter {
+    timestamp_format: String,
+    timezone: Tz,
+}
+
+impl TimestampsExporter {
+    pub fn new(timestamp_format: &str, timezone: Tz) -> Self {
+        TimestampsExporter {
+            timestamp_format: timestamp_format.to_string(),
+            timezone,
+        }
+    }
+
+    /// Write the sorted timestamps to `out`; returns the number of lines written.
+    pub fn export<W: Write>(&self, datapoints: &[DataPoint], out: &mut W) -> Result<u64, String> {
+        // jitter could have shuffled the datapoints slightly
+        let mut ordered: Vec<DataPoint> = datapoints
+            .iter()
+            .map(|datapoint| DataPoint::new(*datapoint.timestamp(), *datapoint.rows_to_add()))
+            .collect();
+        ordered.sort_by_key(|datapoint| *datapoint.timestamp());
+
+        let mut lines: u64 = 0;
+        for timestamp in expand_datapoints_iter(&ordered) {
+            writeln!(
+                out,
+                "{}",
+                timestamp
+                    .with_timezone(&self.timezone)
+                    .format(&self.timestamp_format)
+            )
+            .map_err(|e| format!("failed to write the timestamps: {}", e))?;
+            lines += 1;
+        }
+        out.flush()
+            .map_err(|e| format!("failed to write the timestamps: {}", e))?;
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+
+    #[test]
+    fn test_export_timestamps() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        // out of order (e.g. jittered)
+        let datapoints = vec![
+            DataPoint::new(start_time + Duration::seconds(1), 2),
+            DataPoint::new(start_time, 1),
+        ];
+        let mut out: Vec<u8> = vec![];
+        let lines = TimestampsExporter::new("%Y-%m-%dT%H:%M:%S", Tz::UTC)
+            .export(&datapoints, &mut out)
+            .unwrap();
+        assert_eq!(lines, 3);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "2022-01-01T00:00:00\n2022-01-01T00:00:01\n2022-01-01T00:00:01\n"
+        );
+    }
+
+    #[test]
+    fn test_export_timestamps_timezone() {
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let mut out: Vec<u8> = vec![];
+        TimestampsExporter::new("%Y-%m-%dT%H:%M:%S%:z", Tz::Asia__Hong_Kong)
+            .export(&[DataPoint::new(start_time, 1)], &mut out)
+            .unwrap();
+        // same instant, rendered at +08:00
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "2022-01-01T08:00:00+08:00\n"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use crate::config::ConfigExporter;
+
+/// The exporter field grouping that many consecutive spans under 1 trace (default 1).
+pub const SPANS_PER_TRACE_FIELD: &str = "spans_per_trace";
+
+const DEFAULT_SPANS_PER_TRACE: u64 = 1;
+
+/// A 16-byte OTLP trace ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceId(pub [u8; 16]);
+
+/// An 8-byte OTLP span ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpanId(pub [u8; 8]);
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl fmt::Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+/// Hands out the trace / span IDs of the generated rows (1 span per row); every
+/// `spans_per_trace` consecutive spans share the same trace.
+///
+/// The IDs are derived from the seed and the row index through a bijective mix, hence they are
+/// unique within a run (no collision by chance as with random IDs) and reproducible under the
+/// same seed.
+pub struct TraceIdGenerator {
+    seed: u64,
+    spans_per_trace: u64,
+    next_span: u64,
+}
+
+impl TraceIdGenerator {
+    pub fn new(seed: u64, spans_per_trace: u64) -> Self {
+        TraceIdGenerator {
+            seed,
+            spans_per_trace: spans_per_trace.max(1),
+            next_span: 0,
+        }
+    }
+
+    /// The IDs of the next row (span).
+    pub fn next_ids(&mut self) -> (TraceId, SpanId) {
+        let span = self.next_span;
+        self.next_span += 1;
+        let trace = span / self.spans_per_trace;
+
+        let trace_high = mix(self.seed ^ TRACE_KEY, trace);
+        let trace_low = mix(self.seed ^ TRACE_LOW_KEY, trace);
+        let mut trace_id = [0_u8; 16];
+        trace_id[..8].copy_from_slice(&trace_high.to_be_bytes());
+        trace_id[8..].copy_from_slice(&trace_low.to_be_bytes());
+        (
+            TraceId(trace_id),
+            SpanId(mix(self.seed ^ SPAN_KEY, span).to_be_bytes()),
+        )
+    }
+}
+
+impl Iterator for TraceIdGenerator {
+    type Item = (TraceId, SpanId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_ids())
+    }
+}
+
+// keys separating the trace and span ID streams of the same seed
+const TRACE_KEY: u64 = 0x7472_6163_655f_6869;
+const TRACE_LOW_KEY: u64 = 0x7472_6163_655f_6c6f;
+const SPAN_KEY: u64 = 0x7370_616e_5f69_6421;
+
+/// splitmix64 of `key + index`; a bijection of the index for a given key (distinct indexes
+/// never collide).
+fn mix(key: u64, index: u64) -> u64 {
+    let mut z = key.wrapping_add(index.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The `spans_per_trace` field of the exporter; 1 if unset.
+///
+/// # Errors
+///
+/// If the field is not a positive integer.
+///
+pub fn spans_per_trace_of(cfg: &ConfigExporter) -> Result<u64, String> {
+    match cfg
+        .fields()
+        .as_ref()
+        .and_then(|fields| fields.get(SPANS_PER_TRACE_FIELD))
+    {
+        Some(value) => match value.trim().parse::<u64>() {
+            Ok(spans_per_trace) if spans_per_trace > 0 => Ok(spans_per_trace),
+            _ => Err(format!(
+                "invalid `{}` field [{}]; expecting a positive integer",
+                SPANS_PER_TRACE_FIELD, value
+            )),
+        },
+        None => Ok(DEFAULT_SPANS_PER_TRACE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_trace_id_generator() {
+        let ids: Vec<(TraceId, SpanId)> = TraceIdGenerator::new(42, 4).take(10000).collect();
+
+        let span_ids: HashSet<SpanId> = ids.iter().map(|(_, span_id)| *span_id).collect();
+        assert_eq!(span_ids.len(), 10000);
+        // every 4 consecutive spans share a trace; no trace reused afterwards
+        let trace_ids: HashSet<TraceId> = ids.iter().map(|(trace_id, _)| *trace_id).collect();
+        assert_eq!(trace_ids.len(), 2500);
+        for group in ids.chunks(4) {
+            assert!(group.iter().all(|(trace_id, _)| *trace_id == group[0].0));
+        }
+        assert_ne!(ids[3].0, ids[4].0);
+
+        // reproducible under the same seed; different under another
+        let again: Vec<(TraceId, SpanId)> = TraceIdGenerator::new(42, 4).take(10000).collect();
+        assert_eq!(ids, again);
+        assert_ne!(TraceIdGenerator::new(7, 4).next_ids(), ids[0]);
+
+        // 1 trace per span by default
+        let mut generator = TraceIdGenerator::new(42, 1);
+        let (trace_id, span_id) = generator.next_ids();
+        assert_ne!(trace_id, generator.next_ids().0);
+        assert_eq!(trace_id.to_string().len(), 32);
+        assert_eq!(span_id.to_string().len(), 16);
+    }
+
+    #[test]
+    fn test_spans_per_trace_of() {
+        let cfg: ConfigExporter = toml::from_str("name = \"memory\"").unwrap();
+        assert_eq!(spans_per_trace_of(&cfg).unwrap(), 1);
+        let cfg: ConfigExporter =
+            toml::from_str("name = \"memory\"\n[fields]\nspans_per_trace = \"8\"").unwrap();
+        assert_eq!(spans_per_trace_of(&cfg).unwrap(), 8);
+        let cfg: ConfigExporter =
+            toml::from_str("name = \"memory\"\n[fields]\nspans_per_trace = \"0\"").unwrap();
+        assert_eq!(
+            spans_per_trace_of(&cfg).err().unwrap(),
+            "invalid `spans_per_trace` field [0]; expecting a positive integer"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! otel data generator; generates synthetic datapoints (timestamps and the number of rows per
+//! slot) following a distribution model, then exports the expanded rows to the configured sinks.
+//!
+//! The binary (`main.rs`) wires the modules together; the lib target exposes them for other
+//! consumers (e.g. the benches).
+
+pub mod augmentation;
+pub mod config;
+pub mod exporters;
+pub mod manifest;
+pub mod report;
+pub mod selfcheck;
+pub mod templates;
+pub mod transforms;
+
+use robjetives_log::prepare_loggers;
+use tracing::level_filters::LevelFilter;
+
+use crate::augmentation::{
+    canonical_model_name, generate_time_range, warmup_window, Anomaly, DistributionModelRegistry,
+    Order, RngAlgorithm, Rounding, SparsePlacement, Unit, ValueMode,
+};
+use crate::config::Config;
+
+pub fn app_init(config_file: String) -> Result<(), Box<dyn std::error::Error>> {
+    // default -> "./config/default/loggers.toml"
+    let result = prepare_loggers(config_file);
+    if result.is_err() {
+        return Err(Box::new(result.err().unwrap()));
+    }
+    tracing::info!("otel_broccoli application init successfully !!!");
+
+    Ok(())
+}
+
+/// Init the tracing output at the given level; independent of the loggers.toml (its appenders and
+/// levels are not used), the events go to stderr (stdout stays free for e.g. the `--output-format`
+/// dumps). Library consumers set the level
+/// programmatically through this instead of `app_init`.
+pub fn init_with_level(level: LevelFilter) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::subscriber::set_global_default(level_subscriber(level))?;
+    tracing::info!("otel_broccoli application init successfully !!!");
+
+    Ok(())
+}
+
+/// Check the (loaded) config the way a run would, without generating anything; e.g. a CI lint of
+/// the configs (`--validate-only`).
+///
+/// # Errors
+///
+/// The 1st failing check of `Config::validate`, the exporters (duplicates, signals and static
+/// attributes, the export range, the slot grid) or the generation settings (the window, the
+/// distribution model and the enum-like settings; i.e. every `from_config` a run relies on).
+///
+pub fn validate_config(cfg: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    cfg.validate()?;
+    cfg.validate_exporters()?;
+    cfg.timezone_or_utc()?;
+    cfg.verbosity_level()?;
+    exporters::signal_exporters(cfg)?;
+    exporters::static_attributes_of(cfg)?;
+    exporters::attributes_of(cfg)?;
+    exporters::templates_of(cfg)?;
+    exporters::TimestampTruncate::from_config(cfg)?;
+    exporters::export_range(cfg)?;
+    exporters::replay_speed(cfg)?;
+    exporters::SlotGrid::from_config(cfg)?;
+    for exporter in cfg.exporters().iter().flatten() {
+        exporters::max_export_rate_of(exporter)?;
+    }
+
+    generate_time_range(cfg)?;
+    let model = canonical_model_name(cfg.distribution_by().as_deref().unwrap_or_default());
+    if DistributionModelRegistry::with_builtin_models()
+        .get(model.as_str())
+        .is_none()
+    {
+        return Err(format!("unknown distribution model [{}]", model).into());
+    }
+    ValueMode::from_config(cfg)?;
+    Unit::from_config(cfg)?;
+    Rounding::from_config(cfg)?;
+    SparsePlacement::from_config(cfg)?;
+    RngAlgorithm::from_config(cfg)?;
+    Order::from_config(cfg)?;
+    Anomaly::from_config(cfg)?;
+    warmup_window(cfg)?;
+    Ok(())
+}
+
+/// a stderr subscriber filtering the events above the `level`.
+fn level_subscriber(level: LevelFilter) -> impl tracing::Subscriber + Send + Sync {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(level)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+
+    #[test]
+    fn test_validate_config() {
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let cfg = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "validate_good.toml".to_string(),
+        )
+        .unwrap();
+        assert!(validate_config(&cfg).is_ok());
+
+        // loads fine; the model is only looked up on validating (or generating)
+        let cfg = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "validate_bad.toml".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            validate_config(&cfg).err().unwrap().to_string(),
+            "unknown distribution model [zipf]"
+        );
+
+        // the enum-like settings only read by the generation or the exporters
+        let mut cfg = load_config(
+            "config/default".to_string(),
+            "tests".to_string(),
+            "config.toml".to_string(),
+            "validate_good.toml".to_string(),
+        )
+        .unwrap();
+        cfg.set_rounding(Some("nearest".to_string()));
+        assert!(validate_config(&cfg).is_err());
+        cfg.set_rounding(None);
+        cfg.set_rng(Some("xorshift".to_string()));
+        assert!(validate_config(&cfg).is_err());
+        cfg.set_rng(None);
+        cfg.set_unit(Some("lines".to_string()));
+        assert!(validate_config(&cfg).is_err());
+        cfg.set_unit(None);
+        cfg.set_sparse_placement(Some("clustered".to_string()));
+        assert!(validate_config(&cfg).is_err());
+        cfg.set_sparse_placement(None);
+        cfg.set_verbosity(Some("loud".to_string()));
+        assert_eq!(
+            validate_config(&cfg).err().unwrap().to_string(),
+            "unknown verbosity [loud]; expecting \"off\", \"error\", \"warn\", \"info\", \"debug\" or \"trace\""
+        );
+        cfg.set_verbosity(None);
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_level_subscriber() {
+        tracing::subscriber::with_default(level_subscriber(LevelFilter::ERROR), || {
+            assert!(tracing::enabled!(tracing::Level::ERROR));
+            assert!(!tracing::enabled!(tracing::Level::WARN));
+            assert!(!tracing::enabled!(tracing::Level::INFO));
+        });
+        tracing::subscriber::with_default(level_subscriber(LevelFilter::DEBUG), || {
+            assert!(tracing::enabled!(tracing::Level::DEBUG));
+            assert!(!tracing::enabled!(tracing::Level::TRACE));
+        });
+        tracing::subscriber::with_default(level_subscriber(LevelFilter::OFF), || {
+            assert!(!tracing::enabled!(tracing::Level::ERROR));
+        });
+    }
+}
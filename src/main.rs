@@ -1,25 +1,168 @@
-mod augmentation;
-mod config;
+use std::sync::Arc;
 
-use robjetives_log::prepare_loggers;
+use tracing::level_filters::LevelFilter;
 
-// use this, then no need to import mod config...
-//use crate::load_config;
+use otel_broccoli::{
+    app_init, augmentation, config, exporters, init_with_level, manifest, report, selfcheck,
+    validate_config,
+};
 
 fn main() {
-    if let Err(e) = app_init("./config/default/loggers.toml".to_string()) {
-        panic!("app_init error: {}", e);
+    // print out the supported distribution models then quit
+    if std::env::args().any(|arg| arg == "--list-distributions") {
+        print!(
+            "{}",
+            augmentation::DistributionModelRegistry::with_builtin_models().list()
+        );
+        return;
     }
-    println!("Hello, world!");
+    // loaded once; e.g. a `--config -` custom config drains the stdin
+    let cfg = load_default_config();
+    let init = match startup_level(cfg.as_ref().ok()) {
+        Ok(Some(level)) => init_with_level(level),
+        Ok(None) => app_init("./config/default/loggers.toml".to_string()),
+        Err(e) => Err(e),
+    };
+    // e.g. an unknown verbosity; non-zero exit
+    if let Err(e) = init {
+        eprintln!("app_init error: {}", e);
+        std::process::exit(1);
+    }
+    // load and check the config without generating anything; non-zero exit on errors (e.g. CI linting)
+    if std::env::args().any(|arg| arg == config::VALIDATE_ONLY_ARG) {
+        match cfg.and_then(|cfg| validate_config(&cfg)) {
+            Ok(_) => println!("config is valid"),
+            Err(e) => {
+                eprintln!("invalid config: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    // generate every distribution model (fixed seed) and check the invariants; non-zero exit on violations
+    if std::env::args().nth(1).as_deref() == Some("selfcheck") {
+        if let Err(e) = selfcheck::run_selfcheck() {
+            eprintln!("selfcheck failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    let cfg = match cfg {
+        Ok(cfg) => cfg,
+        Err(e) => panic!("config error: {}", e),
+    };
+    // dump the expanded event stream (e.g. to feed into other load tools)
+    if let Some(output_format) = arg_value("--output-format") {
+        if let Err(e) = dump_output(&cfg, &output_format) {
+            panic!("output error: {}", e);
+        }
+        return;
+    }
+    match run(&cfg) {
+        // the conventional exit code of an interrupted (SIGINT) process
+        Ok(interrupted) if interrupted => std::process::exit(130),
+        Ok(_) => {}
+        Err(e) => panic!("run error: {}", e),
+    }
+}
+
+/// generate the datapoints and run every enabled exporter over them; then log the run report.
+///
+/// On SIGINT the exporters not started yet are skipped and a partial run report is logged.
+///
+/// # Returns
+/// true if the run was interrupted.
+fn run(cfg: &config::Config) -> Result<bool, Box<dyn std::error::Error>> {
+    let cancellation = exporters::CancellationToken::new();
+    cancellation.install_sigint_handler()?;
+    // before the generation; e.g. an invalid replay speed would only fail the exporters after it
+    validate_config(cfg)?;
+    let datapoints = generate(cfg)?;
+    let (export_from, export_to) = exporters::export_range(cfg)?;
+    let datapoints = exporters::filter_export_range(&datapoints, export_from, export_to);
+    let mut report = report::RunReport::new(cfg, &datapoints)?;
+    let truncate = exporters::TimestampTruncate::from_config(cfg)?;
+    let mut datapoints = exporters::truncate_datapoints(&datapoints, truncate);
+    // the merged buckets come out oldest first; keep the configured order
+    if truncate != exporters::TimestampTruncate::None
+        && augmentation::Order::from_config(cfg)? == augmentation::Order::Desc
+    {
+        datapoints.reverse();
+    }
+    let signal_exporters = exporters::signal_exporters(cfg)?;
+    let ctx = exporters::ExportContext::from_config(cfg, report.seed, &datapoints)?;
+    let exporter_runs =
+        exporters::run_exporters(&signal_exporters, Arc::new(datapoints), &ctx, &cancellation)?;
+    tracing::info!("{} exporter(s) completed", exporter_runs.len());
+    report.set_exporters(exporter_runs);
+    report.set_interrupted(cancellation.is_cancelled());
+    tracing::info!("\n{}", report);
+    Ok(report.interrupted)
+}
+
+/// load the default config (or the `--config` custom config; `-` = stdin) then layer the env
+/// and CLI overrides over it.
+fn load_default_config() -> Result<config::Config, Box<dyn std::error::Error>> {
+    let mut cfg = config::load_config(
+        "config/default".to_string(),
+        "config/default".to_string(),
+        "config.toml".to_string(),
+        arg_value("--config").unwrap_or("config.toml".to_string()),
+    )?;
+    cfg.merge_cli(&std::env::args().collect::<Vec<String>>())?;
+    Ok(cfg)
 }
 
-pub fn app_init(config_file: String) -> Result<(), Box<dyn std::error::Error>> {
-    // default -> "./config/default/loggers.toml"
-    let result = prepare_loggers(config_file);
-    if result.is_err() {
-        return Err(Box::new(result.err().unwrap()));
+/// generate the datapoints (and the run manifest if configured).
+fn generate(
+    cfg: &config::Config,
+) -> Result<Vec<augmentation::DataPoint>, Box<dyn std::error::Error>> {
+    let datapoints = augmentation::generate_datapoints(cfg)?;
+    if let Some(manifest_path) = cfg.manifest_path() {
+        manifest::ManifestWriter::new(manifest_path).write(cfg, &datapoints)?;
     }
-    tracing::info!("otel_broccoli application init successfully !!!");
+    Ok(datapoints)
+}
 
+/// the tracing level of the `--quiet` flag or the configured `verbosity` of the loaded `cfg`; none
+/// if unset (or the config failed to load; `main` reports that error once the tracing is up).
+fn startup_level(
+    cfg: Option<&config::Config>,
+) -> Result<Option<LevelFilter>, Box<dyn std::error::Error>> {
+    if std::env::args().any(|arg| arg == config::QUIET_ARG) {
+        return Ok(Some(LevelFilter::ERROR));
+    }
+    match cfg {
+        Some(cfg) => cfg.verbosity_level(),
+        None => Ok(None),
+    }
+}
+
+/// the value following the `flag` in the command line args, if any.
+fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|idx| args.get(idx + 1).cloned())
+}
+
+fn dump_output(
+    cfg: &config::Config,
+    output_format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if output_format != exporters::timestamps::TIMESTAMPS_OUTPUT_FORMAT {
+        return Err(format!("unsupported output format [{}]", output_format).into());
+    }
+    let datapoints = generate(cfg)?;
+    let (export_from, export_to) = exporters::export_range(cfg)?;
+    let datapoints = exporters::filter_export_range(&datapoints, export_from, export_to);
+    let timestamp_format = cfg
+        .timestamp_format()
+        .clone()
+        .unwrap_or("%Y-%m-%dT%H:%M:%S%.f%:z".to_string());
+    let lines =
+        exporters::timestamps::TimestampsExporter::new(&timestamp_format, cfg.timezone_or_utc()?)
+            .export(&datapoints, &mut std::io::stdout().lock())?;
+    tracing::info!("{} timestamps written", lines);
     Ok(())
 }
@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::augmentation::{canonical_model_name, generation_seed, total_rows, DataPoint};
+use crate::config::Config;
+
+pub const MANIFEST_FILENAME: &str = "manifest.json";
+
+/// The metadata of a generation run; for auditing what was generated and how to reproduce it.
+#[derive(Debug, Serialize)]
+pub struct RunManifest {
+    pub crate_version: String,
+    /// the seed actually used (even if randomly picked); re-run with it to reproduce the datapoints.
+    pub seed: u64,
+    pub total_rows: u64,
+    /// the actual time range (rfc3339) of the datapoints; None if no datapoints were generated.
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub distribution_by: Option<String>,
+    /// the settings of the `distribution_by` model; null means the model's default.
+    pub model_parameters: Value,
+    /// the resolved (back-filled) config.
+    pub config: Value,
+}
+
+impl RunManifest {
+    /// Describe the latest generation (on the current thread) of the `datapoints` with `cfg`.
+    pub fn new(cfg: &Config, datapoints: &[DataPoint]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut seed = generation_seed();
+        if let Some(configured_seed) = cfg.seed() {
+            seed = *configured_seed;
+        }
+        Ok(RunManifest {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            seed,
+            total_rows: total_rows(datapoints)?,
+            start_time: datapoints
+                .iter()
+                .map(|d| *d.timestamp())
+                .min()
+                .map(|t| t.to_rfc3339()),
+            end_time: datapoints
+                .iter()
+                .map(|d| *d.timestamp())
+                .max()
+                .map(|t| t.to_rfc3339()),
+            distribution_by: cfg.distribution_by().as_deref().map(canonical_model_name),
+            model_parameters: model_parameters(cfg),
+            config: serde_json::to_value(cfg)?,
+        })
+    }
+}
+
+/// Writes the `RunManifest` as `manifest.json` into the given folder; invoked after generation.
+pub struct ManifestWriter {
+    path: PathBuf,
+}
+
+impl ManifestWriter {
+    pub fn new(path: &str) -> Self {
+        ManifestWriter {
+            path: Path::new(path).join(MANIFEST_FILENAME),
+        }
+    }
+
+    /// # Returns
+    /// The manifest written.
+    ///
+    /// # Errors
+    ///
+    /// If the folder cannot be created or the manifest cannot be written.
+    ///
+    pub fn write(
+        &self,
+        cfg: &Config,
+        datapoints: &[DataPoint],
+    ) -> Result<RunManifest, Box<dyn std::error::Error>> {
+        let manifest = RunManifest::new(cfg, datapoints)?;
+        if let Some(folder) = self.path.parent() {
+            std::fs::create_dir_all(folder)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&manifest)?).map_err(|e| {
+            format!(
+                "failed to write the manifest [{}]: {}",
+                self.path.display(),
+                e
+            )
+        })?;
+        tracing::info!("run manifest written to [{}]", self.path.display());
+        Ok(manifest)
+    }
+}
+
+fn model_parameters(cfg: &Config) -> Value {
+    let model = canonical_model_name(cfg.distribution_by().as_deref().unwrap_or_default());
+    match model.as_str() {
+        "even" => json!({ "rounding": cfg.rounding() }),
+        "sparse_fill" => json!({
+            "sparse_min_zones": cfg.sparse_min_zones(),
+            "sparse_max_zones": cfg.sparse_max_zones(),
+            "sparse_generation_factor": cfg.sparse_generation_factor(),
+            "fill_gaps": cfg.fill_gaps(),
+            "sparse_placement": cfg.sparse_placement(),
+        }),
+        "bimodal" => json!({
+            "peak1": cfg.peak1(),
+            "peak2": cfg.peak2(),
+            "peak1_ratio": cfg.peak1_ratio(),
+        }),
+        "decay" => json!({ "decay_lambda": cfg.decay_lambda() }),
+        "step" => json!({ "steps": cfg.steps() }),
+        "replay_csv" => json!({ "profile_path": cfg.profile_path() }),
+        "weights" => json!({ "weights": cfg.weights() }),
+        _ => json!({}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::augmentation::generate_datapoints;
+    use crate::config::generation_test_config;
+
+    #[test]
+    fn test_write_manifest() {
+        let mut cfg = generation_test_config("sparse_fill", 10000, "10m");
+        cfg.set_sparse_min_zones(Some(4));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let manifest = ManifestWriter::new(folder.path().to_str().unwrap())
+            .write(&cfg, &datapoints)
+            .unwrap();
+        assert_eq!(manifest.total_rows, total_rows(&datapoints).unwrap());
+        // no configured seed; the randomly picked one is recorded
+        assert_eq!(manifest.seed, generation_seed());
+
+        let written: Value = serde_json::from_str(
+            std::fs::read_to_string(folder.path().join(MANIFEST_FILENAME))
+                .unwrap()
+                .as_str(),
+        )
+        .unwrap();
+        assert_eq!(written["total_rows"], json!(10000));
+        assert_eq!(written["seed"], json!(manifest.seed));
+        assert_eq!(written["distribution_by"], json!("sparse_fill"));
+        assert_eq!(written["model_parameters"]["sparse_min_zones"], json!(4));
+        assert_eq!(written["config"]["number_of_entries"], json!(10000));
+        assert_eq!(written["start_time"], json!(manifest.start_time));
+        assert_eq!(written["crate_version"], json!(env!("CARGO_PKG_VERSION")));
+
+        // the same seed reproduces the datapoints
+        cfg.set_seed(Some(manifest.seed));
+        let reproduced = generate_datapoints(&cfg).unwrap();
+        assert_eq!(datapoints.len(), reproduced.len());
+        for (datapoint, reproduced) in datapoints.iter().zip(reproduced.iter()) {
+            assert_eq!(datapoint.timestamp(), reproduced.timestamp());
+            assert_eq!(datapoint.rows_to_add(), reproduced.rows_to_add());
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::augmentation::{
+    canonical_model_name, datapoint_stats, generation_seed, generation_window_in_seconds,
+    histogram_bucketed, scale_entries, total_rows, DataPoint, DistributionStats, Unit,
+};
+use crate::config::Config;
+use crate::exporters::ExporterRun;
+
+// the default number of bars of the histogram.
+const DEFAULT_HISTOGRAM_BARS: u32 = 20;
+// the width (in characters) of the longest bar.
+const HISTOGRAM_WIDTH: u64 = 40;
+
+/// A concise human-readable summary of a run (generation + export); logged at the end of the run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub distribution_by: String,
+    /// the seed actually used (even if randomly picked).
+    pub seed: u64,
+    /// None if the total was derived (e.g. from the steps of the step model).
+    pub entries_requested: Option<u32>,
+    /// excluding the warmup rows.
+    pub entries_produced: u64,
+    /// the rows of the warmup slots (see `warmup_window`) of every window, as tagged on the
+    /// datapoints (hence only the ones within the export range); 0 without a warmup.
+    pub warmup_rows: u64,
+    /// what the entries (rows) stand for; labels the report.
+    pub unit: Unit,
+    /// 1 slot per second of the `generation_duration` (or per slot width with `max_slots`); or the `slot_count`.
+    pub slots: i64,
+    pub non_empty_slots: usize,
+    /// the per-slot rows statistics.
+    pub stats: DistributionStats,
+    /// the actual time range of the datapoints; None if no datapoints were generated.
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    /// the rows per bar of equal time spans (see `histogram_bucketed`); empty = no histogram.
+    pub histogram: Vec<u64>,
+    pub exporters: Vec<ExporterRun>,
+    /// the run was interrupted (e.g. SIGINT); the exporters may have exported only part of the rows.
+    pub interrupted: bool,
+}
+
+impl RunReport {
+    /// Report the latest generation (on the current thread) of the `datapoints` with `cfg`;
+    /// the exporter runs are added afterwards (see `set_exporters`).
+    pub fn new(cfg: &Config, datapoints: &[DataPoint]) -> Result<Self, Box<dyn std::error::Error>> {
+        let slots = match (cfg.generation_duration(), cfg.slot_count()) {
+            (None, None) => 0,
+            _ => {
+                let (seconds, slot_width) = generation_window_in_seconds(cfg)?;
+                (seconds + slot_width - 1) / slot_width
+                    + cfg.end_inclusive().unwrap_or(false) as i64
+            }
+        };
+        let warmup_rows = datapoints
+            .iter()
+            .filter(|datapoint| *datapoint.warmup())
+            .map(|datapoint| (*datapoint.rows_to_add()).max(0) as u64)
+            .sum::<u64>();
+        Ok(RunReport {
+            // the canonical model name; not the alias (if any) configured
+            distribution_by: canonical_model_name(
+                cfg.distribution_by().as_deref().unwrap_or_default(),
+            ),
+            seed: cfg.seed().unwrap_or_else(generation_seed),
+            // as scaled (if so)
+            entries_requested: cfg
+                .number_of_entries()
+                .map(|entries| scale_entries(entries, *cfg.scale()))
+                .transpose()?,
+            entries_produced: total_rows(datapoints)?.saturating_sub(warmup_rows),
+            warmup_rows,
+            unit: Unit::from_config(cfg)?,
+            slots,
+            non_empty_slots: datapoints
+                .iter()
+                .filter(|datapoint| *datapoint.rows_to_add() > 0)
+                .count(),
+            stats: datapoint_stats(datapoints),
+            start_time: datapoints.iter().map(|d| *d.timestamp()).min(),
+            end_time: datapoints.iter().map(|d| *d.timestamp()).max(),
+            histogram: histogram_bucketed(
+                datapoints,
+                cfg.histogram_bars().unwrap_or(DEFAULT_HISTOGRAM_BARS) as usize,
+            ),
+            exporters: vec![],
+            interrupted: false,
+        })
+    }
+
+    pub fn set_exporters(&mut self, exporters: Vec<ExporterRun>) {
+        self.exporters = exporters;
+    }
+
+    pub fn set_interrupted(&mut self, interrupted: bool) {
+        self.interrupted = interrupted;
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.interrupted {
+            true => writeln!(f, "run report (partial; interrupted)")?,
+            false => writeln!(f, "run report")?,
+        }
+        writeln!(f, "  model      : {}", self.distribution_by)?;
+        writeln!(f, "  seed       : {}", self.seed)?;
+        let (total_label, slot_label) = match self.unit {
+            Unit::Events => ("entries", "rows/slot"),
+            Unit::Bytes => ("bytes", "bytes/slot"),
+        };
+        match self.entries_requested {
+            Some(requested) => writeln!(
+                f,
+                "  {:<11}: {} requested, {} produced",
+                total_label, requested, self.entries_produced
+            )?,
+            None => writeln!(
+                f,
+                "  {:<11}: {} produced",
+                total_label, self.entries_produced
+            )?,
+        }
+        if self.warmup_rows > 0 {
+            writeln!(
+                f,
+                "  warmup     : {} {}",
+                self.warmup_rows,
+                self.unit.label()
+            )?;
+        }
+        writeln!(
+            f,
+            "  slots      : {} ({} non-empty)",
+            self.slots, self.non_empty_slots
+        )?;
+        writeln!(
+            f,
+            "  {:<11}: min {}, max {}, mean {:.1}, median {:.1}, p95 {:.1}, stddev {:.1}",
+            slot_label,
+            self.stats.min,
+            self.stats.max,
+            self.stats.mean,
+            self.stats.median,
+            self.stats.p95,
+            self.stats.std_dev
+        )?;
+        match (self.start_time, self.end_time) {
+            (Some(start_time), Some(end_time)) => writeln!(
+                f,
+                "  time range : {} .. {}",
+                start_time.to_rfc3339(),
+                end_time.to_rfc3339()
+            )?,
+            _ => writeln!(f, "  time range : -")?,
+        }
+        if let (Some(start_time), Some(end_time), false) =
+            (self.start_time, self.end_time, self.histogram.is_empty())
+        {
+            writeln!(f, "  histogram  :")?;
+            let highest = self
+                .histogram
+                .iter()
+                .max()
+                .copied()
+                .unwrap_or_default()
+                .max(1);
+            // the same spans as `histogram_bucketed`; each bar labeled by its start
+            let span = (end_time - start_time + chrono::Duration::seconds(1)).num_milliseconds();
+            let bars = self.histogram.len() as i64;
+            for (idx, count) in self.histogram.iter().enumerate() {
+                let bar_start =
+                    start_time + chrono::Duration::milliseconds(span * idx as i64 / bars);
+                writeln!(
+                    f,
+                    "    {} | {:<width$} {}",
+                    bar_start.to_rfc3339(),
+                    "#".repeat((count * HISTOGRAM_WIDTH / highest) as usize),
+                    count,
+                    width = HISTOGRAM_WIDTH as usize
+                )?;
+            }
+        }
+        if self.exporters.is_empty() {
+            write!(f, "  exporters  : -")?;
+        }
+        for (idx, exporter) in self.exporters.iter().enumerate() {
+            write!(
+                f,
+                "  exporter   : [{}] {} {} in {:?}",
+                exporter.name,
+                exporter.rows_exported,
+                self.unit.label(),
+                exporter.elapsed
+            )?;
+            if idx < self.exporters.len() - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::augmentation::generate_datapoints;
+    use crate::config::generation_test_config;
+    use crate::exporters::{export_range, filter_export_range};
+
+    #[test]
+    fn test_run_report() {
+        // reported by the canonical name
+        let mut cfg = generation_test_config("Sparse_Fill", 10000, "10m");
+        cfg.set_seed(Some(42));
+        cfg.set_fill_gaps(Some(true));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+
+        let mut report = RunReport::new(&cfg, &datapoints).unwrap();
+        report.set_exporters(vec![
+            ExporterRun {
+                name: "stdout".to_string(),
+                rows_exported: 10000,
+                elapsed: Duration::from_millis(12),
+            },
+            ExporterRun {
+                name: "file".to_string(),
+                rows_exported: 10000,
+                elapsed: Duration::from_millis(340),
+            },
+        ]);
+        assert_eq!(report.slots, 600);
+        // 20 bars by default
+        assert_eq!(report.histogram.len(), 20);
+        assert_eq!(report.histogram.iter().sum::<u64>(), 10000);
+        assert!(report.non_empty_slots > 0 && report.non_empty_slots < 600);
+
+        let output = report.to_string();
+        for expected in [
+            "model      : sparse_fill".to_string(),
+            "seed       : 42".to_string(),
+            "entries    : 10000 requested, 10000 produced".to_string(),
+            format!("slots      : 600 ({} non-empty)", report.non_empty_slots),
+            format!(
+                "rows/slot  : min 0, max {}, mean {:.1}",
+                report.stats.max, report.stats.mean
+            ),
+            "time range : 2022-01-01T00:00:00+00:00 .. 2022-01-01T00:09:59+00:00".to_string(),
+            "exporter   : [stdout] 10000 rows in 12ms".to_string(),
+            "exporter   : [file] 10000 rows in 340ms".to_string(),
+            "  histogram  :\n    2022-01-01T00:00:00+00:00 | ".to_string(),
+            format!(
+                "    2022-01-01T00:09:30+00:00 | {:<40} {}\n",
+                "#".repeat(
+                    (report.histogram[19] * 40 / report.histogram.iter().max().unwrap()) as usize
+                ),
+                report.histogram[19]
+            ),
+        ] {
+            assert!(
+                output.contains(expected.as_str()),
+                "{}\n{}",
+                expected,
+                output
+            );
+        }
+
+        report.set_interrupted(true);
+        assert!(report
+            .to_string()
+            .starts_with("run report (partial; interrupted)\n"));
+    }
+
+    #[test]
+    fn test_run_report_unit() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_unit(Some("bytes".to_string()));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+
+        let mut report = RunReport::new(&cfg, &datapoints).unwrap();
+        report.set_exporters(vec![ExporterRun {
+            name: "stdout".to_string(),
+            rows_exported: 10000,
+            elapsed: Duration::from_millis(12),
+        }]);
+        assert_eq!(report.unit, Unit::Bytes);
+        let output = report.to_string();
+        for expected in [
+            "bytes      : 10000 requested, 10000 produced",
+            "bytes/slot : min ",
+            "exporter   : [stdout] 10000 bytes in 12ms",
+        ] {
+            assert!(output.contains(expected), "{}\n{}", expected, output);
+        }
+        assert!(!output.contains("rows"), "{}", output);
+
+        // the warmup rows are reported apart
+        cfg.set_unit(None);
+        cfg.set_warmup_duration(Some("1m".to_string()));
+        cfg.set_warmup_rate(Some(3));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        let report = RunReport::new(&cfg, &datapoints).unwrap();
+        assert_eq!(report.warmup_rows, 180);
+        assert_eq!(report.entries_produced, 10000);
+        let output = report.to_string();
+        assert!(
+            output.contains(
+                "entries    : 10000 requested, 10000 produced\n  warmup     : 180 rows\n"
+            ),
+            "{}",
+            output
+        );
+
+        // the warmup cut by the export range; the rows of the warmup slots exported only
+        cfg.set_export_from(Some("2022-01-01T00:00:30.000+00:00".to_string()));
+        let (export_from, export_to) = export_range(&cfg).unwrap();
+        let exported = filter_export_range(&datapoints, export_from, export_to);
+        let report = RunReport::new(&cfg, &exported).unwrap();
+        assert_eq!(report.warmup_rows, 30 * 3);
+        assert_eq!(report.entries_produced, 10000);
+        cfg.set_export_from(Some("2022-01-01T00:02:00.000+00:00".to_string()));
+        let (export_from, export_to) = export_range(&cfg).unwrap();
+        let exported = filter_export_range(&datapoints, export_from, export_to);
+        let report = RunReport::new(&cfg, &exported).unwrap();
+        assert_eq!(report.warmup_rows, 0);
+        assert_eq!(report.entries_produced, total_rows(&exported).unwrap());
+        cfg.set_export_from(None);
+
+        cfg.set_unit(Some("packets".to_string()));
+        assert_eq!(
+            RunReport::new(&cfg, &datapoints).err().unwrap().to_string(),
+            "unknown unit [packets]; expecting \"events\" or \"bytes\""
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::augmentation::{
+    check_invariants, generate_datapoints, generate_time_range, DistributionModelRegistry,
+};
+use crate::config::{load_config, Config, ConfigStep};
+
+const SELFCHECK_SEED: u64 = 20220101;
+const SELFCHECK_NUMBER_OF_ENTRIES: u32 = 10000;
+const SELFCHECK_GENERATION_DURATION: &str = "10m";
+const SELFCHECK_START_TIMESTAMP: &str = "2022-01-01T00:00:00.000+00:00";
+/// the traffic profile of the replay_csv model if none is configured; per-minute counts.
+const SELFCHECK_PROFILE: &str = "timestamp,count\n\
+    2022-01-01T00:00:00Z,10\n\
+    2022-01-01T00:01:00Z,40\n\
+    2022-01-01T00:02:00Z,90\n\
+    2022-01-01T00:03:00Z,40\n\
+    2022-01-01T00:04:00Z,10\n";
+
+/// Generate every built-in distribution model through the default config (with a fixed seed)
+/// and check the core invariants of the datapoints.
+///
+/// # Returns
+/// The number of models checked.
+///
+/// # Errors
+///
+/// If the config fails to load, or any model fails to generate / violates an invariant;
+/// every model is still checked and all violations are reported.
+///
+pub fn run_selfcheck() -> Result<usize, Box<dyn std::error::Error>> {
+    let cfg = load_config(
+        "config/default".to_string(),
+        "config/default".to_string(),
+        "config.toml".to_string(),
+        "config.toml".to_string(),
+    )?;
+    selfcheck(cfg)
+}
+
+fn selfcheck(mut cfg: Config) -> Result<usize, Box<dyn std::error::Error>> {
+    // a small deterministic dataset; the rest (e.g. model settings) as configured
+    cfg.set_seed(Some(SELFCHECK_SEED));
+    cfg.set_number_of_entries(Some(SELFCHECK_NUMBER_OF_ENTRIES));
+    cfg.set_generation_duration(Some(SELFCHECK_GENERATION_DURATION.to_string()));
+    cfg.set_use_now_as_timestamp(Some(false));
+    cfg.set_start_timestamp(Some(SELFCHECK_START_TIMESTAMP.to_string()));
+    // jitter would break the timestamp ordering on purpose
+    cfg.set_timestamp_jitter(None);
+    // the step model requires steps (9000 rows; within the number_of_entries)
+    if cfg.steps().is_none() {
+        cfg.set_steps(Some(vec![
+            ConfigStep::new("0s", 10),
+            ConfigStep::new("5m", 20),
+        ]));
+    }
+    // the replay_csv model requires a profile
+    if cfg.profile_path().is_none() {
+        let profile_path = std::env::temp_dir().join("otel_broccoli_selfcheck_profile.csv");
+        std::fs::write(&profile_path, SELFCHECK_PROFILE)?;
+        cfg.set_profile_path(Some(profile_path.display().to_string()));
+    }
+    // the weights model requires weights
+    if cfg.weights().is_none() {
+        cfg.set_weights(Some(vec![1.0, 2.0, 5.0, 2.0, 1.0]));
+    }
+    let (start_time, end_time) = generate_time_range(&cfg)?;
+
+    let registry = DistributionModelRegistry::with_builtin_models();
+    let models = registry.names();
+    let mut violations: Vec<String> = vec![];
+    for model in models.iter() {
+        cfg.set_distribution_by(Some(model.to_string()));
+        let result = generate_datapoints(&cfg)
+            .map_err(|e| e.to_string())
+            .and_then(|datapoints| {
+                check_invariants(
+                    &datapoints,
+                    SELFCHECK_NUMBER_OF_ENTRIES as u64,
+                    start_time,
+                    end_time,
+                )
+            });
+        match result {
+            Ok(_) => tracing::info!("selfcheck [{}] passed", model),
+            Err(e) => {
+                tracing::error!("selfcheck [{}] failed: {}", model, e);
+                violations.push(format!("[{}] {}", model, e));
+            }
+        }
+    }
+    if !violations.is_empty() {
+        return Err(format!(
+            "{} of {} model(s) failed: {}",
+            violations.len(),
+            models.len(),
+            violations.join("; ")
+        )
+        .into());
+    }
+    Ok(models.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_init;
+
+    #[test]
+    fn test_selfcheck() {
+        // init loggers
+        app_init("./config/default/loggers.toml".to_string()).unwrap();
+
+        let models = DistributionModelRegistry::with_builtin_models()
+            .names()
+            .len();
+        assert_eq!(run_selfcheck().unwrap(), models);
+
+        // an impossible config surfaces as a violation
+        let mut cfg = Config::new();
+        cfg.set_timestamp_format(Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()));
+        cfg.set_sparse_min_zones(Some(0));
+        let e = selfcheck(cfg).err().unwrap().to_string();
+        assert!(e.starts_with("1 of "), "{}", e);
+        assert!(e.contains("[sparse_fill] sparse_min_zones [0]"), "{}", e);
+    }
+}
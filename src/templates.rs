@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::augmentation::random_range;
+use crate::config::Config;
+
+/// A parsed template; literal text and `{name}` placeholders.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// The row field templates (e.g. `message = "GET /api/{path} -> {status}"`); every rendering
+/// substitutes each `{name}` placeholder with a random pick from the `name` value list.
+///
+/// A placeholder without a (non-empty) value list is left literal; warned once when parsed.
+/// The picks are drawn from the generation rng, so a `seed` reproduces them too.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowTemplates {
+    /// (field, template) pairs in field order.
+    templates: Vec<(String, Vec<Segment>)>,
+    values: HashMap<String, Vec<String>>,
+}
+
+impl RowTemplates {
+    pub fn new(templates: &HashMap<String, String>, values: &HashMap<String, Vec<String>>) -> Self {
+        let values: HashMap<String, Vec<String>> = values
+            .iter()
+            .filter(|(_, pool)| !pool.is_empty())
+            .map(|(name, pool)| (name.clone(), pool.clone()))
+            .collect();
+        // sorted fields; a HashMap's iteration order would break the reproducibility
+        let mut fields: Vec<&String> = templates.keys().collect();
+        fields.sort();
+
+        let mut warned: HashSet<String> = HashSet::new();
+        let templates = fields
+            .into_iter()
+            .map(|field| {
+                let segments = parse_template(&templates[field])
+                    .into_iter()
+                    .map(|segment| match segment {
+                        Segment::Placeholder(name) if !values.contains_key(&name) => {
+                            if warned.insert(name.clone()) {
+                                tracing::warn!(
+                                    "unknown template placeholder [{{{}}}]; left as is",
+                                    name
+                                );
+                            }
+                            Segment::Literal(format!("{{{}}}", name))
+                        }
+                        segment => segment,
+                    })
+                    .collect();
+                (field.clone(), segments)
+            })
+            .collect();
+        RowTemplates { templates, values }
+    }
+
+    /// The configured `templates` with their `template_values`; no templates if unset.
+    pub fn from_config(cfg: &Config) -> Self {
+        match cfg.templates() {
+            Some(templates) => RowTemplates::new(
+                templates,
+                &cfg.template_values().clone().unwrap_or_default(),
+            ),
+            None => RowTemplates::default(),
+        }
+    }
+
+    /// The templated fields in field order.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.templates.iter().map(|(field, _)| field.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// Render every template once; (field, value) pairs in field order.
+    pub fn render(&self) -> Vec<(String, String)> {
+        self.templates
+            .iter()
+            .map(|(field, segments)| {
+                let mut value = String::new();
+                for segment in segments {
+                    match segment {
+                        Segment::Literal(text) => value.push_str(text),
+                        Segment::Placeholder(name) => {
+                            let pool = &self.values[name];
+                            value.push_str(&pool[random_range(0..pool.len())]);
+                        }
+                    }
+                }
+                (field.clone(), value)
+            })
+            .collect()
+    }
+}
+
+/// Split the template into literals and `{name}` placeholders; a brace without its pair (or an
+/// empty `{}`) stays literal.
+fn parse_template(template: &str) -> Vec<Segment> {
+    let mut segments = vec![];
+    let mut literal = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        literal.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find(['{', '}']) {
+            Some(close) if after[close..].starts_with('}') && close > 0 => {
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Placeholder(after[..close].to_string()));
+                rest = &after[close + 1..];
+            }
+            _ => {
+                literal.push('{');
+                rest = after;
+            }
+        }
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::augmentation::seed_generation_rng;
+
+    #[test]
+    fn test_parse_template() {
+        assert_eq!(
+            parse_template("GET /api/{path} -> {status}"),
+            vec![
+                Segment::Literal("GET /api/".to_string()),
+                Segment::Placeholder("path".to_string()),
+                Segment::Literal(" -> ".to_string()),
+                Segment::Placeholder("status".to_string()),
+            ]
+        );
+        // unpaired / empty braces stay literal
+        assert_eq!(
+            parse_template("{a{b} {} }{"),
+            vec![
+                Segment::Literal("{a".to_string()),
+                Segment::Placeholder("b".to_string()),
+                Segment::Literal(" {} }{".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_templates() {
+        seed_generation_rng(Some(42));
+        let templates = HashMap::from([
+            (
+                "message".to_string(),
+                "GET /api/{path} -> {status} ({unknown})".to_string(),
+            ),
+            ("user".to_string(), "{user}".to_string()),
+        ]);
+        let values = HashMap::from([
+            (
+                "path".to_string(),
+                vec!["users".to_string(), "orders".to_string()],
+            ),
+            (
+                "status".to_string(),
+                vec!["200".to_string(), "404".to_string()],
+            ),
+            ("user".to_string(), vec!["alice".to_string()]),
+        ]);
+        let row_templates = RowTemplates::new(&templates, &values);
+
+        for _ in 0..100 {
+            let rendered = row_templates.render();
+            assert_eq!(rendered.len(), 2);
+            assert_eq!(rendered[0].0, "message");
+            let message = rendered[0].1.as_str();
+            let rest = message.strip_prefix("GET /api/").unwrap();
+            let (path, rest) = rest.split_once(" -> ").unwrap();
+            let (status, rest) = rest.split_once(' ').unwrap();
+            assert!(values["path"].iter().any(|v| v == path), "{}", message);
+            assert!(values["status"].iter().any(|v| v == status), "{}", message);
+            // the unknown placeholder is left literal
+            assert_eq!(rest, "({unknown})");
+            assert_eq!(rendered[1], ("user".to_string(), "alice".to_string()));
+        }
+        assert_eq!(
+            row_templates.fields().collect::<Vec<&str>>(),
+            vec!["message", "user"]
+        );
+        assert!(RowTemplates::default().render().is_empty());
+        assert!(RowTemplates::default().is_empty());
+    }
+}
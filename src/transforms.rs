@@ -0,0 +1,207 @@
+use crate::augmentation::{distribute_exact, random_range, DataPoint};
+use crate::config::Config;
+
+const DEFAULT_SPIKE_FACTOR: f64 = 5.0;
+
+/// A post-generation transform; adjusts the datapoints after the distribution model filled them
+/// (e.g. adds a known anomaly) without forking the models.
+pub trait Transform {
+    /// adjust the datapoints in place.
+    fn apply(&self, datapoints: &mut Vec<DataPoint>) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// Multiplies the rows of a random (non-empty) slot by `factor`; the extra rows are taken away
+/// from the other slots proportionally to their rows, hence the total stays the same.
+///
+/// The spike is capped by the rows available in the other slots (and the per-slot range).
+pub struct SpikeTransform {
+    factor: f64,
+}
+
+impl SpikeTransform {
+    pub fn new(factor: f64) -> Self {
+        SpikeTransform { factor }
+    }
+}
+
+impl Transform for SpikeTransform {
+    fn apply(&self, datapoints: &mut Vec<DataPoint>) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.factor.is_finite() || self.factor < 1.0 {
+            return Err(format!("spike_factor must be >= 1, got [{}]", self.factor).into());
+        }
+        let non_empty_slots: Vec<usize> = (0..datapoints.len())
+            .filter(|idx| *datapoints[*idx].rows_to_add() > 0)
+            .collect();
+        if non_empty_slots.is_empty() {
+            return Ok(());
+        }
+        let spike_idx = non_empty_slots[random_range(0..non_empty_slots.len())];
+        let rows = *datapoints[spike_idx].rows_to_add() as f64;
+
+        // the rows of the other slots, which the extra rows are taken away from
+        let weights: Vec<f64> = datapoints
+            .iter()
+            .enumerate()
+            .map(|(idx, datapoint)| match idx == spike_idx {
+                true => 0.0,
+                false => (*datapoint.rows_to_add()).max(0) as f64,
+            })
+            .collect();
+        let others: f64 = weights.iter().sum();
+        let extra = (rows * self.factor).min(i16::MAX as f64).round() - rows;
+        let extra = extra.min(others) as u32;
+        if extra == 0 {
+            return Ok(());
+        }
+
+        let taken = distribute_exact(extra, &weights);
+        for (datapoint, taken) in datapoints.iter_mut().zip(taken) {
+            if taken > 0 {
+                *datapoint = DataPoint::new(
+                    *datapoint.timestamp(),
+                    *datapoint.rows_to_add() - taken as i16,
+                );
+            }
+        }
+        let spike = &datapoints[spike_idx];
+        datapoints[spike_idx] = DataPoint::new(*spike.timestamp(), (rows as u32 + extra) as i16);
+        Ok(())
+    }
+}
+
+/// The registry of the transforms by name; the plugin point of the post-generation transforms.
+#[derive(Default)]
+pub struct TransformRegistry {
+    // a Vec instead of a HashMap to keep the registration order for listing
+    transforms: Vec<(String, Box<dyn Transform>)>,
+}
+
+impl TransformRegistry {
+    /// an empty registry.
+    pub fn new() -> Self {
+        TransformRegistry { transforms: vec![] }
+    }
+
+    /// a registry with all the built-in transforms registered (with their settings from the config).
+    pub fn with_builtin_transforms(cfg: &Config) -> Self {
+        let mut registry = TransformRegistry::new();
+        registry.register(
+            "spike",
+            Box::new(SpikeTransform::new(
+                cfg.spike_factor().unwrap_or(DEFAULT_SPIKE_FACTOR),
+            )),
+        );
+        registry
+    }
+
+    /// register a transform by name (case-insensitive); an existing transform with the same name is replaced.
+    pub fn register(&mut self, name: &str, transform: Box<dyn Transform>) {
+        let name = name.to_lowercase();
+        match self.transforms.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = transform,
+            None => self.transforms.push((name, transform)),
+        }
+    }
+
+    /// get the transform by name (case-insensitive).
+    pub fn get(&self, name: &str) -> Option<&dyn Transform> {
+        let name = name.to_lowercase();
+        self.transforms
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, transform)| transform.as_ref())
+    }
+
+    /// Apply the named transforms in order.
+    ///
+    /// # Errors
+    ///
+    /// If a transform is not registered (nothing is applied then) or fails.
+    ///
+    pub fn apply_all(
+        &self,
+        names: &[String],
+        datapoints: &mut Vec<DataPoint>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut transforms = vec![];
+        for name in names {
+            transforms.push(
+                self.get(name)
+                    .ok_or(format!("unknown transform [{}]", name))?,
+            );
+        }
+        for transform in transforms {
+            transform.apply(datapoints)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::augmentation::{generate_datapoints, seed_generation_rng, total_rows};
+    use crate::config::generation_test_config;
+
+    #[test]
+    fn test_spike_transform() {
+        let mut cfg = generation_test_config("even", 10000, "10m");
+        cfg.set_seed(Some(42));
+        let baseline = generate_datapoints(&cfg).unwrap();
+
+        cfg.set_transforms(Some(vec!["spike".to_string()]));
+        cfg.set_spike_factor(Some(4.0));
+        let datapoints = generate_datapoints(&cfg).unwrap();
+        // the total is preserved
+        assert_eq!(total_rows(&datapoints).unwrap(), 10000);
+        assert_eq!(datapoints.len(), baseline.len());
+
+        // exactly 1 slot spiked by the factor; the others only lost rows
+        let mut spikes = vec![];
+        for (before, after) in baseline.iter().zip(datapoints.iter()) {
+            assert_eq!(before.timestamp(), after.timestamp());
+            if after.rows_to_add() > before.rows_to_add() {
+                spikes.push((*before.rows_to_add(), *after.rows_to_add()));
+            }
+        }
+        assert_eq!(spikes.len(), 1);
+        let (before, after) = spikes[0];
+        assert_eq!(after, before * 4);
+
+        cfg.set_transforms(Some(vec!["spike".to_string(), "dip".to_string()]));
+        assert_eq!(
+            generate_datapoints(&cfg).err().unwrap().to_string(),
+            "unknown transform [dip]"
+        );
+    }
+
+    #[test]
+    fn test_spike_transform_capped() {
+        // not enough rows elsewhere; the spike takes all of them
+        let start_time: DateTime<Utc> = "2022-01-01T00:00:00.000Z".parse().unwrap();
+        let mut datapoints = vec![
+            DataPoint::new(start_time, 10),
+            DataPoint::new(start_time + Duration::seconds(1), 0),
+        ];
+        seed_generation_rng(Some(1));
+        SpikeTransform::new(100.0).apply(&mut datapoints).unwrap();
+        assert_eq!(*datapoints[0].rows_to_add(), 10);
+        assert_eq!(*datapoints[1].rows_to_add(), 0);
+
+        datapoints.push(DataPoint::new(start_time + Duration::seconds(2), 3));
+        SpikeTransform::new(100.0).apply(&mut datapoints).unwrap();
+        assert_eq!(total_rows(&datapoints).unwrap(), 13);
+        assert!(datapoints.iter().any(|d| *d.rows_to_add() == 13));
+
+        assert_eq!(
+            SpikeTransform::new(0.5)
+                .apply(&mut datapoints)
+                .err()
+                .unwrap()
+                .to_string(),
+            "spike_factor must be >= 1, got [0.5]"
+        );
+    }
+}